@@ -13,13 +13,19 @@
 // limitations under the License.
 
 use std::env;
+use std::fs;
+use std::io::Error;
+use std::io::ErrorKind;
 use std::io::Result;
 
+use opendal::layers::AclLayer;
 use opendal::Operator;
 use opendal::Scheme;
 
 pub static OAY_ADDR: &str = "OAY_ADDR";
 pub static OAY_BACKEND_TYPE: &str = "OAY_BACKEND_TYPE";
+/// Path to the acl config file in json, refer to [`AclLayer`] for the format.
+pub static OAY_ACL: &str = "OAY_ACL";
 
 pub fn get_oay_addr() -> String {
     env::var(OAY_ADDR).unwrap_or_else(|_| "127.0.0.1:8080".to_string())
@@ -41,7 +47,13 @@ pub async fn get_oay_operator() -> Result<Operator> {
             .map(|k| (k.to_string(), v))
     });
 
-    let op = Operator::from_iter(scheme, envs)?;
+    let mut op = Operator::from_iter(scheme, envs)?;
+
+    if let Ok(path) = env::var(OAY_ACL) {
+        let acl: AclLayer = serde_json::from_slice(&fs::read(path)?)
+            .map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+        op = op.layer(acl);
+    }
 
     Ok(op)
 }
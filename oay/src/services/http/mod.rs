@@ -36,9 +36,8 @@ use futures::AsyncWriteExt;
 use futures::StreamExt;
 use log::error;
 use log::warn;
-use opendal::io_util::into_stream;
 use opendal::ops::BytesRange;
-use opendal::BytesReader;
+use opendal::BytesStreamer;
 use opendal::Operator;
 use percent_encoding::percent_decode;
 
@@ -79,7 +78,7 @@ impl Service {
 
         let meta = o.metadata().await?;
 
-        let (size, r) = if let Some(range) = req.headers().get(header::RANGE) {
+        let (size, s) = if let Some(range) = req.headers().get(header::RANGE) {
             let br = BytesRange::from_header_range(range.to_str().map_err(|e| {
                 Error::new(
                     ErrorKind::InvalidInput,
@@ -91,16 +90,16 @@ impl Service {
 
             (
                 range.size_hint().0 as u64,
-                Box::new(o.range_reader(range).await?) as BytesReader,
+                Box::new(o.range_stream(range).await?) as BytesStreamer,
             )
         } else {
             (
                 meta.content_length(),
-                Box::new(o.reader().await?) as BytesReader,
+                Box::new(o.stream().await?) as BytesStreamer,
             )
         };
 
-        Ok(HttpResponse::Ok().body(SizedStream::new(size, into_stream(r, 8 * 1024))))
+        Ok(HttpResponse::Ok().body(SizedStream::new(size, s)))
     }
 
     async fn put(&self, req: HttpRequest, mut body: web::Payload) -> Result<HttpResponse> {
@@ -21,6 +21,8 @@ use flagset::flags;
 use flagset::FlagSet;
 
 use crate::error::new_unsupported_object_error;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::multipart::ObjectPart;
 use crate::ops::OpAbortMultipart;
 use crate::ops::OpCompleteMultipart;
@@ -30,6 +32,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpRename;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -37,11 +40,15 @@ use crate::ops::Operation;
 use crate::ops::PresignedRequest;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirIterator;
 use crate::DirStreamer;
 use crate::ObjectMetadata;
 use crate::Scheme;
 
+/// Default capacity of chunks while converting reader into stream.
+const DEFAULT_STREAM_CAPACITY: usize = 64 * 1024;
+
 /// Underlying trait of all backends for implementors.
 ///
 /// # Note
@@ -57,8 +64,11 @@ use crate::Scheme;
 /// | [`create`][crate::Accessor::create] | - |
 /// | [`read`][crate::Accessor::read] | - |
 /// | [`write`][crate::Accessor::write] | - |
+/// | [`read_stream`][crate::Accessor::read_stream] | - |
+/// | [`write_stream`][crate::Accessor::write_stream] | - |
 /// | [`delete`][crate::Accessor::delete] | - |
 /// | [`list`][crate::Accessor::list] | - |
+/// | [`rename`][crate::Accessor::rename] | `Rename` |
 /// | [`presign`][crate::Accessor::presign] | `Presign` |
 /// | [`create_multipart`][crate::Accessor::create_multipart] | `Multipart` |
 /// | [`write_multipart`][crate::Accessor::write_multipart] | `Multipart` |
@@ -69,6 +79,7 @@ use crate::Scheme;
 /// | [`blocking_write`][crate::Accessor::blocking_write] | `Blocking` |
 /// | [`blocking_delete`][crate::Accessor::blocking_delete] | `Blocking` |
 /// | [`blocking_list`][crate::Accessor::blocking_list] | `Blocking` |
+/// | [`blocking_rename`][crate::Accessor::blocking_rename] | `Blocking`, `Rename` |
 ///
 /// - Path in args will all be normalized into the same style, services
 ///   should handle them based on services' requirement.
@@ -120,6 +131,37 @@ pub trait Accessor: Send + Sync + Debug {
         unimplemented!()
     }
 
+    /// Invoke the `read` operation on the specified path, returns a
+    /// [`BytesStreamer`][crate::BytesStreamer] if operate successful.
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check object mode.
+    /// - Services that read data in chunks (like HTTP based services) SHOULD
+    ///   implement this to return the underlying chunks without copying.
+    /// - The default implementation converts the reader returned by
+    ///   [`Accessor::read`] into a stream.
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        let r = self.read(args).await?;
+
+        Ok(Box::new(into_stream(r, DEFAULT_STREAM_CAPACITY)))
+    }
+
+    /// Invoke the `write` operation on the specified path with a
+    /// [`BytesStreamer`][crate::BytesStreamer], returns a written size if
+    /// operate successful.
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check object mode.
+    /// - Services that send data in chunks (like HTTP based services) SHOULD
+    ///   implement this to send chunks without copying.
+    /// - The default implementation converts the stream into a reader and
+    ///   calls [`Accessor::write`].
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.write(args, Box::new(into_reader(s))).await
+    }
+
     /// Invoke the `stat` operation on the specified path.
     ///
     /// # Behavior
@@ -152,6 +194,17 @@ pub trait Accessor: Send + Sync + Debug {
         unimplemented!()
     }
 
+    /// Invoke the `rename` operation to move an object to another path.
+    ///
+    /// # Behavior
+    ///
+    /// - Require capability: `Rename`
+    /// - Target object SHOULD be overwritten if exists.
+    /// - This API is optional, return [`std::io::ErrorKind::Unsupported`] if not supported.
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        return Err(new_unsupported_object_error(Operation::Rename, args.from()));
+    }
+
     /// Invoke the `presign` operation on the specified path.
     ///
     /// # Behavior
@@ -317,6 +370,20 @@ pub trait Accessor: Send + Sync + Debug {
             args.path(),
         ));
     }
+
+    /// Invoke the `blocking_rename` operation to move an object to another path.
+    ///
+    /// This operation is the blocking version of [`Accessor::rename`]
+    ///
+    /// # Behavior
+    ///
+    /// - Require capability: `Blocking`, `Rename`
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        return Err(new_unsupported_object_error(
+            Operation::BlockingRename,
+            args.from(),
+        ));
+    }
 }
 
 /// All functions in `Accessor` only requires `&self`, so it's safe to implement
@@ -336,6 +403,12 @@ impl<T: Accessor> Accessor for Arc<T> {
     async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
         self.as_ref().write(args, r).await
     }
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.as_ref().read_stream(args).await
+    }
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.as_ref().write_stream(args, s).await
+    }
     async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
         self.as_ref().stat(args).await
     }
@@ -345,6 +418,9 @@ impl<T: Accessor> Accessor for Arc<T> {
     async fn list(&self, args: &OpList) -> Result<DirStreamer> {
         self.as_ref().list(args).await
     }
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        self.as_ref().rename(args).await
+    }
 
     fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
         self.as_ref().presign(args)
@@ -381,6 +457,9 @@ impl<T: Accessor> Accessor for Arc<T> {
     fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
         self.as_ref().blocking_list(args)
     }
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        self.as_ref().blocking_rename(args)
+    }
 }
 
 /// Metadata for accessor, users can use this metadata to get information of underlying backend.
@@ -458,6 +537,11 @@ impl AccessorMetadata {
         self.capabilities.contains(AccessorCapability::Blocking)
     }
 
+    /// Check if current backend supports [`Accessor::rename`] or not.
+    pub fn can_rename(&self) -> bool {
+        self.capabilities.contains(AccessorCapability::Rename)
+    }
+
     /// Set this function to private so that users can't access capabilities
     /// directly.
     pub(crate) fn capabilities(&self) -> FlagSet<AccessorCapability> {
@@ -488,5 +572,7 @@ flags! {
         Multipart,
         /// Add this capability if service supports `blocking`
         Blocking,
+        /// Add this capability if service supports `rename`
+        Rename,
     }
 }
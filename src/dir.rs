@@ -173,6 +173,14 @@ impl DirEntry {
 
 // implement setters for DirEntry's metadata fields
 impl DirEntry {
+    /// record the accessor of `DirEntry`'s corresponding object
+    pub(crate) fn set_accessor(&mut self, acc: Arc<dyn Accessor>) {
+        self.acc = acc
+    }
+    /// record the path of `DirEntry`'s corresponding object
+    pub(crate) fn set_path(&mut self, path: &str) {
+        self.path = path.to_string()
+    }
     /// record the ETag of `DirEntry`'s corresponding object
     pub(crate) fn set_etag(&mut self, etag: &str) {
         self.etag = Some(etag.to_string())
@@ -36,6 +36,8 @@
 //! ```
 
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
 use std::io;
 
 use thiserror::Error;
@@ -64,28 +66,243 @@ impl BackendError {
     }
 }
 
+/// ObjectErrorKind is the semantic kind of [`ObjectError`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ObjectErrorKind {
+    /// OpenDAL doesn't know what happened, please check the source.
+    Unexpected,
+    /// Operation is not supported by underlying services.
+    Unsupported,
+    /// Object is not found.
+    ObjectNotFound,
+    /// Object already exists.
+    ObjectAlreadyExists,
+    /// Permission denied by underlying services.
+    PermissionDenied,
+    /// Requests are throttled by underlying services.
+    RateLimited,
+    /// Condition like `If-Match` doesn't match.
+    ConditionNotMatch,
+    /// Underlying services are not available for now.
+    ServiceUnavailable,
+    /// Storage quota is exceeded, returned by [`QuotaLayer`][crate::layers::QuotaLayer].
+    QuotaExceeded,
+}
+
+impl ObjectErrorKind {
+    /// Errors of these kinds are expected to be fixed by retrying.
+    fn is_temporary(&self) -> bool {
+        matches!(
+            self,
+            ObjectErrorKind::RateLimited | ObjectErrorKind::ServiceUnavailable
+        )
+    }
+
+    fn io_kind(&self) -> io::ErrorKind {
+        match self {
+            ObjectErrorKind::Unsupported => io::ErrorKind::Unsupported,
+            ObjectErrorKind::ObjectNotFound => io::ErrorKind::NotFound,
+            ObjectErrorKind::ObjectAlreadyExists => io::ErrorKind::AlreadyExists,
+            ObjectErrorKind::PermissionDenied => io::ErrorKind::PermissionDenied,
+            _ => io::ErrorKind::Other,
+        }
+    }
+}
+
+impl Display for ObjectErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
 /// ObjectError carries object related context.
 ///
-/// # Notes
+/// All errors returned by OpenDAL are [`std::io::Error`]. For errors that
+/// happened on objects, use [`ObjectError::downcast`] to get the semantic
+/// kind, the retryability and the context.
 ///
-/// This error is used to carry context only, and should never be returned to users.
-/// Please wrap in [`std::io::Error`] with correct [`std::io::ErrorKind`] instead.
-#[derive(Error, Debug)]
-#[error("object error: (op: {op}, path: {path}, source: {source})")]
+/// # Examples
+///
+/// ```
+/// # use anyhow::Result;
+/// # use opendal::Operator;
+/// # use opendal::Scheme;
+/// use opendal::ObjectError;
+/// use opendal::ObjectErrorKind;
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let op = Operator::from_env(Scheme::Fs)?;
+/// if let Err(e) = op.object("test_file").metadata().await {
+///     if let Some(oe) = ObjectError::downcast(&e) {
+///         if oe.kind() == ObjectErrorKind::ObjectNotFound {
+///             println!("object not exist")
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
 pub struct ObjectError {
     op: Operation,
     path: String,
+    kind: ObjectErrorKind,
+    temporary: bool,
+    context: Vec<(&'static str, String)>,
     source: anyhow::Error,
 }
 
 impl ObjectError {
+    /// Create a new ObjectError with [`ObjectErrorKind::Unexpected`].
     pub fn new(op: Operation, path: &str, source: impl Into<anyhow::Error>) -> Self {
         ObjectError {
             op,
             path: path.to_string(),
+            kind: ObjectErrorKind::Unexpected,
+            temporary: false,
+            context: Vec::new(),
             source: source.into(),
         }
     }
+
+    /// Set the kind of error, retryability will be reset by kind.
+    pub(crate) fn with_kind(mut self, kind: ObjectErrorKind) -> Self {
+        self.kind = kind;
+        self.temporary = kind.is_temporary();
+        self
+    }
+
+    /// Mark this error as temporary or permanent.
+    pub(crate) fn with_temporary(mut self, temporary: bool) -> Self {
+        self.temporary = temporary;
+        self
+    }
+
+    /// Replace the source of this error.
+    pub(crate) fn with_source(mut self, source: impl Into<anyhow::Error>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Add context for this error, like `service`.
+    pub(crate) fn with_context(mut self, key: &'static str, value: impl Into<String>) -> Self {
+        self.context.push((key, value.into()));
+        self
+    }
+
+    /// Get ObjectError carried by [`std::io::Error`].
+    pub fn downcast(err: &io::Error) -> Option<&ObjectError> {
+        err.get_ref().and_then(|e| e.downcast_ref::<ObjectError>())
+    }
+
+    /// Semantic kind of this error.
+    pub fn kind(&self) -> ObjectErrorKind {
+        self.kind
+    }
+
+    /// Check if this error is temporary, temporary errors could be fixed by
+    /// retrying.
+    pub fn is_temporary(&self) -> bool {
+        self.temporary
+    }
+
+    /// The operation that this error happened.
+    pub fn operation(&self) -> Operation {
+        self.op
+    }
+
+    /// The path of object that this error happened.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get context value by key.
+    ///
+    /// Errors returned by services could carry:
+    ///
+    /// - `service`: the name of service, like `s3`.
+    /// - `code`: the error code returned by service, like `SlowDown`.
+    /// - `message`: the error message returned by service.
+    /// - `request_id`: the request id returned by service.
+    pub fn context(&self, key: &str) -> Option<&str> {
+        self.context
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The error code returned by service, like `NoSuchKey`.
+    pub fn service_code(&self) -> Option<&str> {
+        self.context("code")
+    }
+
+    /// The error message returned by service.
+    pub fn service_message(&self) -> Option<&str> {
+        self.context("message")
+    }
+
+    /// The request id returned by service, useful while contacting with
+    /// service providers.
+    pub fn request_id(&self) -> Option<&str> {
+        self.context("request_id")
+    }
+}
+
+impl Display for ObjectError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "object error: (op: {}, path: {}, kind: {}",
+            self.op, self.path, self.kind
+        )?;
+        if self.temporary {
+            write!(f, ", temporary")?;
+        }
+        for (k, v) in &self.context {
+            write!(f, ", {k}: {v}")?;
+        }
+        write!(f, ", source: {})", self.source)
+    }
+}
+
+impl std::error::Error for ObjectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Convert into [`std::io::Error`] with the matched [`std::io::ErrorKind`].
+///
+/// Temporary errors will be converted into [`std::io::ErrorKind::Interrupted`].
+impl From<ObjectError> for io::Error {
+    fn from(err: ObjectError) -> Self {
+        let kind = if err.temporary {
+            io::ErrorKind::Interrupted
+        } else {
+            err.kind.io_kind()
+        };
+        io::Error::new(kind, err)
+    }
+}
+
+/// Check if this error is temporary and could be fixed by retrying.
+pub(crate) fn is_temporary_error(err: &io::Error) -> bool {
+    match ObjectError::downcast(err) {
+        Some(oe) => oe.is_temporary(),
+        None => err.kind() == io::ErrorKind::Interrupted,
+    }
+}
+
+/// Map [`std::io::ErrorKind`] into [`ObjectErrorKind`].
+pub(crate) fn parse_io_error_kind(kind: io::ErrorKind) -> ObjectErrorKind {
+    match kind {
+        io::ErrorKind::NotFound => ObjectErrorKind::ObjectNotFound,
+        io::ErrorKind::AlreadyExists => ObjectErrorKind::ObjectAlreadyExists,
+        io::ErrorKind::PermissionDenied => ObjectErrorKind::PermissionDenied,
+        io::ErrorKind::Unsupported => ObjectErrorKind::Unsupported,
+        _ => ObjectErrorKind::Unexpected,
+    }
 }
 
 /// Copied for [`io::Error::other`], should be removed after `io_error_other` stable.
@@ -102,12 +319,76 @@ where
 
 /// Creates new Unsupported Object Error.
 pub fn new_unsupported_object_error(op: Operation, path: &str) -> io::Error {
-    io::Error::new(
-        io::ErrorKind::Unsupported,
-        ObjectError::new(
-            op,
-            path,
-            anyhow::anyhow!("operation is not supported by underlying services"),
-        ),
+    ObjectError::new(
+        op,
+        path,
+        anyhow::anyhow!("operation is not supported by underlying services"),
     )
+    .with_kind(ObjectErrorKind::Unsupported)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn test_object_error_into_io_error() {
+        let cases = vec![
+            (ObjectErrorKind::ObjectNotFound, io::ErrorKind::NotFound),
+            (
+                ObjectErrorKind::PermissionDenied,
+                io::ErrorKind::PermissionDenied,
+            ),
+            (ObjectErrorKind::Unsupported, io::ErrorKind::Unsupported),
+            (ObjectErrorKind::ConditionNotMatch, io::ErrorKind::Other),
+            (ObjectErrorKind::RateLimited, io::ErrorKind::Interrupted),
+            (
+                ObjectErrorKind::ServiceUnavailable,
+                io::ErrorKind::Interrupted,
+            ),
+        ];
+
+        for (kind, expected) in cases {
+            let err: io::Error = ObjectError::new(Operation::Read, "path", anyhow!("test"))
+                .with_kind(kind)
+                .into();
+            assert_eq!(err.kind(), expected, "{kind}");
+
+            let oe = ObjectError::downcast(&err).expect("must be object error");
+            assert_eq!(oe.kind(), kind);
+            assert_eq!(oe.path(), "path");
+            assert_eq!(is_temporary_error(&err), oe.is_temporary());
+        }
+    }
+
+    #[test]
+    fn test_object_error_context() {
+        let err = ObjectError::new(Operation::Stat, "path", anyhow!("test"))
+            .with_kind(ObjectErrorKind::ServiceUnavailable)
+            .with_temporary(false)
+            .with_context("service", "s3");
+
+        assert!(!err.is_temporary());
+        assert_eq!(err.context("service"), Some("s3"));
+        assert_eq!(err.context("bucket"), None);
+        assert_eq!(
+            err.to_string(),
+            "object error: (op: stat, path: path, kind: ServiceUnavailable, service: s3, source: test)"
+        );
+    }
+
+    #[test]
+    fn test_is_temporary_error() {
+        assert!(is_temporary_error(&io::Error::new(
+            io::ErrorKind::Interrupted,
+            anyhow!("test")
+        )));
+        assert!(!is_temporary_error(&io::Error::new(
+            io::ErrorKind::Other,
+            anyhow!("test")
+        )));
+    }
 }
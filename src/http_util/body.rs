@@ -22,11 +22,16 @@ use std::task::Poll;
 
 use bytes::Buf;
 use bytes::Bytes;
+use futures::stream;
 use futures::AsyncRead;
+use futures::Stream;
+use futures::StreamExt;
 
+use crate::io_util::into_reader;
 use crate::io_util::into_stream;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 
 /// Body used in blocking HTTP requests.
 pub enum Body {
@@ -73,6 +78,8 @@ pub enum AsyncBody {
     Bytes(Bytes),
     /// Body with a Reader.
     Reader(BytesReader),
+    /// Body with a Stream of bytes.
+    Stream(BytesStreamer),
 }
 
 impl AsyncBody {
@@ -80,8 +87,16 @@ impl AsyncBody {
     pub async fn consume(self) -> Result<()> {
         use futures::io;
 
-        if let AsyncBody::Reader(r) = self {
-            io::copy(r, &mut io::sink()).await?;
+        match self {
+            AsyncBody::Reader(r) => {
+                io::copy(r, &mut io::sink()).await?;
+            }
+            AsyncBody::Stream(mut s) => {
+                while let Some(bs) = s.next().await {
+                    bs?;
+                }
+            }
+            _ => {}
         }
 
         Ok(())
@@ -99,6 +114,13 @@ impl AsyncBody {
                 io::copy(r, &mut w).await?;
                 Ok(Bytes::from(w.into_inner()))
             }
+            AsyncBody::Stream(mut s) => {
+                let mut bs = Vec::with_capacity(1024);
+                while let Some(chunk) = s.next().await {
+                    bs.extend_from_slice(&chunk?);
+                }
+                Ok(Bytes::from(bs))
+            }
         }
     }
 
@@ -110,6 +132,19 @@ impl AsyncBody {
             AsyncBody::Empty => Box::new(Cursor::new(vec![])),
             AsyncBody::Bytes(bs) => Box::new(Cursor::new(bs.to_vec())),
             AsyncBody::Reader(r) => r,
+            AsyncBody::Stream(s) => Box::new(into_reader(s)),
+        }
+    }
+
+    /// Consume the response to build a stream.
+    ///
+    /// Stream body will be returned directly without copying.
+    pub fn stream(self) -> BytesStreamer {
+        match self {
+            AsyncBody::Empty => Box::new(stream::empty()),
+            AsyncBody::Bytes(bs) => Box::new(stream::iter(vec![Ok(bs)])),
+            AsyncBody::Reader(r) => Box::new(into_stream(r, 64 * 1024)),
+            AsyncBody::Stream(s) => s,
         }
     }
 }
@@ -120,17 +155,45 @@ impl From<AsyncBody> for reqwest::Body {
             AsyncBody::Empty => reqwest::Body::from(""),
             AsyncBody::Bytes(bs) => reqwest::Body::from(bs),
             AsyncBody::Reader(r) => reqwest::Body::wrap_stream(into_stream(r, 8 * 1024)),
+            AsyncBody::Stream(s) => reqwest::Body::wrap_stream(SyncStream(s)),
         }
     }
 }
 
+/// SyncStream makes [`BytesStreamer`] `Sync` so that it can be used by
+/// `reqwest::Body::wrap_stream`.
+struct SyncStream(BytesStreamer);
+
+/// SyncStream will be accessed uniquely, not concurrent read will happen.
+///
+/// No `get_inner`, no `Clone`, no other ways to access internally fields.
+unsafe impl Sync for SyncStream {}
+
+impl Stream for SyncStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+}
+
 impl AsyncRead for AsyncBody {
     fn poll_read(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         mut buf: &mut [u8],
     ) -> Poll<Result<usize>> {
-        match self.get_mut() {
+        let this = self.get_mut();
+        // Convert stream into reader lazily so that we don't need to
+        // keep a reader for every stream body.
+        if let AsyncBody::Stream(_) = this {
+            match std::mem::replace(this, AsyncBody::Empty) {
+                AsyncBody::Stream(s) => *this = AsyncBody::Reader(Box::new(into_reader(s))),
+                _ => unreachable!(),
+            }
+        }
+
+        match this {
             AsyncBody::Empty => Poll::Ready(Ok(0)),
             AsyncBody::Bytes(bs) => {
                 let size = min(bs.len(), buf.len());
@@ -141,6 +204,7 @@ impl AsyncRead for AsyncBody {
                 Poll::Ready(Ok(size))
             }
             AsyncBody::Reader(r) => Pin::new(r).poll_read(cx, buf),
+            AsyncBody::Stream(_) => unreachable!("stream body must be converted into reader"),
         }
     }
 }
@@ -12,25 +12,67 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fs;
+use std::future::Future;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::io::Result;
+use std::pin::Pin;
 use std::str::FromStr;
+#[cfg(feature = "rustls")]
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
 
+use anyhow::anyhow;
+use bytes::Bytes;
+use futures::ready;
+use futures::Stream;
 use futures::TryStreamExt;
 use http::Request;
 use http::Response;
+#[cfg(not(feature = "rustls"))]
+use log::warn;
 use reqwest::Url;
 
 use super::AsyncBody;
 use super::Body;
-use crate::io_util::into_reader;
 
 /// HttpClient that used across opendal.
+///
+/// Services will create a default client if not specified. Use
+/// [`HttpClientBuilder`] to build a client with custom configs.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::services::s3;
+/// use opendal::HttpClient;
+///
+/// fn main() -> Result<()> {
+///     let client = HttpClient::builder()
+///         .connect_timeout(Duration::from_secs(3))
+///         .read_timeout(Duration::from_secs(10))
+///         .pool_max_idle_per_host(16)
+///         .build()?;
+///
+///     let mut builder = s3::Builder::default();
+///     builder.http_client(client);
+///
+///     Ok(())
+/// }
+/// ```
 #[derive(Debug, Clone)]
 pub struct HttpClient {
     async_client: reqwest::Client,
     sync_client: ureq::Agent,
+    read_timeout: Option<Duration>,
 }
 
 impl HttpClient {
@@ -42,11 +84,17 @@ impl HttpClient {
         HttpClient {
             async_client,
             sync_client,
+            read_timeout: None,
         }
     }
 
+    /// Create a [`HttpClientBuilder`] to build http client with custom configs.
+    pub fn builder() -> HttpClientBuilder {
+        HttpClientBuilder::default()
+    }
+
     /// Send a request in blocking way.
-    pub fn send(&self, req: Request<Body>) -> Result<Response<Body>> {
+    pub(crate) fn send(&self, req: Request<Body>) -> Result<Response<Body>> {
         let (parts, body) = req.into_parts();
 
         let mut ur = self
@@ -88,7 +136,7 @@ impl HttpClient {
     }
 
     /// Send a request in async way.
-    pub async fn send_async(&self, req: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
+    pub(crate) async fn send_async(&self, req: Request<AsyncBody>) -> Result<Response<AsyncBody>> {
         let (parts, body) = req.into_parts();
 
         let resp = self
@@ -119,19 +167,21 @@ impl HttpClient {
             hr = hr.header(k, v);
         }
 
-        let resp = hr
-            .body(AsyncBody::Reader(Box::new(into_reader(
-                resp.bytes_stream().map_err(|err| {
-                    let kind = if err.is_timeout() || err.is_connect() {
-                        ErrorKind::Interrupted
-                    } else {
-                        ErrorKind::Other
-                    };
+        let stream = resp.bytes_stream().map_err(|err| {
+            let kind = if err.is_timeout() || err.is_connect() {
+                ErrorKind::Interrupted
+            } else {
+                ErrorKind::Other
+            };
 
-                    Error::new(kind, err)
-                }),
-            ))))
-            .expect("response must build succeed");
+            Error::new(kind, err)
+        });
+        let body = match self.read_timeout {
+            None => AsyncBody::Stream(Box::new(stream)),
+            Some(timeout) => AsyncBody::Stream(Box::new(ReadTimeoutStream::new(stream, timeout))),
+        };
+
+        let resp = hr.body(body).expect("response must build succeed");
 
         Ok(resp)
     }
@@ -142,3 +192,388 @@ impl Default for HttpClient {
         HttpClient::new()
     }
 }
+
+/// HttpClientBuilder is used to build [`HttpClient`] with custom configs.
+///
+/// All configs could also be set via services' `from_iter` with following keys:
+///
+/// - `http_connect_timeout`: connect timeout in seconds.
+/// - `http_read_timeout`: read timeout in seconds.
+/// - `http_timeout`: total timeout of a request in seconds.
+/// - `http_proxy`: proxy url for both http and https requests.
+/// - `http_root_ca`: path to the PEM encoded root certificates.
+/// - `http_pool_idle_timeout`: pool idle timeout in seconds.
+/// - `http_pool_max_idle_per_host`: max idle connections per host.
+/// - `http2_prior_knowledge`: use HTTP/2 without negotiation if `true`.
+#[derive(Default, Clone)]
+pub struct HttpClientBuilder {
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<String>,
+    root_certificates: Vec<Vec<u8>>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    http2_prior_knowledge: bool,
+}
+
+impl Debug for HttpClientBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("HttpClientBuilder");
+
+        d.field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("timeout", &self.timeout)
+            .field("root_certificates", &self.root_certificates.len())
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("http2_prior_knowledge", &self.http2_prior_knowledge);
+
+        // Proxy url could contain credentials.
+        if self.proxy.is_some() {
+            d.field("proxy", &"<redacted>");
+        }
+
+        d.finish()
+    }
+}
+
+impl HttpClientBuilder {
+    /// Set timeout for connecting to services.
+    pub fn connect_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set timeout for reading data from services.
+    ///
+    /// Reading will fail if no data is received during the timeout.
+    pub fn read_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.read_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set total timeout of a request, from connecting until the response
+    /// body has been finished.
+    ///
+    /// # Notes
+    ///
+    /// Large objects could take a long time to transfer, please use
+    /// `read_timeout` instead if not sure.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set proxy url for all requests, like `http://127.0.0.1:8080`.
+    pub fn proxy(&mut self, proxy: &str) -> &mut Self {
+        if !proxy.is_empty() {
+            self.proxy = Some(proxy.to_string());
+        }
+
+        self
+    }
+
+    /// Add PEM encoded root certificates that trusted by client.
+    ///
+    /// The input could contain multiple certificates.
+    ///
+    /// # Notes
+    ///
+    /// Blocking client only supports this config with `rustls` feature
+    /// enabled, otherwise the certificates will be ignored by it.
+    pub fn root_certificate(&mut self, pem: &[u8]) -> &mut Self {
+        self.root_certificates.push(pem.to_vec());
+
+        self
+    }
+
+    /// Set timeout for idle connections in pool.
+    ///
+    /// # Notes
+    ///
+    /// Only async client supports this config.
+    pub fn pool_idle_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.pool_idle_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set max idle connections per host in pool.
+    pub fn pool_max_idle_per_host(&mut self, max: usize) -> &mut Self {
+        self.pool_max_idle_per_host = Some(max);
+
+        self
+    }
+
+    /// Use HTTP/2 without negotiation.
+    ///
+    /// # Notes
+    ///
+    /// Only async client supports this config, blocking client always uses
+    /// HTTP/1.1.
+    pub fn http2_prior_knowledge(&mut self) -> &mut Self {
+        self.http2_prior_knowledge = true;
+
+        self
+    }
+
+    /// Set config by `from_iter` key.
+    ///
+    /// Returns `false` if the key is not a http client config.
+    pub(crate) fn parse_option(&mut self, key: &str, value: &str) -> Result<bool> {
+        let parse_secs = |v: &str| -> Result<Duration> {
+            v.parse::<u64>().map(Duration::from_secs).map_err(|e| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    anyhow!("{key} value {v} is invalid: {e}"),
+                )
+            })
+        };
+
+        match key {
+            "http_connect_timeout" => self.connect_timeout(parse_secs(value)?),
+            "http_read_timeout" => self.read_timeout(parse_secs(value)?),
+            "http_timeout" => self.timeout(parse_secs(value)?),
+            "http_proxy" => self.proxy(value),
+            "http_root_ca" => {
+                let pem = fs::read(value)
+                    .map_err(|e| Error::new(e.kind(), anyhow!("read {key} from {value}: {e}")))?;
+                self.root_certificate(&pem)
+            }
+            "http_pool_idle_timeout" => self.pool_idle_timeout(parse_secs(value)?),
+            "http_pool_max_idle_per_host" => {
+                self.pool_max_idle_per_host(value.parse().map_err(|e| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        anyhow!("{key} value {value} is invalid: {e}"),
+                    )
+                })?)
+            }
+            "http2_prior_knowledge" => {
+                let enabled: bool = value.parse().map_err(|e| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        anyhow!("{key} value {value} is invalid: {e}"),
+                    )
+                })?;
+                if enabled {
+                    self.http2_prior_knowledge();
+                }
+                self
+            }
+            _ => return Ok(false),
+        };
+
+        Ok(true)
+    }
+
+    /// Build a new [`HttpClient`].
+    pub fn build(&mut self) -> Result<HttpClient> {
+        let invalid_input = |e: &dyn std::fmt::Display| {
+            Error::new(ErrorKind::InvalidInput, anyhow!("build http client: {e}"))
+        };
+
+        let mut async_builder = reqwest::ClientBuilder::new();
+        let mut sync_builder = ureq::AgentBuilder::new();
+
+        if let Some(v) = self.connect_timeout {
+            async_builder = async_builder.connect_timeout(v);
+            sync_builder = sync_builder.timeout_connect(v);
+        }
+        if let Some(v) = self.read_timeout {
+            sync_builder = sync_builder.timeout_read(v);
+        }
+        if let Some(v) = self.timeout {
+            async_builder = async_builder.timeout(v);
+            sync_builder = sync_builder.timeout(v);
+        }
+        if let Some(v) = &self.proxy {
+            async_builder =
+                async_builder.proxy(reqwest::Proxy::all(v).map_err(|e| invalid_input(&e))?);
+            sync_builder = sync_builder.proxy(ureq::Proxy::new(v).map_err(|e| invalid_input(&e))?);
+        }
+        if !self.root_certificates.is_empty() {
+            let mut blocks = Vec::new();
+            for pem in &self.root_certificates {
+                for block in split_pem_bundle(pem) {
+                    async_builder = async_builder.add_root_certificate(
+                        reqwest::Certificate::from_pem(block).map_err(|e| invalid_input(&e))?,
+                    );
+                    blocks.push(block);
+                }
+            }
+
+            #[cfg(feature = "rustls")]
+            {
+                let connector =
+                    super::tls::RustlsConnector::new(&blocks).map_err(|e| invalid_input(&e))?;
+                sync_builder = sync_builder.tls_connector(Arc::new(connector));
+            }
+            #[cfg(not(feature = "rustls"))]
+            warn!(
+                "{} root certificates are ignored by blocking client, enable `rustls` feature to use them",
+                blocks.len()
+            );
+        }
+        if let Some(v) = self.pool_idle_timeout {
+            async_builder = async_builder.pool_idle_timeout(v);
+        }
+        if let Some(v) = self.pool_max_idle_per_host {
+            async_builder = async_builder.pool_max_idle_per_host(v);
+            sync_builder = sync_builder.max_idle_connections_per_host(v);
+        }
+        if self.http2_prior_knowledge {
+            async_builder = async_builder.http2_prior_knowledge();
+        }
+
+        let async_client = async_builder.build().map_err(|e| invalid_input(&e))?;
+        let sync_client = sync_builder.build();
+
+        Ok(HttpClient {
+            async_client,
+            sync_client,
+            read_timeout: self.read_timeout,
+        })
+    }
+}
+
+/// Split PEM bundle into PEM encoded certificates.
+fn split_pem_bundle(pem: &[u8]) -> Vec<&[u8]> {
+    const END: &[u8] = b"-----END CERTIFICATE-----";
+
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    while let Some(idx) = pem[start..].windows(END.len()).position(|w| w == END) {
+        let end = start + idx + END.len();
+        blocks.push(&pem[start..end]);
+        start = end;
+    }
+    blocks
+}
+
+/// ReadTimeoutStream will return error if no data is received from inner
+/// stream during the timeout.
+struct ReadTimeoutStream<S> {
+    inner: S,
+    timeout: Duration,
+    sleep: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl<S> ReadTimeoutStream<S> {
+    fn new(inner: S, timeout: Duration) -> Self {
+        ReadTimeoutStream {
+            inner,
+            timeout,
+            sleep: None,
+        }
+    }
+}
+
+impl<S> Stream for ReadTimeoutStream<S>
+where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Poll::Ready(v) = Pin::new(&mut self.inner).poll_next(cx) {
+            self.sleep = None;
+            return Poll::Ready(v);
+        }
+
+        let timeout = self.timeout;
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)));
+        ready!(sleep.as_mut().poll(cx));
+        self.sleep = None;
+
+        Poll::Ready(Some(Err(Error::new(
+            ErrorKind::Interrupted,
+            anyhow!("no data received in {timeout:?} while reading response"),
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream;
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[cfg(feature = "rustls")]
+    #[test]
+    fn test_root_certificate() -> Result<()> {
+        let pem = b"-----BEGIN CERTIFICATE-----
+MIIBejCCASGgAwIBAgIUISWLLPI2HsF1KtLpG2+t4XMGoP4wCgYIKoZIzj0EAwIw
+EjEQMA4GA1UEAwwHb3BlbmRhbDAgFw0yNjEwMTgyMTM4MDNaGA8yMTI2MDkyNDIx
+MzgwM1owEjEQMA4GA1UEAwwHb3BlbmRhbDBZMBMGByqGSM49AgEGCCqGSM49AwEH
+A0IABOXLPrsUP22bp96JYiYe37f5vA2YfWv9mR3TVx9Aqnk3+kuuibSbzHrI/t0u
+xVrN1lOIjfvhV6ibCYA3gNxyU2ujUzBRMB0GA1UdDgQWBBQYB1FJTWAy2TRa49WD
+oUZJIE4bjjAfBgNVHSMEGDAWgBQYB1FJTWAy2TRa49WDoUZJIE4bjjAPBgNVHRMB
+Af8EBTADAQH/MAoGCCqGSM49BAMCA0cAMEQCID3381XvjJlAFQe4XT/K8oc/Xr3s
+9XSfUYxKrEaY7jKsAiAGNMxPlSBC8x3CyoSWUyeNIp4ECxrxN8/lcMttNCz7bw==
+-----END CERTIFICATE-----
+";
+
+        HttpClient::builder().root_certificate(pem).build()?;
+
+        let err = HttpClient::builder()
+            .root_certificate(b"-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----")
+            .build()
+            .expect_err("invalid certificate must fail");
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_pem_bundle() {
+        let pem = b"-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n-----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----\n";
+
+        let blocks = split_pem_bundle(pem);
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[1].ends_with(b"BBBB\n-----END CERTIFICATE-----"));
+    }
+
+    #[test]
+    fn test_parse_option() -> Result<()> {
+        let mut builder = HttpClient::builder();
+
+        assert!(builder.parse_option("http_connect_timeout", "3")?);
+        assert!(builder.parse_option("http_pool_max_idle_per_host", "16")?);
+        assert!(builder.parse_option("http2_prior_knowledge", "false")?);
+        assert!(!builder.http2_prior_knowledge);
+        assert!(builder.parse_option("http2_prior_knowledge", "true")?);
+        assert!(!builder.parse_option("bucket", "test")?);
+        assert!(builder.parse_option("http_read_timeout", "abc").is_err());
+        assert!(builder.parse_option("http2_prior_knowledge", "on").is_err());
+
+        assert_eq!(builder.connect_timeout, Some(Duration::from_secs(3)));
+        assert_eq!(builder.pool_max_idle_per_host, Some(16));
+        assert!(builder.http2_prior_knowledge);
+
+        builder.build()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout_stream() {
+        let inner = stream::iter(vec![Ok(Bytes::from("hello"))]).chain(stream::pending());
+        let mut s = ReadTimeoutStream::new(inner, Duration::from_millis(10));
+
+        let bs = s.next().await.expect("must have data").expect("must ok");
+        assert_eq!(bs, Bytes::from("hello"));
+
+        let err = s
+            .next()
+            .await
+            .expect("must have data")
+            .expect_err("must timeout");
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+    }
+}
@@ -26,6 +26,7 @@ use http::StatusCode;
 
 use crate::error::other;
 use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
 use crate::http_util::AsyncBody;
 use crate::ops::Operation;
 
@@ -49,20 +50,44 @@ pub fn new_request_sign_error(op: Operation, path: &str, err: anyhow::Error) ->
 
 /// Create error happened during sending http request.
 pub fn new_request_send_error(op: Operation, path: &str, err: Error) -> Error {
+    let kind = err.kind();
     Error::new(
-        err.kind(),
-        ObjectError::new(op, path, anyhow!("sending request:  {err:?}")),
+        kind,
+        ObjectError::new(op, path, anyhow!("sending request:  {err:?}"))
+            .with_temporary(kind == io::ErrorKind::Interrupted),
     )
 }
 
 /// Create error happened during consuming http response.
 pub fn new_response_consume_error(op: Operation, path: &str, err: Error) -> Error {
+    let kind = err.kind();
     Error::new(
-        err.kind(),
-        ObjectError::new(op, path, anyhow!("consuming response: {err:?}")),
+        kind,
+        ObjectError::new(op, path, anyhow!("consuming response: {err:?}"))
+            .with_temporary(kind == io::ErrorKind::Interrupted),
     )
 }
 
+/// Map http status code of error response into [`ObjectErrorKind`].
+///
+/// Services should use this as the fallback and only override the status
+/// codes that have special meanings.
+pub fn parse_error_kind(code: StatusCode) -> ObjectErrorKind {
+    match code {
+        StatusCode::NOT_FOUND => ObjectErrorKind::ObjectNotFound,
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ObjectErrorKind::PermissionDenied,
+        StatusCode::NOT_MODIFIED | StatusCode::PRECONDITION_FAILED => {
+            ObjectErrorKind::ConditionNotMatch
+        }
+        StatusCode::TOO_MANY_REQUESTS => ObjectErrorKind::RateLimited,
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => ObjectErrorKind::ServiceUnavailable,
+        _ => ObjectErrorKind::Unexpected,
+    }
+}
+
 /// ErrorResponse carries HTTP status code, headers and body.
 ///
 /// This struct should only be used to parse error response which is small.
@@ -86,6 +111,29 @@ impl ErrorResponse {
     pub fn body(&self) -> &[u8] {
         &self.body
     }
+
+    /// Describe this response by the parsed error code, message and request
+    /// id instead of the raw body.
+    ///
+    /// Services should only fallback to the raw response if the body can't
+    /// be parsed.
+    pub(crate) fn describe(
+        &self,
+        code: &str,
+        message: &str,
+        request_id: Option<&str>,
+    ) -> anyhow::Error {
+        let mut s = format!("status code: {}", self.status_code());
+        for (k, v) in [("code", code), ("message", message)] {
+            if !v.is_empty() {
+                s.push_str(&format!(", {k}: {v}"));
+            }
+        }
+        if let Some(v) = request_id {
+            s.push_str(&format!(", request id: {v}"));
+        }
+        anyhow!(s)
+    }
 }
 
 impl Display for ErrorResponse {
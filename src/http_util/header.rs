@@ -19,6 +19,8 @@ use http::header::CONTENT_LENGTH;
 use http::header::ETAG;
 use http::header::LAST_MODIFIED;
 use http::HeaderMap;
+use http::HeaderValue;
+use http::Request;
 use time::format_description::well_known::Rfc2822;
 use time::OffsetDateTime;
 
@@ -48,6 +50,17 @@ pub fn parse_content_md5(headers: &HeaderMap) -> Result<Option<&str>> {
     }
 }
 
+/// Parse header value to string according to name.
+pub fn parse_header_to_str<'a>(headers: &'a HeaderMap, name: &str) -> Result<Option<&'a str>> {
+    match headers.get(name) {
+        None => Ok(None),
+        Some(v) => Ok(Some(
+            v.to_str()
+                .map_err(|e| anyhow!("parse {} header: {:?}", name, e))?,
+        )),
+    }
+}
+
 /// Parse last modified from header map.
 pub fn parse_last_modified(headers: &HeaderMap) -> Result<Option<OffsetDateTime>> {
     match headers.get(LAST_MODIFIED) {
@@ -74,3 +87,15 @@ pub fn parse_etag(headers: &HeaderMap) -> Result<Option<&str>> {
         )),
     }
 }
+
+/// Insert header into built request, returns error if value is invalid.
+pub fn insert_header<T>(
+    req: &mut Request<T>,
+    name: &'static str,
+    value: &str,
+) -> std::result::Result<(), http::Error> {
+    let value = HeaderValue::from_str(value)?;
+    req.headers_mut()
+        .insert(HeaderName::from_static(name), value);
+    Ok(())
+}
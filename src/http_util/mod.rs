@@ -13,24 +13,31 @@
 // limitations under the License.
 mod client;
 pub use client::HttpClient;
+pub use client::HttpClientBuilder;
 
 mod body;
 pub use body::AsyncBody;
 pub use body::Body;
 
 mod header;
+pub use header::insert_header;
 pub use header::parse_content_length;
 pub use header::parse_content_md5;
 pub use header::parse_etag;
+pub use header::parse_header_to_str;
 pub use header::parse_last_modified;
 
 mod uri;
 pub use uri::percent_encode_path;
 
+#[cfg(feature = "rustls")]
+mod tls;
+
 mod error;
 pub use error::new_request_build_error;
 pub use error::new_request_send_error;
 pub use error::new_request_sign_error;
 pub use error::new_response_consume_error;
+pub use error::parse_error_kind;
 pub use error::parse_error_response;
 pub use error::ErrorResponse;
@@ -0,0 +1,114 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use rustls::ClientConfig;
+use rustls::ClientConnection;
+use rustls::RootCertStore;
+use rustls::StreamOwned;
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::CertificateDer;
+use rustls_pki_types::ServerName;
+use ureq::ReadWrite;
+use ureq::TlsConnector;
+
+/// RustlsConnector is the tls connector used by blocking client to trust
+/// extra root certificates.
+///
+/// We implement ureq's [`TlsConnector`] instead of using its own rustls
+/// config, so that our rustls version is not bound to ureq's.
+pub(crate) struct RustlsConnector {
+    config: Arc<ClientConfig>,
+}
+
+impl RustlsConnector {
+    /// Create a connector trusts webpki roots and given PEM encoded
+    /// certificates.
+    pub(crate) fn new(pems: &[&[u8]]) -> Result<Self> {
+        let invalid_input =
+            |e: &dyn std::fmt::Display| Error::new(ErrorKind::InvalidInput, anyhow!("{e}"));
+
+        let mut roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        for pem in pems {
+            let cert = CertificateDer::from_pem_slice(pem).map_err(|e| invalid_input(&e))?;
+            roots.add(cert).map_err(|e| invalid_input(&e))?;
+        }
+
+        let config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(|e| invalid_input(&e))?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+
+        Ok(RustlsConnector {
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl TlsConnector for RustlsConnector {
+    fn connect(
+        &self,
+        dns_name: &str,
+        mut io: Box<dyn ReadWrite>,
+    ) -> std::result::Result<Box<dyn ReadWrite>, ureq::Error> {
+        // rustls doesn't accept ipv6 addresses with brackets.
+        let dns_name = dns_name.trim_start_matches('[').trim_end_matches(']');
+        let name = ServerName::try_from(dns_name)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?
+            .to_owned();
+
+        let mut conn = ClientConnection::new(self.config.clone(), name)
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+        conn.complete_io(&mut io)?;
+
+        Ok(Box::new(RustlsStream(StreamOwned::new(conn, io))))
+    }
+}
+
+#[derive(Debug)]
+struct RustlsStream(StreamOwned<ClientConnection, Box<dyn ReadWrite>>);
+
+impl ReadWrite for RustlsStream {
+    fn socket(&self) -> Option<&TcpStream> {
+        self.0.get_ref().socket()
+    }
+}
+
+impl Read for RustlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for RustlsStream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.0.flush()
+    }
+}
@@ -0,0 +1,354 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use bytes::Buf;
+use bytes::BytesMut;
+
+use crate::ops::BytesRange;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::Accessor;
+use crate::BlockingBytesReader;
+use crate::Object;
+
+/// Default capacity of the internal buffer of [`BlockingSeekableReader`].
+const DEFAULT_BUFFER_CAPACITY: usize = 256 * 1024;
+/// Default read ahead window of [`BlockingSeekableReader`].
+const DEFAULT_READ_AHEAD: u64 = 256 * 1024;
+
+/// Add seek support for object via internal lazy blocking operation.
+///
+/// This is the blocking version of [`seekable_read`][crate::io_util::seekable_read].
+///
+/// # Example
+///
+/// ```no_run
+/// # use opendal::Operator;
+/// # use opendal::Scheme;
+/// # use anyhow::Result;
+/// use std::io::Read;
+/// use std::io::Seek;
+/// use std::io::SeekFrom;
+///
+/// use opendal::io_util::blocking_seekable_read;
+/// # fn main() -> Result<()> {
+/// let op = Operator::from_env(Scheme::Fs)?;
+/// let o = op.object("test");
+/// let mut r = blocking_seekable_read(&o, 10..);
+/// r.seek(SeekFrom::Current(10))?;
+/// let mut bs = vec![0; 10];
+/// r.read(&mut bs)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn blocking_seekable_read(o: &Object, range: impl RangeBounds<u64>) -> BlockingSeekableReader {
+    let br = BytesRange::from(range);
+
+    BlockingSeekableReader {
+        acc: o.accessor(),
+        path: o.path().to_string(),
+        offset: br.offset(),
+        size: br.size(),
+
+        buffer_capacity: DEFAULT_BUFFER_CAPACITY,
+        read_ahead: DEFAULT_READ_AHEAD,
+
+        pos: 0,
+        buf: BytesMut::new(),
+        buf_start: 0,
+        reader: None,
+    }
+}
+
+/// BlockingSeekableReader implement `Read` and `Seek`.
+///
+/// It shares the same buffer and read ahead behavior with
+/// [`SeekableReader`][crate::io_util::SeekableReader].
+pub struct BlockingSeekableReader {
+    acc: Arc<dyn Accessor>,
+    path: String,
+    offset: Option<u64>,
+    size: Option<u64>,
+
+    buffer_capacity: usize,
+    read_ahead: u64,
+
+    pos: u64,
+    /// Buffer holds the content in `[buf_start, buf_start + buf.len())`.
+    ///
+    /// The underlying reader (if exists) always starts at the end of buffer.
+    buf: BytesMut,
+    buf_start: u64,
+    reader: Option<BlockingBytesReader>,
+}
+
+impl BlockingSeekableReader {
+    /// Set the capacity of internal buffer.
+    ///
+    /// Set to `0` to disable the buffer, and every read will be sent to the
+    /// underlying reader directly.
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity;
+        self
+    }
+
+    /// Set the read ahead window.
+    ///
+    /// Seeking forward no more than `size` bytes will skip bytes in the
+    /// current reader instead of starting a new read operation.
+    pub fn with_read_ahead(mut self, size: u64) -> Self {
+        self.read_ahead = size;
+        self
+    }
+
+    fn current_offset(&self) -> u64 {
+        self.offset.unwrap_or_default() + self.pos
+    }
+
+    fn current_size(&self) -> Option<u64> {
+        self.size.map(|v| v - self.pos)
+    }
+
+    /// Copy content from buffer if current pos is inside buffer.
+    fn read_from_buffer(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let buf_end = self.buf_start + self.buf.len() as u64;
+        if self.pos < self.buf_start || self.pos >= buf_end {
+            return None;
+        }
+
+        let start = (self.pos - self.buf_start) as usize;
+        let n = buf.len().min(self.buf.len() - start);
+        buf[..n].copy_from_slice(&self.buf[start..start + n]);
+        self.pos += n as u64;
+        Some(n)
+    }
+
+    /// Drop the oldest content to keep buffer under its capacity.
+    ///
+    /// Content after current pos will never be dropped.
+    fn shrink_buffer(&mut self) {
+        if self.buf.len() > self.buffer_capacity {
+            let excess =
+                (self.buf.len() - self.buffer_capacity).min((self.pos - self.buf_start) as usize);
+            self.buf.advance(excess);
+            self.buf_start += excess as u64;
+        }
+    }
+}
+
+impl Read for BlockingSeekableReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            if let Some(n) = self.read_from_buffer(buf) {
+                return Ok(n);
+            }
+            if matches!(self.size, Some(size) if self.pos >= size) {
+                return Ok(0);
+            }
+
+            let stream_pos = self.buf_start + self.buf.len() as u64;
+            let r = match &mut self.reader {
+                Some(r) if self.pos >= stream_pos && self.pos - stream_pos <= self.read_ahead => r,
+                // Current pos is out of buffer and read ahead window, we
+                // have to start a new read.
+                _ => {
+                    let op = OpRead::new_with_offset(
+                        &self.path,
+                        Some(self.current_offset()),
+                        self.current_size(),
+                    )?;
+                    self.reader = Some(self.acc.blocking_read(&op)?);
+
+                    // New reader starts at current pos, reset the buffer.
+                    self.buf.clear();
+                    self.buf_start = self.pos;
+                    continue;
+                }
+            };
+
+            // Large read at stream pos, read into user's buffer directly.
+            if self.pos == stream_pos && buf.len() >= self.buffer_capacity {
+                let n = match r.read(buf) {
+                    Ok(n) => n,
+                    Err(e) => {
+                        self.reader = None;
+                        return Err(e);
+                    }
+                };
+                self.pos += n as u64;
+                self.buf.clear();
+                self.buf_start = self.pos;
+                return Ok(n);
+            }
+
+            let filled = self.buf.len();
+            self.buf
+                .resize(filled + self.buffer_capacity.max(buf.len()), 0);
+            match r.read(&mut self.buf[filled..]) {
+                Err(e) => {
+                    self.buf.truncate(filled);
+                    self.reader = None;
+                    return Err(e);
+                }
+                // Reach the end of reader.
+                Ok(0) => {
+                    self.buf.truncate(filled);
+                    return Ok(0);
+                }
+                Ok(n) => {
+                    self.buf.truncate(filled + n);
+                    self.shrink_buffer();
+                }
+            }
+        }
+    }
+}
+
+impl Seek for BlockingSeekableReader {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let cur = self.pos as i64;
+        let cur = match pos {
+            SeekFrom::Start(off) => off as i64,
+            SeekFrom::Current(off) => cur + off,
+            SeekFrom::End(off) => {
+                // Stat the object to get it's content-length.
+                if self.size.is_none() {
+                    let op = OpStat::new(&self.path)?;
+                    let meta = self.acc.blocking_stat(&op)?;
+                    self.size = Some(meta.content_length() - self.offset.unwrap_or_default())
+                }
+
+                let total_size = self.size.expect("must have valid total_size");
+
+                total_size as i64 + off
+            }
+        };
+
+        if cur < 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                anyhow!("invalid seek to a negative position: {cur}"),
+            ));
+        }
+
+        // Real IO will be decided by the next read: content inside buffer or
+        // read ahead window will reuse current reader.
+        self.pos = cur as u64;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::io::Seek;
+    use std::io::SeekFrom;
+    use std::str::from_utf8;
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::Operator;
+    use crate::Scheme;
+
+    #[test]
+    fn test_reader() -> Result<()> {
+        let f = Operator::from_env(Scheme::Fs)?;
+
+        let path = format!("/tmp/{}", uuid::Uuid::new_v4());
+
+        // Create a test file.
+        f.object(&path).blocking_write("Hello, world!")?;
+
+        let o = f.object(&path);
+        let mut r = blocking_seekable_read(&o, ..);
+
+        // Seek to offset 3.
+        let n = r.seek(SeekFrom::Start(3))?;
+        assert_eq!(n, 3);
+
+        // Read only one byte.
+        let mut bs = vec![0; 1];
+        let n = r.read(&mut bs)?;
+        assert_eq!("l", from_utf8(&bs)?);
+        assert_eq!(n, 1);
+        let n = r.stream_position()?;
+        assert_eq!(n, 4);
+
+        // Seek to end.
+        let n = r.seek(SeekFrom::End(-1))?;
+        assert_eq!(n, 12);
+
+        // Read only one byte.
+        let mut bs = vec![0; 1];
+        let n = r.read(&mut bs)?;
+        assert_eq!("!", from_utf8(&bs)?);
+        assert_eq!(n, 1);
+        let n = r.stream_position()?;
+        assert_eq!(n, 13);
+
+        // Seek backward and read to end.
+        r.seek(SeekFrom::Start(7))?;
+        let mut s = String::new();
+        r.read_to_string(&mut s)?;
+        assert_eq!(s, "world!");
+
+        // Seek to a negative position should fail.
+        assert!(r.seek(SeekFrom::Current(-100)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reader_range() -> Result<()> {
+        let f = Operator::from_env(Scheme::Fs)?;
+
+        let path = format!("/tmp/{}", uuid::Uuid::new_v4());
+        let content: Vec<u8> = (0..1024 * 1024).map(|v| v as u8).collect();
+        f.object(&path).blocking_write(content.clone())?;
+
+        let o = f.object(&path);
+        let mut r = blocking_seekable_read(&o, 1024..)
+            .with_buffer_capacity(4096)
+            .with_read_ahead(8192);
+
+        let mut bs = vec![0; 16];
+        for pos in [0, 100, 10, 6000, 4000, 512 * 1024, 10] {
+            r.seek(SeekFrom::Start(pos))?;
+            r.read_exact(&mut bs)?;
+            let start = 1024 + pos as usize;
+            assert_eq!(bs, content[start..start + 16], "pos {}", pos);
+        }
+
+        let n = r.seek(SeekFrom::End(0))?;
+        assert_eq!(n, 1023 * 1024);
+        assert_eq!(r.read(&mut bs)?, 0);
+
+        Ok(())
+    }
+}
@@ -14,6 +14,7 @@
 
 //! This mod provides compress support for BytesWrite and decompress support for BytesRead.
 
+use std::io::BufRead;
 use std::io::Result;
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -29,6 +30,8 @@ use async_compression::codec::LzmaDecoder;
 use async_compression::codec::XzDecoder;
 use async_compression::codec::ZlibDecoder;
 use async_compression::codec::ZstdDecoder;
+use async_compression::futures::bufread;
+use async_compression::futures::write;
 use async_compression::util::PartialBuffer;
 use bytes::Buf;
 use bytes::BytesMut;
@@ -38,7 +41,9 @@ use futures::ready;
 use log::debug;
 use pin_project::pin_project;
 
+use crate::BlockingBytesRead;
 use crate::BytesRead;
+use crate::BytesWrite;
 
 /// CompressAlgorithm represents all compress algorithm that OpenDAL supports.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -319,6 +324,14 @@ impl DecompressDecoder {
         self.state
     }
 
+    /// Get the size of input that has been filled but not consumed yet.
+    ///
+    /// After decoder reached [`DecompressState::Done`], this is the size of
+    /// data that following current member/frame.
+    pub(crate) fn unconsumed(&self) -> usize {
+        self.buf.len()
+    }
+
     /// Fetch more data from underlying reader.
     ///
     /// # Notes
@@ -482,6 +495,279 @@ impl<R: BytesRead> futures::io::AsyncRead for DecompressReader<R> {
     }
 }
 
+/// BlockingDecompressReader provides blocking decompress support for opendal.
+///
+/// This is the blocking version of [`DecompressReader`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::Cursor;
+/// use std::io::Read;
+///
+/// use opendal::io_util::BlockingDecompressReader;
+/// use opendal::io_util::CompressAlgorithm;
+/// # use std::io::Result;
+///
+/// # fn main() -> Result<()> {
+/// let content = vec![0; 16 * 1024 * 1024];
+/// let mut cr = BlockingDecompressReader::new(Cursor::new(content), CompressAlgorithm::Gzip);
+/// let mut result = vec![];
+/// cr.read_to_end(&mut result)?;
+/// # Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BlockingDecompressReader<R: BlockingBytesRead> {
+    reader: std::io::BufReader<R>,
+    decoder: DecompressDecoder,
+}
+
+impl<R: BlockingBytesRead> BlockingDecompressReader<R> {
+    /// Create a new BlockingDecompressReader.
+    pub fn new(reader: R, algo: CompressAlgorithm) -> Self {
+        Self {
+            reader: std::io::BufReader::new(reader),
+            decoder: DecompressDecoder::new(algo),
+        }
+    }
+}
+
+impl<R: BlockingBytesRead> std::io::Read for BlockingDecompressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            match self.decoder.state() {
+                DecompressState::Reading => {
+                    let input = self.reader.fill_buf()?;
+                    let read = self.decoder.fill(input);
+                    self.reader.consume(read);
+                }
+                DecompressState::Decoding => {
+                    let written = self.decoder.decode(buf)?;
+                    if written != 0 {
+                        return Ok(written);
+                    }
+                }
+                DecompressState::Flushing => {
+                    let written = self.decoder.finish(buf)?;
+                    if written != 0 {
+                        return Ok(written);
+                    }
+                }
+                DecompressState::Done => return Ok(0),
+            }
+        }
+    }
+}
+
+/// CompressReader provides async compress support for opendal: read data
+/// from underlying reader and returns the compressed content.
+///
+/// # Examples
+///
+/// ```no_run
+/// use futures::io::Cursor;
+/// use opendal::io_util::CompressAlgorithm;
+/// use opendal::io_util::CompressReader;
+/// # use std::io::Result;
+/// # use futures::AsyncReadExt;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let content = vec![0; 16 * 1024 * 1024];
+/// let mut cr = CompressReader::new(Cursor::new(content), CompressAlgorithm::Gzip);
+/// let mut result = vec![];
+/// cr.read_to_end(&mut result).await?;
+/// # Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CompressReader<R: BytesRead> {
+    inner: CompressReaderInner<R>,
+}
+
+#[derive(Debug)]
+enum CompressReaderInner<R: BytesRead> {
+    Brotli(Box<bufread::BrotliEncoder<BufReader<R>>>),
+    Bz2(bufread::BzEncoder<BufReader<R>>),
+    Deflate(bufread::DeflateEncoder<BufReader<R>>),
+    Gzip(bufread::GzipEncoder<BufReader<R>>),
+    Lzma(bufread::LzmaEncoder<BufReader<R>>),
+    Xz(bufread::XzEncoder<BufReader<R>>),
+    Zlib(bufread::ZlibEncoder<BufReader<R>>),
+    Zstd(bufread::ZstdEncoder<BufReader<R>>),
+}
+
+impl<R: BytesRead> CompressReader<R> {
+    /// Create a new CompressReader.
+    pub fn new(reader: R, algo: CompressAlgorithm) -> Self {
+        let r = BufReader::new(reader);
+        let inner = match algo {
+            CompressAlgorithm::Brotli => {
+                CompressReaderInner::Brotli(Box::new(bufread::BrotliEncoder::new(r)))
+            }
+            CompressAlgorithm::Bz2 => CompressReaderInner::Bz2(bufread::BzEncoder::new(r)),
+            CompressAlgorithm::Deflate => {
+                CompressReaderInner::Deflate(bufread::DeflateEncoder::new(r))
+            }
+            CompressAlgorithm::Gzip => CompressReaderInner::Gzip(bufread::GzipEncoder::new(r)),
+            CompressAlgorithm::Lzma => CompressReaderInner::Lzma(bufread::LzmaEncoder::new(r)),
+            CompressAlgorithm::Xz => CompressReaderInner::Xz(bufread::XzEncoder::new(r)),
+            CompressAlgorithm::Zlib => CompressReaderInner::Zlib(bufread::ZlibEncoder::new(r)),
+            CompressAlgorithm::Zstd => CompressReaderInner::Zstd(bufread::ZstdEncoder::new(r)),
+        };
+
+        Self { inner }
+    }
+}
+
+impl<R: BytesRead> futures::io::AsyncRead for CompressReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        match &mut self.inner {
+            CompressReaderInner::Brotli(r) => Pin::new(r.as_mut()).poll_read(cx, buf),
+            CompressReaderInner::Bz2(r) => Pin::new(r).poll_read(cx, buf),
+            CompressReaderInner::Deflate(r) => Pin::new(r).poll_read(cx, buf),
+            CompressReaderInner::Gzip(r) => Pin::new(r).poll_read(cx, buf),
+            CompressReaderInner::Lzma(r) => Pin::new(r).poll_read(cx, buf),
+            CompressReaderInner::Xz(r) => Pin::new(r).poll_read(cx, buf),
+            CompressReaderInner::Zlib(r) => Pin::new(r).poll_read(cx, buf),
+            CompressReaderInner::Zstd(r) => Pin::new(r).poll_read(cx, buf),
+        }
+    }
+}
+
+/// CompressWriter provides async compress support for opendal: compress the
+/// written data and write them into underlying writer.
+///
+/// # Notes
+///
+/// Users MUST call `close` to make sure all compressed data has been written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use futures::io::Cursor;
+/// use opendal::io_util::CompressAlgorithm;
+/// use opendal::io_util::CompressWriter;
+/// # use std::io::Result;
+/// # use futures::AsyncWriteExt;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let mut w = CompressWriter::new(Cursor::new(vec![]), CompressAlgorithm::Gzip);
+/// w.write_all(&vec![0; 16 * 1024 * 1024]).await?;
+/// w.close().await?;
+/// let compressed = w.into_inner().into_inner();
+/// # Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CompressWriter<W: BytesWrite> {
+    inner: CompressWriterInner<W>,
+}
+
+#[derive(Debug)]
+enum CompressWriterInner<W: BytesWrite> {
+    Brotli(Box<write::BrotliEncoder<W>>),
+    Bz2(write::BzEncoder<W>),
+    Deflate(write::DeflateEncoder<W>),
+    Gzip(write::GzipEncoder<W>),
+    Lzma(write::LzmaEncoder<W>),
+    Xz(write::XzEncoder<W>),
+    Zlib(write::ZlibEncoder<W>),
+    Zstd(write::ZstdEncoder<W>),
+}
+
+impl<W: BytesWrite> CompressWriter<W> {
+    /// Create a new CompressWriter.
+    pub fn new(writer: W, algo: CompressAlgorithm) -> Self {
+        let w = writer;
+        let inner = match algo {
+            CompressAlgorithm::Brotli => {
+                CompressWriterInner::Brotli(Box::new(write::BrotliEncoder::new(w)))
+            }
+            CompressAlgorithm::Bz2 => CompressWriterInner::Bz2(write::BzEncoder::new(w)),
+            CompressAlgorithm::Deflate => {
+                CompressWriterInner::Deflate(write::DeflateEncoder::new(w))
+            }
+            CompressAlgorithm::Gzip => CompressWriterInner::Gzip(write::GzipEncoder::new(w)),
+            CompressAlgorithm::Lzma => CompressWriterInner::Lzma(write::LzmaEncoder::new(w)),
+            CompressAlgorithm::Xz => CompressWriterInner::Xz(write::XzEncoder::new(w)),
+            CompressAlgorithm::Zlib => CompressWriterInner::Zlib(write::ZlibEncoder::new(w)),
+            CompressAlgorithm::Zstd => CompressWriterInner::Zstd(write::ZstdEncoder::new(w)),
+        };
+
+        Self { inner }
+    }
+
+    /// Consume this writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        match self.inner {
+            CompressWriterInner::Brotli(w) => w.into_inner(),
+            CompressWriterInner::Bz2(w) => w.into_inner(),
+            CompressWriterInner::Deflate(w) => w.into_inner(),
+            CompressWriterInner::Gzip(w) => w.into_inner(),
+            CompressWriterInner::Lzma(w) => w.into_inner(),
+            CompressWriterInner::Xz(w) => w.into_inner(),
+            CompressWriterInner::Zlib(w) => w.into_inner(),
+            CompressWriterInner::Zstd(w) => w.into_inner(),
+        }
+    }
+}
+
+impl<W: BytesWrite> futures::io::AsyncWrite for CompressWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize>> {
+        match &mut self.inner {
+            CompressWriterInner::Brotli(w) => Pin::new(w.as_mut()).poll_write(cx, buf),
+            CompressWriterInner::Bz2(w) => Pin::new(w).poll_write(cx, buf),
+            CompressWriterInner::Deflate(w) => Pin::new(w).poll_write(cx, buf),
+            CompressWriterInner::Gzip(w) => Pin::new(w).poll_write(cx, buf),
+            CompressWriterInner::Lzma(w) => Pin::new(w).poll_write(cx, buf),
+            CompressWriterInner::Xz(w) => Pin::new(w).poll_write(cx, buf),
+            CompressWriterInner::Zlib(w) => Pin::new(w).poll_write(cx, buf),
+            CompressWriterInner::Zstd(w) => Pin::new(w).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match &mut self.inner {
+            CompressWriterInner::Brotli(w) => Pin::new(w.as_mut()).poll_flush(cx),
+            CompressWriterInner::Bz2(w) => Pin::new(w).poll_flush(cx),
+            CompressWriterInner::Deflate(w) => Pin::new(w).poll_flush(cx),
+            CompressWriterInner::Gzip(w) => Pin::new(w).poll_flush(cx),
+            CompressWriterInner::Lzma(w) => Pin::new(w).poll_flush(cx),
+            CompressWriterInner::Xz(w) => Pin::new(w).poll_flush(cx),
+            CompressWriterInner::Zlib(w) => Pin::new(w).poll_flush(cx),
+            CompressWriterInner::Zstd(w) => Pin::new(w).poll_flush(cx),
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match &mut self.inner {
+            CompressWriterInner::Brotli(w) => Pin::new(w.as_mut()).poll_close(cx),
+            CompressWriterInner::Bz2(w) => Pin::new(w).poll_close(cx),
+            CompressWriterInner::Deflate(w) => Pin::new(w).poll_close(cx),
+            CompressWriterInner::Gzip(w) => Pin::new(w).poll_close(cx),
+            CompressWriterInner::Lzma(w) => Pin::new(w).poll_close(cx),
+            CompressWriterInner::Xz(w) => Pin::new(w).poll_close(cx),
+            CompressWriterInner::Zlib(w) => Pin::new(w).poll_close(cx),
+            CompressWriterInner::Zstd(w) => Pin::new(w).poll_close(cx),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::min;
@@ -493,6 +779,7 @@ mod tests {
     use async_compression::futures::bufread::ZlibEncoder;
     use futures::io::Cursor;
     use futures::AsyncReadExt;
+    use futures::AsyncWriteExt;
     use rand::prelude::*;
     use sha2::Digest;
     use sha2::Sha256;
@@ -694,6 +981,123 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_blocking_decompress_reader() -> Result<()> {
+        let _ = env_logger::try_init();
+
+        let mut rng = ThreadRng::default();
+        let mut content = vec![0; 1024 * 1024];
+        rng.fill_bytes(&mut content);
+
+        for algo in [
+            CompressAlgorithm::Brotli,
+            CompressAlgorithm::Bz2,
+            CompressAlgorithm::Deflate,
+            CompressAlgorithm::Gzip,
+            CompressAlgorithm::Lzma,
+            CompressAlgorithm::Xz,
+            CompressAlgorithm::Zlib,
+            CompressAlgorithm::Zstd,
+        ] {
+            let mut cr = CompressReader::new(Cursor::new(content.clone()), algo);
+            let mut compressed_content = vec![];
+            cr.read_to_end(&mut compressed_content).await?;
+
+            let mut dr =
+                BlockingDecompressReader::new(std::io::Cursor::new(compressed_content), algo);
+            let mut result = vec![];
+            std::io::Read::read_to_end(&mut dr, &mut result)?;
+
+            assert_eq!(result, content, "{:?}", algo);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_blocking_decompress_reader_ontime_gzip() -> Result<()> {
+        let _ = env_logger::try_init();
+
+        let content = fs::read(format!(
+            "{}/testdata/ontime.csv",
+            env::current_dir()?.to_string_lossy()
+        ))?;
+        let compressed_content = fs::read(format!(
+            "{}/testdata/ontime.csv.gz",
+            env::current_dir()?.to_string_lossy()
+        ))?;
+
+        let mut cr = BlockingDecompressReader::new(
+            std::io::Cursor::new(compressed_content),
+            CompressAlgorithm::Gzip,
+        );
+
+        let mut result = vec![];
+        std::io::Read::read_to_end(&mut cr, &mut result)?;
+
+        assert_eq!(result, content);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compress_reader() -> Result<()> {
+        let _ = env_logger::try_init();
+
+        let mut rng = ThreadRng::default();
+        let mut content = vec![0; 1024 * 1024];
+        rng.fill_bytes(&mut content);
+
+        for algo in [
+            CompressAlgorithm::Brotli,
+            CompressAlgorithm::Bz2,
+            CompressAlgorithm::Deflate,
+            CompressAlgorithm::Gzip,
+            CompressAlgorithm::Lzma,
+            CompressAlgorithm::Xz,
+            CompressAlgorithm::Zlib,
+            CompressAlgorithm::Zstd,
+        ] {
+            let mut cr = CompressReader::new(Cursor::new(content.clone()), algo);
+            let mut compressed_content = vec![];
+            cr.read_to_end(&mut compressed_content).await?;
+
+            let mut dr = DecompressReader::new(Cursor::new(compressed_content), algo);
+            let mut result = vec![];
+            dr.read_to_end(&mut result).await?;
+
+            assert_eq!(result, content, "{:?}", algo);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compress_writer_gzip() -> Result<()> {
+        let _ = env_logger::try_init();
+
+        let mut rng = ThreadRng::default();
+        let mut content = vec![0; 1024 * 1024];
+        rng.fill_bytes(&mut content);
+
+        let mut w = CompressWriter::new(Cursor::new(vec![]), CompressAlgorithm::Gzip);
+        // Write in small chunks to make sure data is buffered correctly.
+        for chunk in content.chunks(4 * 1024) {
+            w.write_all(chunk).await?;
+        }
+        w.close().await?;
+        let compressed_content = w.into_inner().into_inner();
+
+        let mut dr =
+            DecompressReader::new(Cursor::new(compressed_content), CompressAlgorithm::Gzip);
+        let mut result = vec![];
+        dr.read_to_end(&mut result).await?;
+
+        assert_eq!(result, content);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_decompress_reader_ontime_gzip() -> Result<()> {
         let _ = env_logger::try_init();
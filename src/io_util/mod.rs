@@ -40,11 +40,29 @@ mod seekable_reader;
 pub use seekable_reader::seekable_read;
 pub use seekable_reader::SeekableReader;
 
+mod blocking_seekable_reader;
+pub use blocking_seekable_reader::blocking_seekable_read;
+pub use blocking_seekable_reader::BlockingSeekableReader;
+
+mod parallel_reader;
+pub use parallel_reader::parallel_read;
+pub use parallel_reader::ParallelReader;
+
+mod ranges;
+pub(crate) use ranges::merge_ranges;
+pub(crate) use ranges::split_ranges;
+
 #[cfg(feature = "compress")]
 mod compress;
 #[cfg(feature = "compress")]
+pub use compress::BlockingDecompressReader;
+#[cfg(feature = "compress")]
 pub use compress::CompressAlgorithm;
 #[cfg(feature = "compress")]
+pub use compress::CompressReader;
+#[cfg(feature = "compress")]
+pub use compress::CompressWriter;
+#[cfg(feature = "compress")]
 pub use compress::DecompressCodec;
 #[cfg(feature = "compress")]
 pub use compress::DecompressDecoder;
@@ -53,6 +71,15 @@ pub use compress::DecompressReader;
 #[cfg(feature = "compress")]
 pub use compress::DecompressState;
 
+#[cfg(feature = "compress")]
+mod seekable_decompress_reader;
+#[cfg(feature = "compress")]
+pub use seekable_decompress_reader::seekable_decompress_read;
+#[cfg(feature = "compress")]
+pub use seekable_decompress_reader::SeekableCompressFormat;
+#[cfg(feature = "compress")]
+pub use seekable_decompress_reader::SeekableDecompressReader;
+
 mod walk;
 pub use walk::BottomUpWalker;
 pub use walk::TopDownWalker;
@@ -0,0 +1,339 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use bytes::Buf;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::ready;
+use futures::stream::FuturesOrdered;
+use futures::AsyncRead;
+use futures::AsyncReadExt;
+use futures::StreamExt;
+
+use crate::error::is_temporary_error;
+use crate::error::ObjectError;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::Operation;
+use crate::Accessor;
+use crate::Object;
+
+/// Default max retries for every chunk of [`ParallelReader`].
+const DEFAULT_MAX_RETRIES: usize = 3;
+/// Delay before the first retry, doubled for each following retry.
+const RETRY_MIN_DELAY: Duration = Duration::from_millis(100);
+/// Max delay between retries.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Create a reader which fetches object in chunks concurrently.
+///
+/// The object will be stat first to get its content length and etag:
+///
+/// - Chunks are fetched via range reads, at most `concurrency` chunks will
+///   be fetched at the same time.
+/// - Chunks are returned in order, so memory usage is bounded by
+///   `(concurrency + 1) * chunk_size`.
+/// - Every chunk will be retried individually on temporary errors with
+///   exponential backoff.
+/// - The etag will be pinned via `If-Match`, so that a concurrent overwrite
+///   will be detected on services that support conditional read.
+///
+/// # Example
+///
+/// ```no_run
+/// # use opendal::Operator;
+/// # use opendal::Scheme;
+/// # use anyhow::Result;
+/// use futures::io;
+/// use opendal::io_util::parallel_read;
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let op = Operator::from_env(Scheme::Memory)?;
+/// let o = op.object("test");
+/// let r = parallel_read(&o, 8 * 1024 * 1024, 8).await?;
+/// io::copy(r, &mut io::sink()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn parallel_read(
+    o: &Object,
+    chunk_size: u64,
+    concurrency: usize,
+) -> Result<ParallelReader> {
+    if chunk_size == 0 || concurrency == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            ObjectError::new(
+                Operation::Read,
+                o.path(),
+                anyhow!("chunk_size and concurrency must be larger than 0"),
+            ),
+        ));
+    }
+
+    let acc = o.accessor();
+    let meta = acc.stat(&OpStat::new(o.path())?).await?;
+
+    Ok(ParallelReader {
+        acc,
+        path: o.path().to_string(),
+        etag: meta.etag().map(|v| v.to_string()),
+        size: meta.content_length(),
+        chunk_size,
+        concurrency,
+        max_retries: DEFAULT_MAX_RETRIES,
+
+        next_offset: 0,
+        chunks: FuturesOrdered::new(),
+        chunk: Bytes::new(),
+    })
+}
+
+/// ParallelReader implement `AsyncRead` by fetching chunks concurrently.
+///
+/// Created by [`parallel_read`].
+pub struct ParallelReader {
+    acc: Arc<dyn Accessor>,
+    path: String,
+    etag: Option<String>,
+    size: u64,
+    chunk_size: u64,
+    concurrency: usize,
+    max_retries: usize,
+
+    /// Offset of the next chunk to fetch.
+    next_offset: u64,
+    chunks: FuturesOrdered<BoxFuture<'static, Result<Bytes>>>,
+    /// Current chunk which is being consumed.
+    chunk: Bytes,
+}
+
+impl ParallelReader {
+    /// Set the max retries for every chunk.
+    ///
+    /// Only temporary errors will be retried.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Content length of the object.
+    pub fn content_length(&self) -> u64 {
+        self.size
+    }
+
+    /// Start fetching chunks until reaching the concurrency limit.
+    fn schedule(&mut self) {
+        while self.chunks.len() < self.concurrency && self.next_offset < self.size {
+            let offset = self.next_offset;
+            let size = self.chunk_size.min(self.size - offset);
+            self.next_offset += size;
+
+            let fut = fetch_chunk(
+                self.acc.clone(),
+                self.path.clone(),
+                self.etag.clone(),
+                offset,
+                size,
+                self.max_retries,
+            );
+            self.chunks.push_back(Box::pin(fut));
+        }
+    }
+}
+
+async fn fetch_chunk(
+    acc: Arc<dyn Accessor>,
+    path: String,
+    etag: Option<String>,
+    offset: u64,
+    size: u64,
+    max_retries: usize,
+) -> Result<Bytes> {
+    let mut retries = 0;
+    let mut delay = RETRY_MIN_DELAY;
+    loop {
+        let mut op = OpRead::new_with_offset(&path, Some(offset), Some(size))?;
+        if let Some(etag) = &etag {
+            op = op.with_if_match(etag);
+        }
+
+        let res = async {
+            let mut r = acc.read(&op).await?;
+            let mut bs = Vec::with_capacity(size as usize);
+            r.read_to_end(&mut bs).await?;
+            Ok::<_, Error>(bs)
+        }
+        .await;
+
+        match res {
+            Ok(bs) if bs.len() as u64 == size => return Ok(Bytes::from(bs)),
+            // The object has been truncated while reading.
+            Ok(bs) => {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    ObjectError::new(
+                        Operation::Read,
+                        &path,
+                        anyhow!(
+                            "chunk at {offset} expected {size} bytes, but got {}",
+                            bs.len()
+                        ),
+                    ),
+                ))
+            }
+            Err(e) if is_temporary_error(&e) && retries < max_retries => {
+                retries += 1;
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(RETRY_MAX_DELAY);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+impl AsyncRead for ParallelReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        loop {
+            if self.chunk.has_remaining() || buf.is_empty() {
+                let n = buf.len().min(self.chunk.remaining());
+                self.chunk.copy_to_slice(&mut buf[..n]);
+                return Poll::Ready(Ok(n));
+            }
+
+            self.schedule();
+            match ready!(self.chunks.poll_next_unpin(cx)) {
+                Some(Ok(bs)) => self.chunk = bs,
+                Some(Err(e)) => {
+                    // Stop fetching the following chunks.
+                    self.chunks = FuturesOrdered::new();
+                    self.next_offset = self.size;
+                    return Poll::Ready(Err(e));
+                }
+                None => return Poll::Ready(Ok(0)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use async_trait::async_trait;
+    use futures::io::Cursor;
+
+    use super::*;
+    use crate::BytesReader;
+    use crate::Layer;
+    use crate::ObjectMetadata;
+    use crate::Operator;
+    use crate::Scheme;
+
+    /// Fail the first read of every chunk with a temporary error.
+    #[derive(Debug, Clone, Default)]
+    struct FlakyLayer {
+        reads: Arc<AtomicUsize>,
+    }
+
+    impl Layer for FlakyLayer {
+        fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+            Arc::new(FlakyAccessor {
+                inner,
+                reads: self.reads.clone(),
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct FlakyAccessor {
+        inner: Arc<dyn Accessor>,
+        reads: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Accessor for FlakyAccessor {
+        async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+            if self.reads.fetch_add(1, Ordering::SeqCst) & 1 == 0 {
+                return Err(Error::new(ErrorKind::Interrupted, anyhow!("flaky")));
+            }
+            self.inner.read(args).await
+        }
+
+        async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+            self.inner.stat(args).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parallel_read() -> anyhow::Result<()> {
+        let op = Operator::from_env(Scheme::Memory)?;
+        let content: Vec<u8> = (0..1024 * 1024 + 7).map(|v| v as u8).collect();
+        op.object("test").write(content.clone()).await?;
+
+        for (chunk_size, concurrency) in [(1000, 3), (64 * 1024, 4), (4 << 20, 2)] {
+            let r = parallel_read(&op.object("test"), chunk_size, concurrency).await?;
+            assert_eq!(r.content_length(), content.len() as u64);
+            let mut bs = Cursor::new(Vec::new());
+            futures::io::copy(r, &mut bs).await?;
+            assert_eq!(bs.into_inner(), content, "chunk_size {}", chunk_size);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parallel_read_retry() -> anyhow::Result<()> {
+        let op = Operator::from_env(Scheme::Memory)?;
+        let content: Vec<u8> = (0..4096).map(|v| v as u8).collect();
+        op.object("test").write(content.clone()).await?;
+
+        let layer = FlakyLayer::default();
+        let op = op.layer(layer.clone());
+
+        // Every chunk will succeed after one retry with backoff.
+        let start = std::time::Instant::now();
+        let r = parallel_read(&op.object("test"), 1024, 1).await?;
+        let mut bs = Cursor::new(Vec::new());
+        futures::io::copy(r, &mut bs).await?;
+        assert_eq!(bs.into_inner(), content);
+        assert_eq!(layer.reads.load(Ordering::SeqCst), 8);
+        assert!(start.elapsed() >= RETRY_MIN_DELAY * 4);
+
+        // Retry disabled.
+        let r = parallel_read(&op.object("test"), 1024, 1)
+            .await?
+            .with_max_retries(0);
+        let mut bs = Cursor::new(Vec::new());
+        let err = futures::io::copy(r, &mut bs).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+/// Merge ranges whose distance is not larger than `gap` into one range.
+///
+/// Empty ranges will be ignored. The returning ranges are sorted by start
+/// and never overlap with each other.
+pub(crate) fn merge_ranges(ranges: &[Range<u64>], gap: u64) -> Vec<Range<u64>> {
+    let mut sorted: Vec<Range<u64>> = ranges.iter().filter(|r| r.start < r.end).cloned().collect();
+    sorted.sort_by_key(|r| r.start);
+
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(sorted.len());
+    for r in sorted {
+        match merged.last_mut() {
+            Some(last) if r.start <= last.end.saturating_add(gap) => {
+                last.end = last.end.max(r.end);
+            }
+            _ => merged.push(r),
+        }
+    }
+
+    merged
+}
+
+/// Split the content of merged ranges back into the requested ranges.
+///
+/// `merged` must be returned by [`merge_ranges`] with the same `ranges`,
+/// and `contents` must be the contents of `merged` in the same order.
+///
+/// If the content of a merged range is shorter than expected (for example,
+/// the range is beyond the end of object), the requested range will be
+/// truncated like what `range_read` does.
+pub(crate) fn split_ranges(
+    ranges: &[Range<u64>],
+    merged: &[Range<u64>],
+    contents: &[Vec<u8>],
+) -> Vec<Vec<u8>> {
+    debug_assert_eq!(merged.len(), contents.len());
+
+    ranges
+        .iter()
+        .map(|r| {
+            if r.start >= r.end {
+                return Vec::new();
+            }
+
+            // Find the last merged range which starts before `r.start`.
+            let idx = merged.partition_point(|m| m.start <= r.start) - 1;
+            let (m, bs) = (&merged[idx], &contents[idx]);
+            debug_assert!(m.start <= r.start && r.end <= m.end);
+
+            let start = ((r.start - m.start) as usize).min(bs.len());
+            let end = ((r.end - m.start) as usize).min(bs.len());
+            bs[start..end].to_vec()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_ranges(v: &[(u64, u64)]) -> Vec<Range<u64>> {
+        v.iter().map(|(start, end)| *start..*end).collect()
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        let cases = vec![
+            ("empty", vec![], 0, vec![]),
+            ("single", vec![(0, 10)], 0, vec![(0, 10)]),
+            ("ignore empty", vec![(0, 10), (5, 5)], 0, vec![(0, 10)]),
+            ("adjacent", vec![(0, 10), (10, 20)], 0, vec![(0, 20)]),
+            (
+                "not merged",
+                vec![(0, 10), (11, 20)],
+                0,
+                vec![(0, 10), (11, 20)],
+            ),
+            ("in gap", vec![(0, 10), (15, 20)], 5, vec![(0, 20)]),
+            (
+                "out of gap",
+                vec![(0, 10), (16, 20)],
+                5,
+                vec![(0, 10), (16, 20)],
+            ),
+            ("overlap", vec![(0, 10), (5, 8), (6, 12)], 0, vec![(0, 12)]),
+            (
+                "unsorted",
+                vec![(30, 40), (0, 10), (12, 20)],
+                2,
+                vec![(0, 20), (30, 40)],
+            ),
+        ];
+
+        for (name, input, gap, expected) in cases {
+            assert_eq!(
+                merge_ranges(&to_ranges(&input), gap),
+                to_ranges(&expected),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_split_ranges() {
+        let content: Vec<u8> = (0..100).collect();
+
+        let ranges = vec![30..40, 0..10, 12..20, 5..5, 95..110];
+        let merged = merge_ranges(&ranges, 2);
+        assert_eq!(merged, vec![0..20, 30..40, 95..110]);
+
+        let contents = merged
+            .iter()
+            .map(|m| content[m.start as usize..(m.end as usize).min(content.len())].to_vec())
+            .collect::<Vec<_>>();
+
+        let actual = split_ranges(&ranges, &merged, &contents);
+        assert_eq!(
+            actual,
+            vec![
+                content[30..40].to_vec(),
+                content[0..10].to_vec(),
+                content[12..20].to_vec(),
+                vec![],
+                content[95..100].to_vec(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,821 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::min;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::io::SeekFrom;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::io;
+use futures::io::Cursor;
+use futures::ready;
+use futures::AsyncRead;
+use futures::AsyncReadExt;
+use futures::AsyncSeek;
+
+use super::CompressAlgorithm;
+use super::DecompressDecoder;
+use super::DecompressState;
+use crate::error::ObjectError;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::Operation;
+use crate::Accessor;
+use crate::Object;
+
+/// Magic number at the end of zstd seek table.
+const ZSTD_SEEKABLE_MAGIC: u32 = 0x8F92_EAB1;
+/// Magic number of the skippable frame which contains zstd seek table.
+const ZSTD_SKIPPABLE_MAGIC: u32 = 0x184D_2A5E;
+/// Size of zstd seek table footer.
+const ZSTD_SEEK_TABLE_FOOTER_SIZE: u64 = 9;
+/// Size of zstd skippable frame header.
+const ZSTD_SKIPPABLE_HEADER_SIZE: u64 = 8;
+/// Size of the fixed part of gzip header, including `XLEN`.
+const GZIP_FIXED_HEADER_SIZE: u64 = 12;
+
+/// Compressed formats that can be decompressed from the middle.
+///
+/// All of them split content into independent frames and provide an index
+/// of frames, so that we can only decompress the frames we need.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SeekableCompressFormat {
+    /// [Zstd seekable format](https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md).
+    ///
+    /// Seek table will be read from the end of object.
+    ZstdSeekable,
+    /// [BGZF](https://samtools.github.io/hts-specs/SAMv1.pdf) format used by samtools.
+    ///
+    /// Index will be read from `<path>.gzi` if exists. Otherwise, we will
+    /// read the whole object once to build index from block headers.
+    Bgzf,
+    /// Multi-member gzip with a `.gzi` index.
+    ///
+    /// Index will be read from `<path>.gzi` which shares the same layout of
+    /// BGZF index: a little-endian `u64` count followed by pairs of
+    /// `(compressed_offset, uncompressed_offset)` for every member except
+    /// the first one.
+    Gzip,
+}
+
+impl SeekableCompressFormat {
+    fn algorithm(&self) -> CompressAlgorithm {
+        match self {
+            SeekableCompressFormat::ZstdSeekable => CompressAlgorithm::Zstd,
+            SeekableCompressFormat::Bgzf | SeekableCompressFormat::Gzip => CompressAlgorithm::Gzip,
+        }
+    }
+}
+
+/// Frame is an independent compressed unit.
+#[derive(Copy, Clone, Debug)]
+struct Frame {
+    c_offset: u64,
+    c_size: u64,
+    d_offset: u64,
+    d_size: u64,
+}
+
+/// Add seek support for compressed object via frame index.
+///
+/// Only the frame that containing current position will be read and
+/// decompressed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use opendal::Operator;
+/// # use opendal::Scheme;
+/// # use anyhow::Result;
+/// # use futures::{AsyncReadExt, AsyncSeekExt};
+/// use std::io::SeekFrom;
+///
+/// use opendal::io_util::seekable_decompress_read;
+/// use opendal::io_util::SeekableCompressFormat;
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let op = Operator::from_env(Scheme::Memory)?;
+/// let o = op.object("ontime.csv.zst");
+/// let mut r = seekable_decompress_read(&o, SeekableCompressFormat::ZstdSeekable).await?;
+/// r.seek(SeekFrom::Start(1024 * 1024)).await?;
+/// let mut bs = vec![0; 10];
+/// r.read(&mut bs).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn seekable_decompress_read(
+    o: &Object,
+    format: SeekableCompressFormat,
+) -> Result<SeekableDecompressReader> {
+    let acc = o.accessor();
+    let path = o.path().to_string();
+    let algo = format.algorithm();
+
+    let total = acc.stat(&OpStat::new(&path)?).await?.content_length();
+
+    let (frames, frame) = match format {
+        SeekableCompressFormat::ZstdSeekable => {
+            (zstd_seekable_frames(&acc, &path, total).await?, None)
+        }
+        SeekableCompressFormat::Bgzf => match read_gzi(&acc, &path).await {
+            Ok(index) => gzi_frames(&acc, &path, total, &index).await?,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                (bgzf_scan_frames(&acc, &path).await?, None)
+            }
+            Err(err) => return Err(err),
+        },
+        SeekableCompressFormat::Gzip => {
+            let index = read_gzi(&acc, &path).await?;
+            gzi_frames(&acc, &path, total, &index).await?
+        }
+    };
+
+    let size = frames.last().map(|f| f.d_offset + f.d_size).unwrap_or(0);
+
+    Ok(SeekableDecompressReader {
+        acc,
+        path,
+        algo,
+        frames,
+        size,
+        pos: 0,
+        frame,
+        state: State::Idle,
+    })
+}
+
+/// SeekableDecompressReader implement `AsyncRead` and `AsyncSeek` over the
+/// decompressed content.
+///
+/// The latest decompressed frame will be kept in memory, reads inside it
+/// will not trigger new read operation.
+pub struct SeekableDecompressReader {
+    acc: Arc<dyn Accessor>,
+    path: String,
+    algo: CompressAlgorithm,
+    /// Frames with content, sorted by offset.
+    frames: Vec<Frame>,
+    size: u64,
+
+    pos: u64,
+    /// The index and content of the latest decompressed frame.
+    frame: Option<(usize, Bytes)>,
+    state: State,
+}
+
+enum State {
+    Idle,
+    Fetching(usize, BoxFuture<'static, Result<Bytes>>),
+}
+
+impl SeekableDecompressReader {
+    /// Get the size of decompressed content.
+    pub fn content_length(&self) -> u64 {
+        self.size
+    }
+}
+
+impl AsyncRead for SeekableDecompressReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        loop {
+            if this.pos >= this.size {
+                return Poll::Ready(Ok(0));
+            }
+
+            if let Some((idx, bs)) = &this.frame {
+                let f = this.frames[*idx];
+                if f.d_offset <= this.pos && this.pos < f.d_offset + bs.len() as u64 {
+                    let start = (this.pos - f.d_offset) as usize;
+                    let n = min(buf.len(), bs.len() - start);
+                    buf[..n].copy_from_slice(&bs[start..start + n]);
+                    this.pos += n as u64;
+                    return Poll::Ready(Ok(n));
+                }
+            }
+
+            match &mut this.state {
+                State::Idle => {
+                    let pos = this.pos;
+                    let idx = this.frames.partition_point(|f| f.d_offset <= pos) - 1;
+                    let future = fetch_frame(
+                        this.acc.clone(),
+                        this.path.clone(),
+                        this.algo,
+                        this.frames[idx],
+                    );
+                    this.state = State::Fetching(idx, Box::pin(future));
+                }
+                State::Fetching(idx, future) => {
+                    let idx = *idx;
+                    let res = ready!(future.as_mut().poll(cx));
+                    this.state = State::Idle;
+                    this.frame = Some((idx, res?));
+                }
+            }
+        }
+    }
+}
+
+impl AsyncSeek for SeekableDecompressReader {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<Result<u64>> {
+        let cur = match pos {
+            SeekFrom::Start(off) => off as i64,
+            SeekFrom::Current(off) => self.pos as i64 + off,
+            SeekFrom::End(off) => self.size as i64 + off,
+        };
+
+        if cur < 0 {
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::InvalidInput,
+                anyhow!("invalid seek to a negative position: {cur}"),
+            )));
+        }
+
+        // Frame will be fetched by the next read if needed.
+        self.pos = cur as u64;
+        Poll::Ready(Ok(self.pos))
+    }
+}
+
+fn invalid_data(path: &str, err: anyhow::Error) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        ObjectError::new(Operation::Read, path, err),
+    )
+}
+
+async fn range_read(
+    acc: &Arc<dyn Accessor>,
+    path: &str,
+    offset: u64,
+    size: u64,
+) -> Result<Vec<u8>> {
+    let op = OpRead::new_with_offset(path, Some(offset), Some(size))?;
+    let r = acc.read(&op).await?;
+
+    let mut bs = Cursor::new(Vec::with_capacity(size as usize));
+    io::copy(r, &mut bs).await?;
+    let bs = bs.into_inner();
+
+    if bs.len() as u64 != size {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            ObjectError::new(
+                Operation::Read,
+                path,
+                anyhow!("read short, expect {} actual {}", size, bs.len()),
+            ),
+        ));
+    }
+    Ok(bs)
+}
+
+/// Fetch the compressed frame and decompress it.
+async fn fetch_frame(
+    acc: Arc<dyn Accessor>,
+    path: String,
+    algo: CompressAlgorithm,
+    f: Frame,
+) -> Result<Bytes> {
+    let input = range_read(&acc, &path, f.c_offset, f.c_size).await?;
+    let bs = decompress_frame(algo, &input)?;
+
+    if bs.len() as u64 != f.d_size {
+        return Err(invalid_data(
+            &path,
+            anyhow!(
+                "decompressed size of frame at {} mismatched, expect {} actual {}",
+                f.c_offset,
+                f.d_size,
+                bs.len()
+            ),
+        ));
+    }
+    Ok(Bytes::from(bs))
+}
+
+/// Decompress all members/frames in input.
+fn decompress_frame(algo: CompressAlgorithm, input: &[u8]) -> Result<Vec<u8>> {
+    let mut result = Vec::new();
+    let mut buf = vec![0; 64 * 1024];
+
+    let mut consumed = 0;
+    while consumed < input.len() {
+        let mut decoder = DecompressDecoder::new(algo);
+
+        // Input will be filled in order, and an empty input will be filled
+        // at last to notify decoder about EOF.
+        let mut pending: Vec<&[u8]> = Vec::with_capacity(3);
+        let header;
+        match algo {
+            CompressAlgorithm::Gzip => match strip_gzip_extra(&input[consumed..]) {
+                Some((fixed, skip)) => {
+                    header = fixed;
+                    pending.push(&header);
+                    pending.push(&input[consumed + skip..]);
+                }
+                None => pending.push(&input[consumed..]),
+            },
+            _ => pending.push(&input[consumed..]),
+        }
+        pending.push(&[]);
+        let mut pending = pending.into_iter();
+
+        loop {
+            match decoder.state() {
+                DecompressState::Reading => {
+                    decoder.fill(pending.next().unwrap_or_default());
+                }
+                DecompressState::Decoding => {
+                    let n = decoder.decode(&mut buf)?;
+                    result.extend_from_slice(&buf[..n]);
+                }
+                DecompressState::Flushing => {
+                    let n = decoder.finish(&mut buf)?;
+                    result.extend_from_slice(&buf[..n]);
+                }
+                DecompressState::Done => break,
+            }
+        }
+
+        let next = input.len() - decoder.unconsumed();
+        if next == consumed {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                anyhow!("decompress frame doesn't make progress"),
+            ));
+        }
+        consumed = next;
+    }
+
+    Ok(result)
+}
+
+/// Strip the extra field (`FEXTRA`) from gzip header.
+///
+/// The gzip decoder we used reads `XLEN` in wrong byte order, which breaks
+/// members with extra field like BGZF blocks. Extra field carries no data
+/// we need, so we remove it before decoding.
+///
+/// Return the fixed header without `FEXTRA` flag and the size of header
+/// that has been replaced, or `None` if there is no extra field.
+fn strip_gzip_extra(input: &[u8]) -> Option<([u8; 10], usize)> {
+    let size = GZIP_FIXED_HEADER_SIZE as usize;
+    if input.len() < size || input[0..3] != [0x1f, 0x8b, 0x08] || input[3] & 0x04 == 0 {
+        return None;
+    }
+
+    let xlen = u16::from_le_bytes([input[10], input[11]]) as usize;
+    if input.len() < size + xlen {
+        return None;
+    }
+
+    let mut header = [0; 10];
+    header.copy_from_slice(&input[0..10]);
+    header[3] &= !0x04;
+    Some((header, size + xlen))
+}
+
+/// Build frames from the seek table at the end of zstd seekable object.
+async fn zstd_seekable_frames(
+    acc: &Arc<dyn Accessor>,
+    path: &str,
+    total: u64,
+) -> Result<Vec<Frame>> {
+    if total < ZSTD_SEEK_TABLE_FOOTER_SIZE + ZSTD_SKIPPABLE_HEADER_SIZE {
+        return Err(invalid_data(path, anyhow!("object is too small")));
+    }
+
+    let footer = range_read(
+        acc,
+        path,
+        total - ZSTD_SEEK_TABLE_FOOTER_SIZE,
+        ZSTD_SEEK_TABLE_FOOTER_SIZE,
+    )
+    .await?;
+    let magic = u32::from_le_bytes(footer[5..9].try_into().expect("must be 4 bytes"));
+    if magic != ZSTD_SEEKABLE_MAGIC {
+        return Err(invalid_data(path, anyhow!("seek table is not found")));
+    }
+    let count = u32::from_le_bytes(footer[0..4].try_into().expect("must be 4 bytes")) as u64;
+    // The highest bit of descriptor is checksum flag.
+    let entry_size = if footer[4] & 0x80 != 0 { 12 } else { 8 };
+
+    let table_size = count * entry_size;
+    let frame_size = ZSTD_SKIPPABLE_HEADER_SIZE + table_size + ZSTD_SEEK_TABLE_FOOTER_SIZE;
+    if total < frame_size {
+        return Err(invalid_data(path, anyhow!("seek table is truncated")));
+    }
+
+    let table = range_read(
+        acc,
+        path,
+        total - frame_size,
+        frame_size - ZSTD_SEEK_TABLE_FOOTER_SIZE,
+    )
+    .await?;
+    let magic = u32::from_le_bytes(table[0..4].try_into().expect("must be 4 bytes"));
+    if magic != ZSTD_SKIPPABLE_MAGIC {
+        return Err(invalid_data(path, anyhow!("seek table frame is invalid")));
+    }
+
+    let mut frames = Vec::with_capacity(count as usize);
+    let (mut c_offset, mut d_offset) = (0, 0);
+    for entry in table[ZSTD_SKIPPABLE_HEADER_SIZE as usize..].chunks(entry_size as usize) {
+        let c_size = u32::from_le_bytes(entry[0..4].try_into().expect("must be 4 bytes")) as u64;
+        let d_size = u32::from_le_bytes(entry[4..8].try_into().expect("must be 4 bytes")) as u64;
+        if d_size != 0 {
+            frames.push(Frame {
+                c_offset,
+                c_size,
+                d_offset,
+                d_size,
+            });
+        }
+        c_offset += c_size;
+        d_offset += d_size;
+    }
+
+    if c_offset > total - frame_size {
+        return Err(invalid_data(path, anyhow!("seek table is out of range")));
+    }
+    Ok(frames)
+}
+
+/// Read the `.gzi` index of object.
+async fn read_gzi(acc: &Arc<dyn Accessor>, path: &str) -> Result<Vec<(u64, u64)>> {
+    let index_path = format!("{path}.gzi");
+    let r = acc.read(&OpRead::new(&index_path, ..)?).await?;
+
+    let mut bs = Cursor::new(Vec::new());
+    io::copy(r, &mut bs).await?;
+    let bs = bs.into_inner();
+
+    let invalid = || invalid_data(&index_path, anyhow!("gzi index is invalid"));
+    if bs.len() < 8 {
+        return Err(invalid());
+    }
+    let count = u64::from_le_bytes(bs[0..8].try_into().expect("must be 8 bytes"));
+    if count.checked_mul(16) != Some(bs.len() as u64 - 8) {
+        return Err(invalid());
+    }
+
+    // The first member is not included in index.
+    let mut index = vec![(0, 0)];
+    for entry in bs[8..].chunks(16) {
+        index.push((
+            u64::from_le_bytes(entry[0..8].try_into().expect("must be 8 bytes")),
+            u64::from_le_bytes(entry[8..16].try_into().expect("must be 8 bytes")),
+        ));
+    }
+    Ok(index)
+}
+
+/// Build frames from `.gzi` index.
+///
+/// The uncompressed size of the last member is not recorded in index, so
+/// we have to decompress it. The decompressed content will be returned too.
+async fn gzi_frames(
+    acc: &Arc<dyn Accessor>,
+    path: &str,
+    total: u64,
+    index: &[(u64, u64)],
+) -> Result<(Vec<Frame>, Option<(usize, Bytes)>)> {
+    let mut frames = Vec::with_capacity(index.len());
+    for (i, (c_offset, d_offset)) in index.iter().copied().enumerate() {
+        let (c_end, d_end) = match index.get(i + 1) {
+            Some(v) => *v,
+            None => (total, d_offset),
+        };
+        if c_end < c_offset || d_end < d_offset {
+            return Err(invalid_data(path, anyhow!("gzi index is not sorted")));
+        }
+
+        frames.push(Frame {
+            c_offset,
+            c_size: c_end - c_offset,
+            d_offset,
+            d_size: d_end - d_offset,
+        })
+    }
+
+    let last = frames.last_mut().expect("index must not be empty");
+    let mut content = None;
+    if last.c_size != 0 {
+        let input = range_read(acc, path, last.c_offset, last.c_size).await?;
+        let bs = decompress_frame(CompressAlgorithm::Gzip, &input)?;
+        last.d_size = bs.len() as u64;
+        content = Some(Bytes::from(bs));
+    }
+
+    // Frames without content are useless for reading.
+    let last_idx = frames.len() - 1;
+    let mut result = Vec::with_capacity(frames.len());
+    let mut frame = None;
+    for (i, f) in frames.into_iter().enumerate() {
+        if f.d_size == 0 {
+            continue;
+        }
+        if i == last_idx {
+            frame = content.take().map(|bs| (result.len(), bs));
+        }
+        result.push(f);
+    }
+
+    Ok((result, frame))
+}
+
+/// Build frames by reading BGZF block headers from the whole object.
+async fn bgzf_scan_frames(acc: &Arc<dyn Accessor>, path: &str) -> Result<Vec<Frame>> {
+    let mut r = acc.read(&OpRead::new(path, ..)?).await?;
+
+    let mut frames = Vec::new();
+    let (mut c_offset, mut d_offset) = (0, 0);
+    loop {
+        let mut header = [0; GZIP_FIXED_HEADER_SIZE as usize];
+        // Reach the end of object.
+        if r.read(&mut header[..1]).await? == 0 {
+            break;
+        }
+        r.read_exact(&mut header[1..]).await?;
+
+        // ID1, ID2, CM and FLG with FEXTRA.
+        if header[0..3] != [0x1f, 0x8b, 0x08] || header[3] & 0x04 == 0 {
+            return Err(invalid_data(
+                path,
+                anyhow!("invalid bgzf block header at {}", c_offset),
+            ));
+        }
+
+        let xlen = u16::from_le_bytes([header[10], header[11]]) as u64;
+        let mut extra = vec![0; xlen as usize];
+        r.read_exact(&mut extra).await?;
+
+        let mut bsize = None;
+        let mut subfields = extra.as_slice();
+        while subfields.len() >= 4 {
+            let slen = u16::from_le_bytes([subfields[2], subfields[3]]) as usize;
+            if subfields[0..2] == [b'B', b'C'] && slen == 2 && subfields.len() >= 6 {
+                bsize = Some(u16::from_le_bytes([subfields[4], subfields[5]]) as u64);
+            }
+            subfields = &subfields[min(4 + slen, subfields.len())..];
+        }
+        let block_size = match bsize {
+            Some(v) if v + 1 >= GZIP_FIXED_HEADER_SIZE + xlen + 8 => v + 1,
+            _ => {
+                return Err(invalid_data(
+                    path,
+                    anyhow!("invalid bgzf block size at {}", c_offset),
+                ))
+            }
+        };
+
+        // Skip compressed data and CRC32 to read ISIZE.
+        let skip = block_size - GZIP_FIXED_HEADER_SIZE - xlen - 4;
+        let n = io::copy((&mut r).take(skip), &mut io::sink()).await?;
+        if n != skip {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                anyhow!("bgzf block at {} is truncated", c_offset),
+            ));
+        }
+        let mut isize = [0; 4];
+        r.read_exact(&mut isize).await?;
+        let d_size = u32::from_le_bytes(isize) as u64;
+
+        if d_size != 0 {
+            frames.push(Frame {
+                c_offset,
+                c_size: block_size,
+                d_offset,
+                d_size,
+            });
+        }
+        c_offset += block_size;
+        d_offset += d_size;
+    }
+
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::AsyncSeekExt;
+
+    use super::*;
+    use crate::io_util::CompressReader;
+    use crate::Operator;
+    use crate::Scheme;
+
+    /// BGZF end-of-file marker block.
+    const BGZF_EOF: [u8; 28] = [
+        0x1f, 0x8b, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x06, 0x00, 0x42, 0x43, 0x02,
+        0x00, 0x1b, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    fn gen_content() -> Vec<u8> {
+        (0..1024 * 1024)
+            .map(|v: u32| (v % 251) as u8 ^ (v / 4096) as u8)
+            .collect()
+    }
+
+    async fn compress(algo: CompressAlgorithm, bs: &[u8]) -> Result<Vec<u8>> {
+        let mut r = CompressReader::new(Cursor::new(bs.to_vec()), algo);
+        let mut result = vec![];
+        r.read_to_end(&mut result).await?;
+        Ok(result)
+    }
+
+    /// Convert a gzip member into a BGZF block by adding the `BC` subfield.
+    fn into_bgzf_block(member: Vec<u8>) -> Vec<u8> {
+        assert_eq!(member[3], 0, "gzip member must not have flags");
+
+        let bsize = (member.len() + 8 - 1) as u16;
+        let mut block = Vec::with_capacity(member.len() + 8);
+        block.extend_from_slice(&member[0..3]);
+        block.push(0x04);
+        block.extend_from_slice(&member[4..10]);
+        block.extend_from_slice(&[0x06, 0x00, b'B', b'C', 0x02, 0x00]);
+        block.extend_from_slice(&bsize.to_le_bytes());
+        block.extend_from_slice(&member[10..]);
+        block
+    }
+
+    async fn check_reader(r: &mut SeekableDecompressReader, content: &[u8]) -> Result<()> {
+        assert_eq!(r.content_length(), content.len() as u64);
+
+        let mut bs = vec![0; 1000];
+        for pos in [0, 100, 70000, 65535, 500 * 1024, 10, 1024 * 1024 - 1000] {
+            r.seek(SeekFrom::Start(pos)).await?;
+            r.read_exact(&mut bs).await?;
+            assert_eq!(bs, content[pos as usize..pos as usize + 1000], "pos {pos}");
+        }
+
+        let n = r.seek(SeekFrom::End(-10)).await?;
+        assert_eq!(n, content.len() as u64 - 10);
+        let mut bs = vec![];
+        r.read_to_end(&mut bs).await?;
+        assert_eq!(bs, content[content.len() - 10..]);
+
+        r.seek(SeekFrom::Start(3000)).await?;
+        let mut bs = vec![];
+        r.read_to_end(&mut bs).await?;
+        assert_eq!(bs, content[3000..]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_zstd_seekable() -> Result<()> {
+        let op = Operator::from_env(Scheme::Memory)?;
+        let content = gen_content();
+
+        let mut bs = vec![];
+        let mut table = vec![];
+        for chunk in content.chunks(64 * 1024) {
+            let frame = compress(CompressAlgorithm::Zstd, chunk).await?;
+            table.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            table.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            // Checksum, will be ignored.
+            table.extend_from_slice(&0u32.to_le_bytes());
+            bs.extend_from_slice(&frame);
+        }
+        let count = content.chunks(64 * 1024).count() as u32;
+        bs.extend_from_slice(&ZSTD_SKIPPABLE_MAGIC.to_le_bytes());
+        bs.extend_from_slice(&(table.len() as u32 + 9).to_le_bytes());
+        bs.extend_from_slice(&table);
+        bs.extend_from_slice(&count.to_le_bytes());
+        bs.push(0x80);
+        bs.extend_from_slice(&ZSTD_SEEKABLE_MAGIC.to_le_bytes());
+        op.object("test.zst").write(bs).await?;
+
+        let mut r =
+            seekable_decompress_read(&op.object("test.zst"), SeekableCompressFormat::ZstdSeekable)
+                .await?;
+        check_reader(&mut r, &content).await?;
+
+        // Object without seek table should fail.
+        let bs = compress(CompressAlgorithm::Zstd, &content).await?;
+        op.object("plain.zst").write(bs).await?;
+        let res = seekable_decompress_read(
+            &op.object("plain.zst"),
+            SeekableCompressFormat::ZstdSeekable,
+        )
+        .await;
+        assert_eq!(res.err().unwrap().kind(), ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_gzip_indexed() -> Result<()> {
+        let op = Operator::from_env(Scheme::Memory)?;
+        let content = gen_content();
+
+        let mut bs = vec![];
+        let mut index = vec![];
+        for (i, chunk) in content.chunks(100 * 1000).enumerate() {
+            if i != 0 {
+                index.push((bs.len() as u64, (i * 100 * 1000) as u64));
+            }
+            bs.extend_from_slice(&compress(CompressAlgorithm::Gzip, chunk).await?);
+        }
+        op.object("test.gz").write(bs).await?;
+
+        let mut gzi = (index.len() as u64).to_le_bytes().to_vec();
+        for (c, d) in index {
+            gzi.extend_from_slice(&c.to_le_bytes());
+            gzi.extend_from_slice(&d.to_le_bytes());
+        }
+        op.object("test.gz.gzi").write(gzi).await?;
+
+        let mut r =
+            seekable_decompress_read(&op.object("test.gz"), SeekableCompressFormat::Gzip).await?;
+        check_reader(&mut r, &content).await?;
+
+        // Index with overflowed count should fail.
+        let mut gzi = ((1u64 << 60) + 1).to_le_bytes().to_vec();
+        gzi.extend_from_slice(&[0; 16]);
+        op.object("test.gz.gzi").write(gzi).await?;
+        let res =
+            seekable_decompress_read(&op.object("test.gz"), SeekableCompressFormat::Gzip).await;
+        assert_eq!(res.err().unwrap().kind(), ErrorKind::InvalidData);
+
+        // Gzip without index should fail.
+        op.object("test.gz.gzi").delete().await?;
+        let res =
+            seekable_decompress_read(&op.object("test.gz"), SeekableCompressFormat::Gzip).await;
+        assert_eq!(res.err().unwrap().kind(), ErrorKind::NotFound);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bgzf() -> Result<()> {
+        let op = Operator::from_env(Scheme::Memory)?;
+        let content = gen_content();
+
+        let mut bs = vec![];
+        let mut index = vec![];
+        for (i, chunk) in content.chunks(60 * 1024).enumerate() {
+            if i != 0 {
+                index.push((bs.len() as u64, (i * 60 * 1024) as u64));
+            }
+            let member = compress(CompressAlgorithm::Gzip, chunk).await?;
+            bs.extend_from_slice(&into_bgzf_block(member));
+        }
+        bs.extend_from_slice(&BGZF_EOF);
+        op.object("test.bgz").write(bs).await?;
+
+        // Build index by scanning block headers.
+        let mut r =
+            seekable_decompress_read(&op.object("test.bgz"), SeekableCompressFormat::Bgzf).await?;
+        check_reader(&mut r, &content).await?;
+
+        // Build index from gzi.
+        let mut gzi = (index.len() as u64).to_le_bytes().to_vec();
+        for (c, d) in index {
+            gzi.extend_from_slice(&c.to_le_bytes());
+            gzi.extend_from_slice(&d.to_le_bytes());
+        }
+        op.object("test.bgz.gzi").write(gzi).await?;
+        let mut r =
+            seekable_decompress_read(&op.object("test.bgz"), SeekableCompressFormat::Bgzf).await?;
+        check_reader(&mut r, &content).await?;
+
+        Ok(())
+    }
+}
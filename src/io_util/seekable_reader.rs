@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use std::future::Future;
+use std::io::Error;
+use std::io::ErrorKind;
 use std::io::Result;
 use std::io::SeekFrom;
 use std::ops::RangeBounds;
@@ -21,6 +23,9 @@ use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
 
+use anyhow::anyhow;
+use bytes::Buf;
+use bytes::BytesMut;
 use futures::future::BoxFuture;
 use futures::ready;
 use futures::AsyncRead;
@@ -34,6 +39,11 @@ use crate::BytesReader;
 use crate::Object;
 use crate::ObjectMetadata;
 
+/// Default capacity of the internal buffer of [`SeekableReader`].
+const DEFAULT_BUFFER_CAPACITY: usize = 256 * 1024;
+/// Default read ahead window of [`SeekableReader`].
+const DEFAULT_READ_AHEAD: u64 = 256 * 1024;
+
 /// Add seek support for object via internal lazy operation.
 ///
 /// # Example
@@ -66,30 +76,77 @@ pub fn seekable_read(o: &Object, range: impl RangeBounds<u64>) -> SeekableReader
         offset: br.offset(),
         size: br.size(),
 
+        buffer_capacity: DEFAULT_BUFFER_CAPACITY,
+        read_ahead: DEFAULT_READ_AHEAD,
+
         pos: 0,
+        buf: BytesMut::new(),
+        buf_start: 0,
+        scratch: Vec::new(),
         state: State::Idle,
+        stat: None,
     }
 }
 
 /// SeekableReader implement `AsyncRead` and `AsyncSeek`.
+///
+/// # Buffer
+///
+/// SeekableReader keeps the latest read bytes (at most `buffer_capacity`)
+/// in memory, so that:
+///
+/// - Seeking backward inside the buffer will be served from memory.
+/// - Seeking forward no more than `read_ahead` will be served by skipping
+///   bytes in the current stream.
+///
+/// Only seeking out of both windows will start a new read operation on
+/// backend.
 pub struct SeekableReader {
     acc: Arc<dyn Accessor>,
     path: String,
     offset: Option<u64>,
     size: Option<u64>,
 
+    buffer_capacity: usize,
+    read_ahead: u64,
+
     pos: u64,
+    /// Buffer holds the content in `[buf_start, buf_start + buf.len())`.
+    ///
+    /// The underlying stream (if exists) always starts at the end of buffer.
+    buf: BytesMut,
+    buf_start: u64,
+    /// Scratch space to read from the underlying stream.
+    scratch: Vec<u8>,
     state: State,
+    stat: Option<BoxFuture<'static, Result<ObjectMetadata>>>,
 }
 
 enum State {
     Idle,
     Sending(BoxFuture<'static, Result<BytesReader>>),
-    Seeking(BoxFuture<'static, Result<ObjectMetadata>>),
     Reading(BytesReader),
 }
 
 impl SeekableReader {
+    /// Set the capacity of internal buffer.
+    ///
+    /// Set to `0` to disable the buffer, and every read will be sent to the
+    /// underlying stream directly.
+    pub fn with_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity;
+        self
+    }
+
+    /// Set the read ahead window.
+    ///
+    /// Seeking forward no more than `size` bytes will skip bytes in the
+    /// current stream instead of starting a new read operation.
+    pub fn with_read_ahead(mut self, size: u64) -> Self {
+        self.read_ahead = size;
+        self
+    }
+
     fn current_offset(&self) -> u64 {
         self.offset.unwrap_or_default() + self.pos
     }
@@ -97,6 +154,35 @@ impl SeekableReader {
     fn current_size(&self) -> Option<u64> {
         self.size.map(|v| v - self.pos)
     }
+
+    fn buf_end(&self) -> u64 {
+        self.buf_start + self.buf.len() as u64
+    }
+
+    /// Copy content from buffer if current pos is inside buffer.
+    fn read_from_buffer(&mut self, buf: &mut [u8]) -> Option<usize> {
+        if self.pos < self.buf_start || self.pos >= self.buf_end() {
+            return None;
+        }
+
+        let start = (self.pos - self.buf_start) as usize;
+        let n = buf.len().min(self.buf.len() - start);
+        buf[..n].copy_from_slice(&self.buf[start..start + n]);
+        self.pos += n as u64;
+        Some(n)
+    }
+
+    /// Drop the oldest content to keep buffer under its capacity.
+    ///
+    /// Content after current pos will never be dropped.
+    fn shrink_buffer(&mut self) {
+        if self.buf.len() > self.buffer_capacity {
+            let excess =
+                (self.buf.len() - self.buffer_capacity).min((self.pos - self.buf_start) as usize);
+            self.buf.advance(excess);
+            self.buf_start += excess as u64;
+        }
+    }
 }
 
 impl AsyncRead for SeekableReader {
@@ -105,35 +191,87 @@ impl AsyncRead for SeekableReader {
         cx: &mut Context<'_>,
         buf: &mut [u8],
     ) -> Poll<Result<usize>> {
-        match &mut self.state {
-            State::Idle => {
-                let acc = self.acc.clone();
-                let op = OpRead::new_with_offset(
-                    &self.path,
-                    Some(self.current_offset()),
-                    self.current_size(),
-                )?;
-
-                let future = async move { acc.read(&op).await };
-
-                self.state = State::Sending(Box::pin(future));
-                self.poll_read(cx, buf)
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let this = &mut *self;
+        loop {
+            if let Some(n) = this.read_from_buffer(buf) {
+                return Poll::Ready(Ok(n));
+            }
+            if matches!(this.size, Some(size) if this.pos >= size) {
+                return Poll::Ready(Ok(0));
             }
-            State::Sending(future) => match ready!(Pin::new(future).poll(cx)) {
-                Ok(r) => {
-                    self.state = State::Reading(Box::new(r));
-                    self.poll_read(cx, buf)
+
+            let stream_pos = this.buf_end();
+            match &mut this.state {
+                State::Idle => {
+                    let acc = this.acc.clone();
+                    let op = OpRead::new_with_offset(
+                        &this.path,
+                        Some(this.current_offset()),
+                        this.current_size(),
+                    )?;
+
+                    let future = async move { acc.read(&op).await };
+
+                    // New stream starts at current pos, reset the buffer.
+                    this.buf.clear();
+                    this.buf_start = this.pos;
+                    this.state = State::Sending(Box::pin(future));
                 }
-                Err(e) => Poll::Ready(Err(e)),
-            },
-            State::Reading(r) => match ready!(Pin::new(r).poll_read(cx, buf)) {
-                Ok(n) => {
-                    self.pos += n as u64;
-                    Poll::Ready(Ok(n))
+                State::Sending(future) => match ready!(Pin::new(future).poll(cx)) {
+                    Ok(r) => this.state = State::Reading(r),
+                    Err(e) => {
+                        this.state = State::Idle;
+                        return Poll::Ready(Err(e));
+                    }
+                },
+                State::Reading(r) => {
+                    // Current pos is out of buffer and read ahead window, we
+                    // have to start a new read.
+                    if this.pos < stream_pos || this.pos - stream_pos > this.read_ahead {
+                        this.state = State::Idle;
+                        continue;
+                    }
+
+                    // Large read at stream pos, read into user's buffer directly.
+                    if this.pos == stream_pos && buf.len() >= this.buffer_capacity {
+                        let res = ready!(Pin::new(r).poll_read(cx, buf));
+                        return match res {
+                            Ok(n) => {
+                                this.pos += n as u64;
+                                this.buf.clear();
+                                this.buf_start = this.pos;
+                                Poll::Ready(Ok(n))
+                            }
+                            Err(e) => {
+                                this.state = State::Idle;
+                                Poll::Ready(Err(e))
+                            }
+                        };
+                    }
+
+                    // Scratch is allocated once and reused by every read.
+                    let size = this.buffer_capacity.max(buf.len());
+                    if this.scratch.len() < size {
+                        this.scratch.resize(size, 0);
+                    }
+                    match ready!(Pin::new(r).poll_read(cx, &mut this.scratch[..size])) {
+                        Err(e) => {
+                            this.state = State::Idle;
+                            return Poll::Ready(Err(e));
+                        }
+                        // Reach the end of stream.
+                        Ok(0) => return Poll::Ready(Ok(0)),
+                        Ok(n) => {
+                            this.buf.extend_from_slice(&this.scratch[..n]);
+                            this.shrink_buffer();
+                        }
+                    }
                 }
-                Err(e) => Poll::Ready(Err(e)),
-            },
-            _ => unreachable!("read while seeking is invalid"),
+            }
         }
     }
 }
@@ -144,9 +282,10 @@ impl AsyncSeek for SeekableReader {
         cx: &mut Context<'_>,
         pos: SeekFrom,
     ) -> Poll<Result<u64>> {
-        if let State::Seeking(future) = &mut self.state {
-            let meta = ready!(Pin::new(future).poll(cx))?;
-            self.size = Some(meta.content_length() - self.offset.unwrap_or_default())
+        if let Some(future) = &mut self.stat {
+            let meta = ready!(Pin::new(future).poll(cx));
+            self.stat = None;
+            self.size = Some(meta?.content_length() - self.offset.unwrap_or_default())
         }
 
         let cur = self.pos as i64;
@@ -161,7 +300,7 @@ impl AsyncSeek for SeekableReader {
 
                     let future = async move { acc.stat(&op).await };
 
-                    self.state = State::Seeking(Box::pin(future));
+                    self.stat = Some(Box::pin(future));
                     return self.poll_seek(cx, pos);
                 }
 
@@ -171,9 +310,16 @@ impl AsyncSeek for SeekableReader {
             }
         };
 
-        self.pos = cur as u64;
+        if cur < 0 {
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::InvalidInput,
+                anyhow!("invalid seek to a negative position: {cur}"),
+            )));
+        }
 
-        self.state = State::Idle;
+        // Real IO will be decided by the next read: content inside buffer or
+        // read ahead window will reuse current stream.
+        self.pos = cur as u64;
         Poll::Ready(Ok(self.pos))
     }
 }
@@ -182,15 +328,52 @@ impl AsyncSeek for SeekableReader {
 mod tests {
     use std::io::SeekFrom;
     use std::str::from_utf8;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
 
     use anyhow::Result;
+    use async_trait::async_trait;
     use futures::AsyncReadExt;
     use futures::AsyncSeekExt;
 
     use super::*;
+    use crate::Layer;
     use crate::Operator;
     use crate::Scheme;
 
+    /// Count the read operations sent to the inner accessor.
+    #[derive(Debug, Clone, Default)]
+    struct CountLayer {
+        reads: Arc<AtomicUsize>,
+    }
+
+    impl Layer for CountLayer {
+        fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+            Arc::new(CountAccessor {
+                inner,
+                reads: self.reads.clone(),
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct CountAccessor {
+        inner: Arc<dyn Accessor>,
+        reads: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Accessor for CountAccessor {
+        async fn read(&self, args: &OpRead) -> std::io::Result<BytesReader> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            self.inner.read(args).await
+        }
+
+        async fn stat(&self, args: &OpStat) -> std::io::Result<ObjectMetadata> {
+            self.inner.stat(args).await
+        }
+    }
+
     #[tokio::test]
     async fn test_reader() -> Result<()> {
         let f = Operator::from_env(Scheme::Fs)?;
@@ -231,4 +414,101 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_reader_buffered_seek() -> Result<()> {
+        let layer = CountLayer::default();
+        let op = Operator::from_env(Scheme::Memory)?;
+        let content: Vec<u8> = (0..1024 * 1024).map(|v| v as u8).collect();
+        op.object("test").write(content.clone()).await?;
+
+        let op = op.layer(layer.clone());
+        let o = op.object("test");
+        let mut r = seekable_read(&o, ..)
+            .with_buffer_capacity(64 * 1024)
+            .with_read_ahead(128 * 1024);
+
+        let mut bs = vec![0; 16];
+        let cases = vec![
+            // First read will send a new request.
+            ("first read", 0, 1),
+            // Short forward seek will skip in current stream.
+            ("forward seek", 100, 1),
+            // Backward seek inside buffer will be served from memory.
+            ("backward seek", 10, 1),
+            // Forward seek inside read ahead window.
+            ("forward seek in window", 100 * 1024, 1),
+            // Backward seek inside buffer after buffer has been shifted.
+            ("backward seek in buffer", 90 * 1024, 1),
+            // Backward seek out of buffer.
+            ("backward seek out of buffer", 10, 2),
+            // Forward seek out of read ahead window.
+            ("forward seek out of window", 512 * 1024, 3),
+        ];
+
+        for (name, pos, reads) in cases {
+            r.seek(SeekFrom::Start(pos)).await?;
+            r.read_exact(&mut bs).await?;
+            assert_eq!(bs, content[pos as usize..pos as usize + 16], "{}", name);
+            assert_eq!(layer.reads.load(Ordering::SeqCst), reads, "{}", name);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reader_without_buffer() -> Result<()> {
+        let layer = CountLayer::default();
+        let op = Operator::from_env(Scheme::Memory)?;
+        let content: Vec<u8> = (0..1024).map(|v| v as u8).collect();
+        op.object("test").write(content.clone()).await?;
+
+        let op = op.layer(layer.clone());
+        let o = op.object("test");
+        let mut r = seekable_read(&o, 100..)
+            .with_buffer_capacity(0)
+            .with_read_ahead(0);
+
+        let mut bs = vec![0; 16];
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, content[100..116]);
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, content[116..132]);
+        assert_eq!(layer.reads.load(Ordering::SeqCst), 1);
+
+        r.seek(SeekFrom::Current(1)).await?;
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, content[133..149]);
+        assert_eq!(layer.reads.load(Ordering::SeqCst), 2);
+
+        // Read at the end of range.
+        r.seek(SeekFrom::End(0)).await?;
+        assert_eq!(r.read(&mut bs).await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reader_large_skip() -> Result<()> {
+        let layer = CountLayer::default();
+        let op = Operator::from_env(Scheme::Memory)?;
+        let content: Vec<u8> = (0..1024 * 1024).map(|v| v as u8).collect();
+        op.object("test").write(content.clone()).await?;
+
+        let op = op.layer(layer.clone());
+        let o = op.object("test");
+        let mut r = seekable_read(&o, ..)
+            .with_buffer_capacity(16)
+            .with_read_ahead(u64::MAX);
+
+        // Skip the whole object in small chunks should not overflow stack.
+        let mut bs = vec![0; 16];
+        r.read_exact(&mut bs).await?;
+        r.seek(SeekFrom::End(-16)).await?;
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, content[content.len() - 16..]);
+        assert_eq!(layer.reads.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
 }
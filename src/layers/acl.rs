@@ -0,0 +1,685 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignOperation;
+use crate::ops::PresignedRequest;
+use crate::path::has_parent_segment;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+
+/// Effect of an acl rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AclEffect {
+    /// Allow the operation.
+    #[default]
+    Allow,
+    /// Deny the operation with `PermissionDenied` error.
+    Deny,
+}
+
+/// Rule of [`AclLayer`].
+///
+/// Path is matched by glob:
+///
+/// - `*` matches any characters except `/`.
+/// - `**` matches any characters including `/`.
+/// - `?` matches one character except `/`.
+///
+/// Leading `/` will be ignored, so `/raw/**` and `raw/**` are the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AclRule {
+    effect: AclEffect,
+    path: String,
+    /// Empty operations means all operations.
+    #[cfg_attr(feature = "serde", serde(default))]
+    operations: Vec<Operation>,
+}
+
+impl AclRule {
+    /// Create a rule to allow operations on paths matched by glob.
+    ///
+    /// Empty operations means all operations.
+    pub fn allow(path: &str, operations: impl IntoIterator<Item = Operation>) -> Self {
+        AclRule {
+            effect: AclEffect::Allow,
+            path: path.to_string(),
+            operations: operations.into_iter().collect(),
+        }
+    }
+
+    /// Create a rule to deny operations on paths matched by glob.
+    ///
+    /// Empty operations means all operations.
+    pub fn deny(path: &str, operations: impl IntoIterator<Item = Operation>) -> Self {
+        AclRule {
+            effect: AclEffect::Deny,
+            path: path.to_string(),
+            operations: operations.into_iter().collect(),
+        }
+    }
+
+    /// Check if this rule matches the operation on path.
+    fn is_match(&self, op: Operation, path: &str) -> bool {
+        let op = matched_operation(op);
+        if !self.operations.is_empty()
+            && !self.operations.iter().any(|v| matched_operation(*v) == op)
+        {
+            return false;
+        }
+
+        let path = if path == "/" { "" } else { path };
+        glob_match(
+            self.path.trim_start_matches('/').as_bytes(),
+            path.as_bytes(),
+        )
+    }
+}
+
+impl Display for AclRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let effect = match self.effect {
+            AclEffect::Allow => "allow",
+            AclEffect::Deny => "deny",
+        };
+        write!(f, "{effect} ")?;
+        if self.operations.is_empty() {
+            write!(f, "*")?;
+        } else {
+            let ops: Vec<_> = self.operations.iter().map(|v| v.into_static()).collect();
+            write!(f, "{}", ops.join(","))?;
+        }
+        write!(f, " on {}", self.path)
+    }
+}
+
+/// Returns the operation used to match rules.
+///
+/// - Blocking operations are matched by their async ones.
+/// - Multipart operations except abort are matched by `write`, since they
+///   create objects too.
+fn matched_operation(op: Operation) -> Operation {
+    match op.into_async() {
+        Operation::CreateMultipart | Operation::WriteMultipart | Operation::CompleteMultipart => {
+            Operation::Write
+        }
+        op => op,
+    }
+}
+
+/// Token of glob pattern.
+#[derive(Clone, Copy)]
+enum GlobToken {
+    /// `**`
+    AnyPath,
+    /// `*`
+    AnyName,
+    /// `?`
+    AnyChar,
+    Literal(u8),
+}
+
+/// Match path with glob pattern.
+///
+/// `matched[j]` records whether tokens after current one match `path[j..]`,
+/// so that the time is `O(pattern * path)`.
+fn glob_match(pattern: &[u8], path: &[u8]) -> bool {
+    let mut tokens = Vec::with_capacity(pattern.len());
+    let mut idx = 0;
+    while idx < pattern.len() {
+        let token = match &pattern[idx..] {
+            [b'*', b'*', ..] => GlobToken::AnyPath,
+            [b'*', ..] => GlobToken::AnyName,
+            [b'?', ..] => GlobToken::AnyChar,
+            [c, ..] => GlobToken::Literal(*c),
+            [] => unreachable!("pattern must not be empty"),
+        };
+        idx += if matches!(token, GlobToken::AnyPath) {
+            2
+        } else {
+            1
+        };
+        tokens.push(token);
+    }
+
+    // Empty pattern only matches empty path.
+    let mut matched = vec![false; path.len() + 1];
+    matched[path.len()] = true;
+
+    for token in tokens.iter().rev() {
+        let mut current = vec![false; path.len() + 1];
+        for j in (0..=path.len()).rev() {
+            let c = path.get(j).copied();
+            current[j] = match token {
+                GlobToken::AnyPath => matched[j] || (c.is_some() && current[j + 1]),
+                GlobToken::AnyName => {
+                    matched[j] || (c.is_some_and(|c| c != b'/') && current[j + 1])
+                }
+                GlobToken::AnyChar => c.is_some_and(|c| c != b'/') && matched[j + 1],
+                GlobToken::Literal(v) => c == Some(*v) && matched[j + 1],
+            };
+        }
+        matched = current;
+    }
+
+    matched[0]
+}
+
+/// AclLayer will check operations by path based rules for OpenDAL.
+///
+/// Rules will be evaluated in order, and the first matched rule decides
+/// whether the operation is allowed. Operations not matched by any rule
+/// will use the default effect, which is [`AclEffect::Allow`].
+///
+/// Denied operations will return `PermissionDenied` error before sending
+/// requests to services. Presign will be checked by the operation to
+/// presign, so presign write on read-only paths is denied too. Multipart
+/// operations except abort are checked as `write`. Rename is checked as
+/// `rename` on both paths, plus `read` and `delete` on the source and
+/// `write` on the target. Paths with
+/// `..` are always denied.
+///
+/// AclLayer could be deserialized from config with feature `serde`:
+///
+/// ```json
+/// {
+///   "default": "allow",
+///   "rules": [
+///     { "effect": "allow", "path": "/raw/**", "operations": ["read", "stat", "list"] },
+///     { "effect": "deny", "path": "/raw/**" },
+///     { "effect": "deny", "path": "/audit/**", "operations": ["delete"] }
+///   ]
+/// }
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use opendal::layers::AclLayer;
+/// use opendal::layers::AclRule;
+/// use opendal::ops::Operation;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(
+///         AclLayer::new()
+///             .with_rule(AclRule::allow(
+///                 "/raw/**",
+///                 [Operation::Read, Operation::Stat, Operation::List],
+///             ))
+///             .with_rule(AclRule::deny("/raw/**", []))
+///             .with_rule(AclRule::deny("/audit/**", [Operation::Delete])),
+///     );
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AclLayer {
+    #[cfg_attr(feature = "serde", serde(default))]
+    default: AclEffect,
+    #[cfg_attr(feature = "serde", serde(default))]
+    rules: Vec<AclRule>,
+}
+
+impl AclLayer {
+    /// Create a new acl layer without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a rule, rules will be evaluated in the order they are added.
+    pub fn with_rule(mut self, rule: AclRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set the effect for operations not matched by any rule.
+    pub fn with_default(mut self, effect: AclEffect) -> Self {
+        self.default = effect;
+        self
+    }
+}
+
+impl Layer for AclLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(AclAccessor {
+            inner,
+            acl: Arc::new(self.clone()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct AclAccessor {
+    inner: Arc<dyn Accessor>,
+    acl: Arc<AclLayer>,
+}
+
+impl AclAccessor {
+    fn check(&self, op: Operation, path: &str) -> Result<()> {
+        // Paths with `..` could reach paths not matched by rules on fs.
+        if has_parent_segment(path) {
+            return Err(
+                ObjectError::new(op, path, anyhow!("path must not contain `..`"))
+                    .with_kind(ObjectErrorKind::PermissionDenied)
+                    .into(),
+            );
+        }
+
+        let rule = self.acl.rules.iter().find(|v| v.is_match(op, path));
+        let effect = rule.map_or(self.acl.default, |v| v.effect);
+        if effect == AclEffect::Allow {
+            return Ok(());
+        }
+
+        let err = match rule {
+            Some(rule) => anyhow!("denied by acl rule: {rule}"),
+            None => anyhow!("denied by acl default rule"),
+        };
+        Err(ObjectError::new(op, path, err)
+            .with_kind(ObjectErrorKind::PermissionDenied)
+            .into())
+    }
+}
+
+#[async_trait]
+impl Accessor for AclAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        self.check(Operation::Create, args.path())?;
+        self.inner.create(args).await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        self.check(Operation::Read, args.path())?;
+        self.inner.read(args).await
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        self.check(Operation::Write, args.path())?;
+        self.inner.write(args, r).await
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.check(Operation::Read, args.path())?;
+        self.inner.read_stream(args).await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.check(Operation::Write, args.path())?;
+        self.inner.write_stream(args, s).await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.check(Operation::Stat, args.path())?;
+        self.inner.stat(args).await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        self.check(Operation::Delete, args.path())?;
+        self.inner.delete(args).await
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        self.check(Operation::Rename, args.from())?;
+        self.check(Operation::Rename, args.to())?;
+        self.check(Operation::Read, args.from())?;
+        self.check(Operation::Delete, args.from())?;
+        self.check(Operation::Write, args.to())?;
+        self.inner.rename(args).await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        self.check(Operation::List, args.path())?;
+        let ds = self.inner.list(args).await?;
+
+        // Objects converted from entries should be checked too.
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        Ok(Box::new(ds.map_ok(move |mut de| {
+            de.set_accessor(this.clone());
+            de
+        })))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        let op = match args.operation() {
+            PresignOperation::Read(_) => Operation::Read,
+            PresignOperation::Write(_) => Operation::Write,
+            PresignOperation::WriteMultipart(_) => Operation::WriteMultipart,
+        };
+        self.check(op, args.path())?;
+
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.check(Operation::CreateMultipart, args.path())?;
+        self.inner.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        self.check(Operation::WriteMultipart, args.path())?;
+        self.inner.write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        self.check(Operation::CompleteMultipart, args.path())?;
+        self.inner.complete_multipart(args).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.check(Operation::AbortMultipart, args.path())?;
+        self.inner.abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        self.check(Operation::BlockingCreate, args.path())?;
+        self.inner.blocking_create(args)
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.check(Operation::BlockingRead, args.path())?;
+        self.inner.blocking_read(args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.check(Operation::BlockingWrite, args.path())?;
+        self.inner.blocking_write(args, r)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.check(Operation::BlockingStat, args.path())?;
+        self.inner.blocking_stat(args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        self.check(Operation::BlockingDelete, args.path())?;
+        self.inner.blocking_delete(args)
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        self.check(Operation::BlockingRename, args.from())?;
+        self.check(Operation::BlockingRename, args.to())?;
+        self.check(Operation::BlockingRead, args.from())?;
+        self.check(Operation::BlockingDelete, args.from())?;
+        self.check(Operation::BlockingWrite, args.to())?;
+        self.inner.blocking_rename(args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        self.check(Operation::BlockingList, args.path())?;
+        let it = self.inner.blocking_list(args)?;
+
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        Ok(Box::new(it.map(move |de| {
+            de.map(|mut de| {
+                de.set_accessor(this.clone());
+                de
+            })
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use time::Duration;
+
+    use super::*;
+    use crate::services::memory;
+    use crate::Operator;
+
+    #[test]
+    fn test_glob_match() {
+        let cases = vec![
+            ("raw/**", "raw/", true),
+            ("raw/**", "raw/a/b/c", true),
+            ("raw/**", "rawx/a", false),
+            ("raw/*", "raw/a", true),
+            ("raw/*", "raw/a/b", false),
+            ("raw/*.csv", "raw/a.csv", true),
+            ("raw/*.csv", "raw/a.json", false),
+            ("**/*.csv", "a/b/c.csv", true),
+            ("raw/?", "raw/a", true),
+            ("raw/?", "raw/ab", false),
+            ("**", "", true),
+            ("audit", "audit", true),
+            ("audit", "audit/", false),
+            ("a/**/b/**/c", "a/x/b/y/z/c", true),
+            ("a/**/b/**/c", "a/x/y/z/c", false),
+            ("a/*/*.csv", "a/b/c.csv", true),
+            ("a/*/*.csv", "a/b/c/d.csv", false),
+        ];
+
+        for (pattern, path, expected) in cases {
+            assert_eq!(
+                glob_match(pattern.as_bytes(), path.as_bytes()),
+                expected,
+                "pattern: {pattern}, path: {path}"
+            );
+        }
+    }
+
+    fn new_operator() -> anyhow::Result<Operator> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        Ok(op.layer(
+            AclLayer::new()
+                .with_rule(AclRule::allow(
+                    "/raw/**",
+                    [Operation::Read, Operation::Stat, Operation::List],
+                ))
+                .with_rule(AclRule::deny("/raw/**", []))
+                .with_rule(AclRule::deny("/audit/**", [Operation::Delete])),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_acl() -> anyhow::Result<()> {
+        let op = new_operator()?;
+
+        let err = op
+            .object("raw/file")
+            .write("x")
+            .await
+            .expect_err("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("deny * on /raw/**"), "{err}");
+        let err = op.object("raw/file").read().await.expect_err("not found");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+
+        op.object("audit/log").write("x").await?;
+        let err = op
+            .object("audit/log")
+            .delete()
+            .await
+            .expect_err("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        // Objects from list should be checked too.
+        let de = op
+            .object("audit/")
+            .list()
+            .await?
+            .try_next()
+            .await?
+            .expect("must have entry");
+        let err = de.into_object().delete().await.expect_err("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        op.object("other").write("x").await?;
+        op.object("other").delete().await?;
+
+        // Paths with `..` should not bypass rules.
+        op.object("secret").write("x").await?;
+        let err = op
+            .object("raw/../secret")
+            .read()
+            .await
+            .expect_err("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        // Long patterns should not take exponential time.
+        let pattern = "**/".repeat(16) + "x";
+        let path = "a/".repeat(64) + "y";
+        assert!(!glob_match(pattern.as_bytes(), path.as_bytes()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_acl_multipart() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?)
+            .layer(AclLayer::new().with_rule(AclRule::deny("/locked/**", [Operation::Write])));
+
+        // Multipart creates objects too, so it's checked as write.
+        let o = op.object("locked/file");
+        let err = o.create_multipart().await.err().expect("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        let mp = o.to_multipart("upload");
+        let err = mp.write(1, "x").await.expect_err("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        let err = mp.complete(vec![]).await.expect_err("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        let err = mp
+            .presign_write(1, Duration::hours(1))
+            .expect_err("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        // Abort is not checked as write, memory doesn't support multipart.
+        let err = mp.abort().await.expect_err("must be unsupported");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_acl_presign() -> anyhow::Result<()> {
+        let op = new_operator()?;
+
+        // Presign is checked before reaching memory which doesn't support it.
+        let err = op
+            .object("raw/file")
+            .presign_write(Duration::hours(1))
+            .expect_err("must deny");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        let err = op
+            .object("raw/file")
+            .presign_read(Duration::hours(1))
+            .expect_err("must be unsupported");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_acl_rename() -> anyhow::Result<()> {
+        let op = new_operator()?;
+
+        // Rename is checked before reaching memory which doesn't support it.
+        for (from, to) in [
+            ("audit/log", "other"),
+            ("raw/file", "other"),
+            ("other", "raw/file"),
+        ] {
+            let err = op.object(from).blocking_rename(to).expect_err("must deny");
+            assert_eq!(err.kind(), ErrorKind::PermissionDenied, "{from} -> {to}");
+        }
+        let err = op
+            .object("other")
+            .blocking_rename("another")
+            .expect_err("must be unsupported");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        // Rename itself could be denied on either path.
+        let op = Operator::new(memory::Builder::default().build()?)
+            .layer(AclLayer::new().with_rule(AclRule::deny("/frozen/**", [Operation::Rename])));
+        for (from, to) in [("frozen/file", "other"), ("other", "frozen/file")] {
+            let err = op.object(from).blocking_rename(to).expect_err("must deny");
+            assert_eq!(err.kind(), ErrorKind::PermissionDenied, "{from} -> {to}");
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_acl_from_config() -> anyhow::Result<()> {
+        let acl: AclLayer = serde_json::from_str(
+            r#"{
+                "default": "deny",
+                "rules": [
+                    { "effect": "allow", "path": "/raw/**", "operations": ["read", "blocking_stat"] },
+                    { "effect": "deny", "path": "/audit/**", "operations": ["delete"] }
+                ]
+            }"#,
+        )?;
+
+        assert_eq!(acl.default, AclEffect::Deny);
+        assert_eq!(
+            acl.rules,
+            vec![
+                AclRule::allow("/raw/**", [Operation::Read, Operation::BlockingStat]),
+                AclRule::deny("/audit/**", [Operation::Delete]),
+            ]
+        );
+        assert!(acl.rules[0].is_match(Operation::Stat, "raw/file"));
+        assert!(acl.rules[0].is_match(Operation::BlockingRead, "raw/file"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,681 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::io::Cursor;
+use futures::ready;
+use futures::AsyncRead;
+use futures::AsyncReadExt;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::error::ObjectError;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+
+/// Checksum algorithms supported by [`ChecksumLayer`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ChecksumAlgorithm {
+    /// MD5, will be sent as `Content-MD5` or `x-goog-hash: md5=`.
+    Md5,
+    /// CRC32C, will be sent as `x-amz-checksum-crc32c` or `x-goog-hash: crc32c=`.
+    Crc32c,
+    /// SHA-256, will be sent as `x-amz-checksum-sha256`.
+    Sha256,
+}
+
+/// ChecksumLayer will verify the content against checksums provided by
+/// services.
+///
+/// # Read
+///
+/// For full object reads, ChecksumLayer will stat the object first and
+/// verify the content against all checksums (MD5, CRC32C and SHA-256)
+/// that services provide. Reader will return an error with
+/// [`ErrorKind::InvalidData`] at the end of content if checksum mismatched.
+///
+/// Range reads and objects without checksum will not be verified.
+///
+/// # Write
+///
+/// ChecksumLayer will compute checksums of content and send them along with
+/// content, so that services will reject corrupted content. By default, MD5
+/// and CRC32C will be computed, use [`ChecksumLayer::with_algorithms`] to
+/// change them.
+///
+/// # Notes
+///
+/// - Content will be buffered in memory to compute checksums before writing.
+/// - ChecksumLayer should be applied before layers that transform content
+///   like [`CompressLayer`](super::CompressLayer), so that checksums are
+///   computed over the stored content.
+///
+/// # Feature
+///
+/// This layer needs to enable feature `layers-checksum`.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::ChecksumLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(ChecksumLayer::new());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChecksumLayer {
+    algorithms: Vec<ChecksumAlgorithm>,
+}
+
+impl Default for ChecksumLayer {
+    fn default() -> Self {
+        Self {
+            algorithms: vec![ChecksumAlgorithm::Md5, ChecksumAlgorithm::Crc32c],
+        }
+    }
+}
+
+impl ChecksumLayer {
+    /// Create a new ChecksumLayer which computes MD5 and CRC32C for writing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new ChecksumLayer which computes given algorithms for
+    /// writing.
+    pub fn with_algorithms(algorithms: &[ChecksumAlgorithm]) -> Self {
+        Self {
+            algorithms: algorithms.to_vec(),
+        }
+    }
+}
+
+impl Layer for ChecksumLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(ChecksumAccessor {
+            algorithms: self.algorithms.clone(),
+            inner,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ChecksumAccessor {
+    algorithms: Vec<ChecksumAlgorithm>,
+    inner: Arc<dyn Accessor>,
+}
+
+/// Checksums that services provided, all in raw bytes.
+#[derive(Debug, Default, Clone)]
+struct Checksums {
+    md5: Option<Vec<u8>>,
+    crc32c: Option<Vec<u8>>,
+    sha256: Option<Vec<u8>>,
+}
+
+impl Checksums {
+    /// Parse checksums from metadata, invalid values will be ignored.
+    fn from_metadata(meta: &ObjectMetadata) -> Self {
+        Self {
+            md5: meta.content_md5().and_then(decode_md5),
+            crc32c: meta
+                .content_crc32c()
+                .and_then(|v| base64::decode(v).ok())
+                .filter(|v| v.len() == 4),
+            sha256: meta
+                .content_sha256()
+                .and_then(|v| base64::decode(v).ok())
+                .filter(|v| v.len() == 32),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.md5.is_none() && self.crc32c.is_none() && self.sha256.is_none()
+    }
+}
+
+/// Services return md5 in hex (from ETag) or in base64 (from `Content-MD5`).
+fn decode_md5(v: &str) -> Option<Vec<u8>> {
+    let v = v.trim_matches('"');
+    if v.len() == 32 && v.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return (0..32)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&v[i..i + 2], 16).ok())
+            .collect();
+    }
+
+    base64::decode(v).ok().filter(|v| v.len() == 16)
+}
+
+/// Hasher computes checksums of content.
+struct Hasher {
+    md5: Option<md5::Context>,
+    crc32c: Option<u32>,
+    sha256: Option<Sha256>,
+}
+
+impl Hasher {
+    /// Create a hasher for expected checksums.
+    fn new(expected: &Checksums) -> Self {
+        Self {
+            md5: expected.md5.as_ref().map(|_| md5::Context::new()),
+            crc32c: expected.crc32c.as_ref().map(|_| 0),
+            sha256: expected.sha256.as_ref().map(|_| Sha256::new()),
+        }
+    }
+
+    /// Create a hasher for given algorithms.
+    fn with_algorithms(algorithms: &[ChecksumAlgorithm]) -> Self {
+        let enabled = |algo| algorithms.contains(&algo);
+        Self {
+            md5: enabled(ChecksumAlgorithm::Md5).then(md5::Context::new),
+            crc32c: enabled(ChecksumAlgorithm::Crc32c).then_some(0),
+            sha256: enabled(ChecksumAlgorithm::Sha256).then(Sha256::new),
+        }
+    }
+
+    fn update(&mut self, bs: &[u8]) {
+        if let Some(ctx) = &mut self.md5 {
+            ctx.consume(bs);
+        }
+        if let Some(crc) = &mut self.crc32c {
+            *crc = crc32c::crc32c_append(*crc, bs);
+        }
+        if let Some(ctx) = &mut self.sha256 {
+            ctx.update(bs);
+        }
+    }
+
+    fn finish(self) -> Checksums {
+        Checksums {
+            md5: self.md5.map(|ctx| ctx.compute().0.to_vec()),
+            crc32c: self.crc32c.map(|crc| crc.to_be_bytes().to_vec()),
+            sha256: self.sha256.map(|ctx| ctx.finalize().to_vec()),
+        }
+    }
+
+    /// Verify computed checksums against expected ones.
+    fn verify(self, path: &str, expected: &Checksums) -> Result<()> {
+        let actual = self.finish();
+
+        for (name, expect, actual) in [
+            ("md5", &expected.md5, &actual.md5),
+            ("crc32c", &expected.crc32c, &actual.crc32c),
+            ("sha256", &expected.sha256, &actual.sha256),
+        ] {
+            if let (Some(expect), Some(actual)) = (expect, actual) {
+                if expect != actual {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        ObjectError::new(
+                            Operation::Read,
+                            path,
+                            anyhow!(
+                                "{name} checksum mismatch, expect {} actual {}",
+                                base64::encode(expect),
+                                base64::encode(actual)
+                            ),
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ChecksumAccessor {
+    /// Build the OpRead for a verified read.
+    ///
+    /// Returns `None` if this read can't be verified.
+    fn verified_read_op(args: &OpRead, meta: &ObjectMetadata) -> Option<(OpRead, Checksums)> {
+        let expected = Checksums::from_metadata(meta);
+        if expected.is_empty() {
+            return None;
+        }
+        if let Some(size) = args.size() {
+            if size < meta.content_length() {
+                return None;
+            }
+        }
+
+        // Make sure we are reading the object with the same checksums.
+        let op = match (args.if_match(), meta.etag()) {
+            (None, Some(etag)) => args.clone().with_if_match(etag),
+            _ => args.clone(),
+        };
+        Some((op, expected))
+    }
+
+    /// Build the OpWrite with computed checksums.
+    fn checksum_write_op(&self, args: &OpWrite, content: &[u8]) -> Result<OpWrite> {
+        if content.len() as u64 != args.size() {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                ObjectError::new(
+                    Operation::Write,
+                    args.path(),
+                    anyhow!(
+                        "reader got too little data, expect {} actual {}",
+                        args.size(),
+                        content.len()
+                    ),
+                ),
+            ));
+        }
+
+        let mut hasher = Hasher::with_algorithms(&self.algorithms);
+        hasher.update(content);
+        let checksums = hasher.finish();
+
+        let mut op = args.clone();
+        if let Some(v) = checksums.md5 {
+            op = op.with_content_md5(&base64::encode(v));
+        }
+        if let Some(v) = checksums.crc32c {
+            op = op.with_content_crc32c(&base64::encode(v));
+        }
+        if let Some(v) = checksums.sha256 {
+            op = op.with_content_sha256(&base64::encode(v));
+        }
+        Ok(op)
+    }
+}
+
+#[async_trait]
+impl Accessor for ChecksumAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.create(args).await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        if args.offset().unwrap_or_default() != 0 {
+            return self.inner.read(args).await;
+        }
+
+        let meta = self.inner.stat(&OpStat::new(args.path())?).await?;
+        let (op, expected) = match Self::verified_read_op(args, &meta) {
+            Some(v) => v,
+            None => return self.inner.read(args).await,
+        };
+
+        let r = self.inner.read(&op).await?;
+        Ok(Box::new(ChecksumReader::new(r, args.path(), expected)))
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        let mut content = Vec::with_capacity(args.size() as usize);
+        r.take(args.size()).read_to_end(&mut content).await?;
+
+        let op = self.checksum_write_op(args, &content)?;
+        self.inner.write(&op, Box::new(Cursor::new(content))).await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.stat(args).await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.delete(args).await
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.rename(args).await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        self.inner.list(args).await
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.inner.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        self.inner.write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        self.inner.complete_multipart(args).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.inner.abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.blocking_create(args)
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        if args.offset().unwrap_or_default() != 0 {
+            return self.inner.blocking_read(args);
+        }
+
+        let meta = self.inner.blocking_stat(&OpStat::new(args.path())?)?;
+        let (op, expected) = match Self::verified_read_op(args, &meta) {
+            Some(v) => v,
+            None => return self.inner.blocking_read(args),
+        };
+
+        let r = self.inner.blocking_read(&op)?;
+        Ok(Box::new(BlockingChecksumReader::new(
+            r,
+            args.path(),
+            expected,
+        )))
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let mut content = Vec::with_capacity(args.size() as usize);
+        r.take(args.size()).read_to_end(&mut content)?;
+
+        let op = self.checksum_write_op(args, &content)?;
+        self.inner
+            .blocking_write(&op, Box::new(std::io::Cursor::new(content)))
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.blocking_stat(args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.blocking_delete(args)
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.blocking_rename(args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        self.inner.blocking_list(args)
+    }
+}
+
+/// ChecksumReader verifies content while reading, and returns an error
+/// instead of EOF if checksum mismatched.
+struct ChecksumReader {
+    inner: BytesReader,
+    path: String,
+    expected: Checksums,
+    hasher: Option<Hasher>,
+}
+
+impl ChecksumReader {
+    fn new(inner: BytesReader, path: &str, expected: Checksums) -> Self {
+        Self {
+            inner,
+            path: path.to_string(),
+            hasher: Some(Hasher::new(&expected)),
+            expected,
+        }
+    }
+}
+
+impl AsyncRead for ChecksumReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let n = ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+
+        match self.hasher.as_mut() {
+            Some(hasher) if n != 0 => hasher.update(&buf[..n]),
+            // Only verify once while reaching EOF.
+            Some(_) if !buf.is_empty() => {
+                let hasher = self.hasher.take().expect("hasher must be valid");
+                hasher.verify(&self.path, &self.expected)?;
+            }
+            _ => {}
+        }
+
+        Poll::Ready(Ok(n))
+    }
+}
+
+/// Blocking version of [`ChecksumReader`].
+struct BlockingChecksumReader {
+    inner: BlockingBytesReader,
+    path: String,
+    expected: Checksums,
+    hasher: Option<Hasher>,
+}
+
+impl BlockingChecksumReader {
+    fn new(inner: BlockingBytesReader, path: &str, expected: Checksums) -> Self {
+        Self {
+            inner,
+            path: path.to_string(),
+            hasher: Some(Hasher::new(&expected)),
+            expected,
+        }
+    }
+}
+
+impl Read for BlockingChecksumReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.inner.read(buf)?;
+
+        match self.hasher.as_mut() {
+            Some(hasher) if n != 0 => hasher.update(&buf[..n]),
+            // Only verify once while reaching EOF.
+            Some(_) if !buf.is_empty() => {
+                let hasher = self.hasher.take().expect("hasher must be valid");
+                hasher.verify(&self.path, &self.expected)?;
+            }
+            _ => {}
+        }
+
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::services::fs;
+    use crate::services::memory;
+    use crate::Operator;
+
+    /// MockAccessor stores checksums sent by write, and returns them in
+    /// stat like services.
+    #[derive(Debug)]
+    struct MockAccessor {
+        inner: Arc<dyn Accessor>,
+        checksums: Mutex<HashMap<String, OpWrite>>,
+    }
+
+    impl MockAccessor {
+        fn with_checksums(&self, path: &str, mut meta: ObjectMetadata) -> ObjectMetadata {
+            if let Some(op) = self.checksums.lock().get(path) {
+                if let Some(v) = op.content_md5() {
+                    meta.set_content_md5(v);
+                }
+                if let Some(v) = op.content_crc32c() {
+                    meta.set_content_crc32c(v);
+                }
+                if let Some(v) = op.content_sha256() {
+                    meta.set_content_sha256(v);
+                }
+            }
+            meta
+        }
+    }
+
+    #[async_trait]
+    impl Accessor for MockAccessor {
+        fn metadata(&self) -> AccessorMetadata {
+            self.inner.metadata()
+        }
+
+        async fn read(&self, args: &OpRead) -> std::io::Result<BytesReader> {
+            self.inner.read(args).await
+        }
+
+        async fn write(&self, args: &OpWrite, r: BytesReader) -> std::io::Result<u64> {
+            self.checksums
+                .lock()
+                .insert(args.path().to_string(), args.clone());
+            self.inner.write(args, r).await
+        }
+
+        async fn stat(&self, args: &OpStat) -> std::io::Result<ObjectMetadata> {
+            let meta = self.inner.stat(args).await?;
+            Ok(self.with_checksums(args.path(), meta))
+        }
+
+        fn blocking_read(&self, args: &OpRead) -> std::io::Result<BlockingBytesReader> {
+            self.inner.blocking_read(args)
+        }
+
+        fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> std::io::Result<u64> {
+            self.checksums
+                .lock()
+                .insert(args.path().to_string(), args.clone());
+            self.inner.blocking_write(args, r)
+        }
+
+        fn blocking_stat(&self, args: &OpStat) -> std::io::Result<ObjectMetadata> {
+            let meta = self.inner.blocking_stat(args)?;
+            Ok(self.with_checksums(args.path(), meta))
+        }
+    }
+
+    /// Returns the raw operator and the mocked one that sharing the same
+    /// storage.
+    fn new_operator(inner: impl Accessor + 'static) -> (Operator, Operator) {
+        let inner = Arc::new(inner);
+        let mock = Operator::new(MockAccessor {
+            inner: inner.clone(),
+            checksums: Mutex::new(HashMap::new()),
+        });
+        (Operator::new(inner), mock)
+    }
+
+    #[test]
+    fn test_decode_md5() {
+        let expected = md5::compute("Hello, World!").0.to_vec();
+
+        assert_eq!(
+            decode_md5("65a8e27d8879283831b664bd8b7f0ad4"),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            decode_md5("\"65a8e27d8879283831b664bd8b7f0ad4\""),
+            Some(expected.clone())
+        );
+        assert_eq!(decode_md5("ZajifYh5KDgxtmS9i38K1A=="), Some(expected));
+        assert_eq!(decode_md5("0x8DA8BEB55D0EA35"), None);
+        assert_eq!(decode_md5("65a8e27d8879283831b664bd8b7f0ad4-2"), None);
+    }
+
+    #[tokio::test]
+    async fn test_checksum_layer() -> Result<()> {
+        let (raw, mock) = new_operator(memory::Builder::default().build()?);
+        let op = mock.layer(ChecksumLayer::with_algorithms(&[
+            ChecksumAlgorithm::Md5,
+            ChecksumAlgorithm::Crc32c,
+            ChecksumAlgorithm::Sha256,
+        ]));
+
+        op.object("test").write("Hello, World!").await?;
+        let meta = op.object("test").metadata().await?;
+        assert_eq!(meta.content_md5(), Some("ZajifYh5KDgxtmS9i38K1A=="));
+        assert_eq!(meta.content_crc32c(), Some("TVUQaA=="));
+        assert_eq!(
+            meta.content_sha256(),
+            Some("3/1gIbsr1bCvZ2KQgJ7DpTGR3YHH9wpLKGiKNiGCmG8=")
+        );
+        assert_eq!(op.object("test").read().await?, b"Hello, World!");
+
+        // Corrupt the stored content.
+        raw.object("test").write("Hello, World?").await?;
+        let err = op.object("test").read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        // Range read will not be verified.
+        assert_eq!(op.object("test").range_read(7..).await?, b"World?");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_checksum_layer_blocking() -> Result<()> {
+        let root = format!("/tmp/{}/", uuid::Uuid::new_v4());
+        let (raw, mock) = new_operator(fs::Builder::default().root(&root).build()?);
+        let op = mock.layer(ChecksumLayer::new());
+
+        op.object("test").blocking_write("Hello, World!")?;
+        let meta = op.object("test").blocking_metadata()?;
+        assert_eq!(meta.content_md5(), Some("ZajifYh5KDgxtmS9i38K1A=="));
+        assert_eq!(meta.content_sha256(), None);
+        assert_eq!(op.object("test").blocking_read()?, b"Hello, World!");
+
+        raw.object("test").blocking_write("Hello, World?")?;
+        let err = op.object("test").blocking_read().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        raw.object("test").blocking_delete()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Read;
+use std::io::Result;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::executor::block_on;
+use futures::io;
+use futures::io::Cursor;
+use futures::AsyncReadExt;
+
+use crate::error::new_unsupported_object_error;
+use crate::io_util::BlockingDecompressReader;
+use crate::io_util::CompressAlgorithm;
+use crate::io_util::CompressReader;
+use crate::io_util::DecompressReader;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesRead;
+use crate::BytesReader;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+
+/// CompressLayer will compress data while writing and decompress data while
+/// reading transparently.
+///
+/// By default, the compress algorithm is detected by the extension of path
+/// via [`CompressAlgorithm::from_path`], objects without known extension will
+/// not be touched. Use [`CompressLayer::with_algorithm`] to compress all
+/// objects with a fixed algorithm.
+///
+/// # Notes
+///
+/// - Data will be compressed in memory before writing, because the
+///   compressed size must be known before sending it to storage.
+/// - Range read applies to the decompressed content, so the whole object
+///   will be read and decompressed.
+/// - `stat` returns the metadata of the stored (compressed) object.
+/// - `write_multipart` is not supported for compressed objects.
+/// - `rename` is only supported if both paths use the same algorithm.
+///
+/// # Feature
+///
+/// This layer needs to enable feature `compress`.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::io_util::CompressAlgorithm;
+/// use opendal::layers::CompressLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(CompressLayer::with_algorithm(CompressAlgorithm::Zstd));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct CompressLayer {
+    algo: Option<CompressAlgorithm>,
+}
+
+impl CompressLayer {
+    /// Create a new CompressLayer which detects compress algorithm by path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new CompressLayer which compresses all objects with given
+    /// algorithm.
+    pub fn with_algorithm(algo: CompressAlgorithm) -> Self {
+        Self { algo: Some(algo) }
+    }
+}
+
+impl Layer for CompressLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(CompressAccessor {
+            algo: self.algo,
+            inner,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct CompressAccessor {
+    algo: Option<CompressAlgorithm>,
+    inner: Arc<dyn Accessor>,
+}
+
+impl CompressAccessor {
+    fn algorithm(&self, path: &str) -> Option<CompressAlgorithm> {
+        self.algo.or_else(|| CompressAlgorithm::from_path(path))
+    }
+
+    /// Build an OpRead to read the whole compressed object.
+    fn full_read_op(args: &OpRead) -> Result<OpRead> {
+        let op = OpRead::new(args.path(), ..)?;
+        Ok(match args.if_match() {
+            Some(etag) => op.with_if_match(etag),
+            None => op,
+        })
+    }
+}
+
+/// Compress all content of reader into memory.
+async fn compress(r: impl BytesRead, algo: CompressAlgorithm) -> Result<Vec<u8>> {
+    let mut bs = Cursor::new(Vec::new());
+    io::copy(CompressReader::new(r, algo), &mut bs).await?;
+    Ok(bs.into_inner())
+}
+
+#[async_trait]
+impl Accessor for CompressAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.create(args).await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let algo = match self.algorithm(args.path()) {
+            Some(algo) => algo,
+            None => return self.inner.read(args).await,
+        };
+
+        let r = self.inner.read(&Self::full_read_op(args)?).await?;
+        let mut r = DecompressReader::new(r, algo);
+
+        // Skip content before offset.
+        if let Some(offset) = args.offset() {
+            io::copy((&mut r).take(offset), &mut io::sink()).await?;
+        }
+
+        match args.size() {
+            Some(size) => Ok(Box::new(r.take(size))),
+            None => Ok(Box::new(r)),
+        }
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        let algo = match self.algorithm(args.path()) {
+            Some(algo) => algo,
+            None => return self.inner.write(args, r).await,
+        };
+
+        let bs = compress(r.take(args.size()), algo).await?;
+        let op = OpWrite::new(args.path(), bs.len() as u64)?;
+        self.inner.write(&op, Box::new(Cursor::new(bs))).await?;
+
+        Ok(args.size())
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.stat(args).await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.delete(args).await
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        if self.algorithm(args.from()) != self.algorithm(args.to()) {
+            return Err(new_unsupported_object_error(Operation::Rename, args.from()));
+        }
+
+        self.inner.rename(args).await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        self.inner.list(args).await
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        if self.algorithm(args.path()).is_some() {
+            return Err(new_unsupported_object_error(
+                Operation::CreateMultipart,
+                args.path(),
+            ));
+        }
+
+        self.inner.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        if self.algorithm(args.path()).is_some() {
+            return Err(new_unsupported_object_error(
+                Operation::WriteMultipart,
+                args.path(),
+            ));
+        }
+
+        self.inner.write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        self.inner.complete_multipart(args).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.inner.abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.blocking_create(args)
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        let algo = match self.algorithm(args.path()) {
+            Some(algo) => algo,
+            None => return self.inner.blocking_read(args),
+        };
+
+        let r = self.inner.blocking_read(&Self::full_read_op(args)?)?;
+        let mut r = BlockingDecompressReader::new(r, algo);
+
+        // Skip content before offset.
+        if let Some(offset) = args.offset() {
+            std::io::copy(&mut (&mut r).take(offset), &mut std::io::sink())?;
+        }
+
+        match args.size() {
+            Some(size) => Ok(Box::new(r.take(size))),
+            None => Ok(Box::new(r)),
+        }
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let algo = match self.algorithm(args.path()) {
+            Some(algo) => algo,
+            None => return self.inner.blocking_write(args, r),
+        };
+
+        let mut content = Vec::with_capacity(args.size() as usize);
+        r.take(args.size()).read_to_end(&mut content)?;
+
+        // Compress happens in memory, it's safe to block on it.
+        let bs = block_on(compress(Cursor::new(content), algo))?;
+        let op = OpWrite::new(args.path(), bs.len() as u64)?;
+        self.inner
+            .blocking_write(&op, Box::new(std::io::Cursor::new(bs)))?;
+
+        Ok(args.size())
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.blocking_stat(args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.blocking_delete(args)
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        if self.algorithm(args.from()) != self.algorithm(args.to()) {
+            return Err(new_unsupported_object_error(
+                Operation::BlockingRename,
+                args.from(),
+            ));
+        }
+
+        self.inner.blocking_rename(args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        self.inner.blocking_list(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::Operator;
+    use crate::Scheme;
+
+    #[tokio::test]
+    async fn test_compress_layer() -> Result<()> {
+        let op = Operator::from_env(Scheme::Memory)?;
+        let cop = op.clone().layer(CompressLayer::new());
+
+        let content = "Hello, World!".repeat(1024);
+
+        // Objects with known extension will be compressed.
+        cop.object("test.gz").write(content.clone()).await?;
+        let raw = op.object("test.gz").read().await?;
+        assert!(raw.len() < content.len());
+        let bs = op
+            .object("test.gz")
+            .decompress_read_with(CompressAlgorithm::Gzip)
+            .await?;
+        assert_eq!(bs, content.as_bytes());
+        assert_eq!(cop.object("test.gz").read().await?, content.as_bytes());
+        assert_eq!(
+            cop.object("test.gz").range_read(7..12).await?,
+            "World".as_bytes()
+        );
+
+        // Objects without known extension will not be touched.
+        cop.object("test").write(content.clone()).await?;
+        assert_eq!(op.object("test").read().await?, content.as_bytes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_layer_blocking() -> Result<()> {
+        let op = Operator::from_env(Scheme::Fs)?;
+        let cop = op
+            .clone()
+            .layer(CompressLayer::with_algorithm(CompressAlgorithm::Zstd));
+
+        let path = format!("/tmp/{}", uuid::Uuid::new_v4());
+        let content = "Hello, World!".repeat(1024);
+
+        cop.object(&path).blocking_write(content.clone())?;
+        let raw = op.object(&path).blocking_read()?;
+        assert!(raw.len() < content.len());
+
+        assert_eq!(cop.object(&path).blocking_read()?, content.as_bytes());
+        let mut s = String::new();
+        cop.object(&path)
+            .blocking_range_reader(7..12)?
+            .read_to_string(&mut s)?;
+        assert_eq!(s, "World");
+
+        Ok(())
+    }
+}
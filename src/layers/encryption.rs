@@ -0,0 +1,1079 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::min;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::io::Cursor;
+use futures::ready;
+use futures::AsyncRead;
+use futures::AsyncReadExt;
+use futures::StreamExt;
+use ring::aead::Aad;
+use ring::aead::LessSafeKey;
+use ring::aead::Nonce;
+use ring::aead::UnboundKey;
+use ring::aead::AES_256_GCM;
+use ring::aead::NONCE_LEN;
+use ring::rand::SecureRandom;
+use ring::rand::SystemRandom;
+
+use crate::accessor::AccessorCapability;
+use crate::error::new_unsupported_object_error;
+use crate::error::ObjectError;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesRead;
+use crate::BlockingBytesReader;
+use crate::BytesRead;
+use crate::BytesReader;
+use crate::DirEntry;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+use crate::ObjectMode;
+
+/// Magic number at the beginning of encrypted object.
+const MAGIC: &[u8; 4] = b"OENC";
+/// Version of encrypted object format.
+const VERSION: u8 = 1;
+/// Size of the header of encrypted object.
+///
+/// Header is fixed-sized so that we can calculate plaintext size from the
+/// size of encrypted object without reading it.
+const HEADER_SIZE: u64 = 256;
+/// Max size of wrapped data key, which is header size minus magic,
+/// version and key length.
+const MAX_WRAPPED_KEY_SIZE: usize = HEADER_SIZE as usize - 4 - 1 - 2;
+/// Size of plaintext in every chunk.
+const CHUNK_SIZE: u64 = 64 * 1024;
+/// Size of the authentication tag appended to every chunk.
+const TAG_SIZE: u64 = 16;
+/// Size of data key.
+const KEY_SIZE: usize = 32;
+
+/// KeyProvider is used by [`EncryptionLayer`] to protect per-object data
+/// keys.
+///
+/// Every object is encrypted by a random data key, the data key will be
+/// wrapped by the key provider and stored along with the object.
+///
+/// Implement this trait to integrate with key management services.
+pub trait KeyProvider: Debug + Send + Sync + 'static {
+    /// Wrap (encrypt) the data key.
+    ///
+    /// The wrapped key must not be larger than 249 bytes.
+    fn wrap_key(&self, key: &[u8]) -> Result<Vec<u8>>;
+
+    /// Unwrap (decrypt) the data key wrapped by [`KeyProvider::wrap_key`].
+    fn unwrap_key(&self, wrapped: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// AesKeyProvider wraps data keys with AES-256-GCM by a master key.
+///
+/// # Examples
+///
+/// ```
+/// use opendal::layers::AesKeyProvider;
+///
+/// let provider = AesKeyProvider::new([0; 32]);
+/// ```
+pub struct AesKeyProvider {
+    key: [u8; KEY_SIZE],
+}
+
+impl AesKeyProvider {
+    /// Create a new AesKeyProvider with 256 bits master key.
+    pub fn new(key: [u8; KEY_SIZE]) -> Self {
+        Self { key }
+    }
+
+    fn cipher(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.key).expect("key size must be valid"))
+    }
+}
+
+impl Debug for AesKeyProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AesKeyProvider")
+            .field("key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl KeyProvider for AesKeyProvider {
+    /// Wrapped key is formed by `nonce + ciphertext + tag`.
+    fn wrap_key(&self, key: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| Error::other(anyhow!("generate nonce failed")))?;
+
+        let mut bs = key.to_vec();
+        self.cipher()
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut bs)
+            .map_err(|_| Error::other(anyhow!("wrap key failed")))?;
+
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend_from_slice(&bs);
+        Ok(wrapped)
+    }
+
+    fn unwrap_key(&self, wrapped: &[u8]) -> Result<Vec<u8>> {
+        if wrapped.len() < NONCE_LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                anyhow!("wrapped key is too short"),
+            ));
+        }
+
+        let nonce = Nonce::try_assume_unique_for_key(&wrapped[..NONCE_LEN])
+            .expect("nonce size must be valid");
+        let mut bs = wrapped[NONCE_LEN..].to_vec();
+        let key = self
+            .cipher()
+            .open_in_place(nonce, Aad::empty(), &mut bs)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, anyhow!("unwrap key failed")))?;
+        Ok(key.to_vec())
+    }
+}
+
+/// EncryptionLayer will encrypt data before writing and decrypt data after
+/// reading transparently, which works over any services.
+///
+/// # Format
+///
+/// - Every object is encrypted by a random data key with AES-256-GCM, the
+///   data key is wrapped by [`KeyProvider`] and stored in the header.
+/// - Content is split into 64 KiB chunks, every chunk is authenticated on
+///   its own, so that range read only need to decrypt the chunks it covers.
+/// - Chunks are bound to their index and the last chunk is marked, so
+///   reordered or truncated chunks will be detected.
+/// - Chunks are bound to the object path too, so encrypted objects can't be
+///   moved or copied to other paths.
+///
+/// # Notes
+///
+/// - `stat` and `list` report the size of plaintext.
+/// - `presign` and multipart operations are not supported, since they
+///   would bypass the encryption.
+/// - Objects not written by this layer can't be read via this layer.
+/// - `rename` is not supported, since the path of object is bound into
+///   the ciphertext.
+///
+/// # Feature
+///
+/// This layer needs to enable feature `layers-encryption`.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::AesKeyProvider;
+/// use opendal::layers::EncryptionLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(EncryptionLayer::new(AesKeyProvider::new([0; 32])));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EncryptionLayer {
+    provider: Arc<dyn KeyProvider>,
+}
+
+impl EncryptionLayer {
+    /// Create a new EncryptionLayer with given key provider.
+    pub fn new(provider: impl KeyProvider) -> Self {
+        Self {
+            provider: Arc::new(provider),
+        }
+    }
+}
+
+impl Layer for EncryptionLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(EncryptionAccessor {
+            provider: self.provider.clone(),
+            inner,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptionAccessor {
+    provider: Arc<dyn KeyProvider>,
+    inner: Arc<dyn Accessor>,
+}
+
+/// Calculate the count of chunks for plaintext.
+///
+/// There is at least one chunk so that empty content is authenticated too.
+fn chunk_count(plain_size: u64) -> u64 {
+    if plain_size == 0 {
+        1
+    } else {
+        plain_size.div_ceil(CHUNK_SIZE)
+    }
+}
+
+/// Calculate the size of plaintext in chunk `idx`.
+fn chunk_plain_size(plain_size: u64, idx: u64) -> usize {
+    min(CHUNK_SIZE, plain_size - idx * CHUNK_SIZE) as usize
+}
+
+/// Calculate the size of encrypted object.
+fn cipher_size(plain_size: u64) -> u64 {
+    HEADER_SIZE + plain_size + chunk_count(plain_size) * TAG_SIZE
+}
+
+/// Calculate the size of plaintext, returns `None` if the size is not a
+/// valid encrypted object.
+fn plain_size(cipher_size: u64) -> Option<u64> {
+    let body = cipher_size.checked_sub(HEADER_SIZE)?;
+    let (full, rem) = (
+        body / (CHUNK_SIZE + TAG_SIZE),
+        body % (CHUNK_SIZE + TAG_SIZE),
+    );
+
+    match (full, rem) {
+        (0, 0) => None,
+        (_, 0) => Some(full * CHUNK_SIZE),
+        (_, rem) if rem >= TAG_SIZE => Some(full * CHUNK_SIZE + rem - TAG_SIZE),
+        _ => None,
+    }
+}
+
+/// ChunkCipher seals and opens chunks of an object.
+struct ChunkCipher {
+    key: LessSafeKey,
+    /// Path of the object, authenticated with every chunk.
+    path: String,
+    /// Count of chunks in the object.
+    chunks: u64,
+}
+
+impl ChunkCipher {
+    fn new(key: &[u8], path: &str, plain_size: u64) -> Result<Self> {
+        let key = UnboundKey::new(&AES_256_GCM, key)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, anyhow!("invalid data key")))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            path: path.to_string(),
+            chunks: chunk_count(plain_size),
+        })
+    }
+
+    /// Every object has its own data key, so chunk index is unique for key.
+    fn nonce(idx: u64) -> Nonce {
+        let mut nonce = [0; NONCE_LEN];
+        nonce[..8].copy_from_slice(&idx.to_be_bytes());
+        Nonce::assume_unique_for_key(nonce)
+    }
+
+    /// Bind the object path and mark the last chunk to detect truncation.
+    fn aad(&self, idx: u64) -> Aad<Vec<u8>> {
+        let mut aad = Vec::with_capacity(self.path.len() + 1);
+        aad.extend_from_slice(self.path.as_bytes());
+        aad.push((idx + 1 == self.chunks) as u8);
+        Aad::from(aad)
+    }
+
+    fn seal(&self, idx: u64, buf: &mut Vec<u8>) -> Result<()> {
+        self.key
+            .seal_in_place_append_tag(Self::nonce(idx), self.aad(idx), buf)
+            .map_err(|_| Error::other(anyhow!("encrypt chunk {idx} failed")))
+    }
+
+    fn open(&self, idx: u64, buf: &mut Vec<u8>) -> Result<()> {
+        let n = self
+            .key
+            .open_in_place(Self::nonce(idx), self.aad(idx), buf)
+            .map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    anyhow!("decrypt chunk {idx} failed"),
+                )
+            })?
+            .len();
+        buf.truncate(n);
+        Ok(())
+    }
+}
+
+impl EncryptionAccessor {
+    /// Generate a new data key and build the header that carrying it.
+    fn new_header(&self, path: &str, plain_size: u64) -> Result<(Vec<u8>, ChunkCipher)> {
+        let mut key = [0; KEY_SIZE];
+        SystemRandom::new().fill(&mut key).map_err(|_| {
+            Error::other(ObjectError::new(
+                Operation::Write,
+                path,
+                anyhow!("generate data key failed"),
+            ))
+        })?;
+
+        let wrapped = self.provider.wrap_key(&key)?;
+        if wrapped.len() > MAX_WRAPPED_KEY_SIZE {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                ObjectError::new(
+                    Operation::Write,
+                    path,
+                    anyhow!("wrapped key is too large: {}", wrapped.len()),
+                ),
+            ));
+        }
+
+        let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&(wrapped.len() as u16).to_le_bytes());
+        header.extend_from_slice(&wrapped);
+        header.resize(HEADER_SIZE as usize, 0);
+
+        Ok((header, ChunkCipher::new(&key, path, plain_size)?))
+    }
+
+    /// Parse header and unwrap the data key.
+    fn parse_header(&self, path: &str, header: &[u8], plain_size: u64) -> Result<ChunkCipher> {
+        let invalid = |msg| {
+            Error::new(
+                ErrorKind::InvalidData,
+                ObjectError::new(Operation::Read, path, msg),
+            )
+        };
+
+        if &header[0..4] != MAGIC {
+            return Err(invalid(anyhow!("object is not encrypted")));
+        }
+        if header[4] != VERSION {
+            return Err(invalid(anyhow!("unsupported version: {}", header[4])));
+        }
+        let len = u16::from_le_bytes([header[5], header[6]]) as usize;
+        if len > MAX_WRAPPED_KEY_SIZE {
+            return Err(invalid(anyhow!("invalid wrapped key size: {len}")));
+        }
+
+        let key = self.provider.unwrap_key(&header[7..7 + len])?;
+        ChunkCipher::new(&key, path, plain_size)
+    }
+
+    fn plain_size(path: &str, cipher_size: u64) -> Result<u64> {
+        plain_size(cipher_size).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                ObjectError::new(
+                    Operation::Stat,
+                    path,
+                    anyhow!("invalid encrypted object size: {cipher_size}"),
+                ),
+            )
+        })
+    }
+
+    /// Convert metadata of encrypted object into plaintext's.
+    ///
+    /// `content_md5` is dropped since it's the checksum of encrypted object.
+    fn plain_metadata(path: &str, meta: ObjectMetadata) -> Result<ObjectMetadata> {
+        let mut m = ObjectMetadata::default();
+        m.set_mode(meta.mode());
+        if meta.mode() == ObjectMode::FILE {
+            m.set_content_length(Self::plain_size(path, meta.content_length())?);
+        }
+        if let Some(v) = meta.last_modified() {
+            m.set_last_modified(v);
+        }
+        if let Some(v) = meta.etag() {
+            m.set_etag(v);
+        }
+        Ok(m)
+    }
+
+    /// Convert entry of encrypted object into plaintext's, and make sure
+    /// lazy metadata fetching goes through this layer.
+    fn plain_entry(self: &Arc<Self>, de: DirEntry) -> DirEntry {
+        let mut entry = DirEntry::new(self.clone(), de.mode(), de.path());
+        if let Some(v) = de.content_length().and_then(plain_size) {
+            entry.set_content_length(v);
+        }
+        if let Some(v) = de.last_modified() {
+            entry.set_last_modified(v);
+        }
+        if let Some(v) = de.etag() {
+            entry.set_etag(v);
+        }
+        entry
+    }
+
+    /// Resolve the range of plaintext and the range of encrypted object
+    /// that covers it.
+    ///
+    /// Returns `None` if there is nothing to read.
+    fn resolve_range(
+        args: &OpRead,
+        plain_size: u64,
+        cipher_size: u64,
+    ) -> Result<Option<(u64, u64, u64, u64)>> {
+        let offset = args.offset().unwrap_or_default();
+        if offset > plain_size {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                ObjectError::new(
+                    Operation::Read,
+                    args.path(),
+                    anyhow!("offset {offset} is out of range {plain_size}"),
+                ),
+            ));
+        }
+        let size = min(
+            args.size().unwrap_or(plain_size - offset),
+            plain_size - offset,
+        );
+        if size == 0 {
+            return Ok(None);
+        }
+
+        let first = offset / CHUNK_SIZE;
+        let last = (offset + size - 1) / CHUNK_SIZE;
+        let start = HEADER_SIZE + first * (CHUNK_SIZE + TAG_SIZE);
+        let end = min(
+            HEADER_SIZE + (last + 1) * (CHUNK_SIZE + TAG_SIZE),
+            cipher_size,
+        );
+
+        Ok(Some((offset, size, start, end - start)))
+    }
+
+    fn inner_read_op(args: &OpRead, offset: u64, size: u64) -> Result<OpRead> {
+        let op = OpRead::new_with_offset(args.path(), Some(offset), Some(size))?;
+        Ok(match args.if_match() {
+            Some(etag) => op.with_if_match(etag),
+            None => op,
+        })
+    }
+}
+
+#[async_trait]
+impl Accessor for EncryptionAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        meta.set_capabilities(meta.capabilities() - AccessorCapability::Rename);
+
+        meta
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        match args.mode() {
+            // Empty file should be encrypted too.
+            ObjectMode::FILE => {
+                let op = OpWrite::new(args.path(), 0)?;
+                self.write(&op, Box::new(Cursor::new(vec![]))).await?;
+                Ok(())
+            }
+            _ => self.inner.create(args).await,
+        }
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let path = args.path();
+        let cipher_size = self.inner.stat(&OpStat::new(path)?).await?.content_length();
+        let plain_size = Self::plain_size(path, cipher_size)?;
+
+        let (offset, size, start, len) = match Self::resolve_range(args, plain_size, cipher_size)? {
+            Some(v) => v,
+            None => return Ok(Box::new(Cursor::new(vec![]))),
+        };
+
+        let mut header = vec![0; HEADER_SIZE as usize];
+        self.inner
+            .read(&Self::inner_read_op(args, 0, HEADER_SIZE)?)
+            .await?
+            .read_exact(&mut header)
+            .await?;
+        let cipher = self.parse_header(path, &header, plain_size)?;
+
+        let r = self
+            .inner
+            .read(&Self::inner_read_op(args, start, len)?)
+            .await?;
+        let r = DecryptReader::new(r, cipher, plain_size, offset);
+
+        Ok(Box::new(r.take(size)))
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        let (header, cipher) = self.new_header(args.path(), args.size())?;
+        let r = EncryptReader::new(r.take(args.size()), header, cipher, args.size());
+
+        let op = OpWrite::new(args.path(), cipher_size(args.size()))?;
+        self.inner.write(&op, Box::new(r)).await?;
+
+        Ok(args.size())
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        let meta = self.inner.stat(args).await?;
+        Self::plain_metadata(args.path(), meta)
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.delete(args).await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let acc = Arc::new(self.clone());
+        let ds = self.inner.list(args).await?;
+
+        Ok(Box::new(ds.map(move |de| de.map(|de| acc.plain_entry(de)))))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        Err(new_unsupported_object_error(
+            Operation::Presign,
+            args.path(),
+        ))
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        Err(new_unsupported_object_error(
+            Operation::CreateMultipart,
+            args.path(),
+        ))
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, _: BytesReader) -> Result<ObjectPart> {
+        Err(new_unsupported_object_error(
+            Operation::WriteMultipart,
+            args.path(),
+        ))
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::CompleteMultipart,
+            args.path(),
+        ))
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::AbortMultipart,
+            args.path(),
+        ))
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        match args.mode() {
+            // Empty file should be encrypted too.
+            ObjectMode::FILE => {
+                let op = OpWrite::new(args.path(), 0)?;
+                self.blocking_write(&op, Box::new(std::io::Cursor::new(vec![])))?;
+                Ok(())
+            }
+            _ => self.inner.blocking_create(args),
+        }
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        let path = args.path();
+        let cipher_size = self
+            .inner
+            .blocking_stat(&OpStat::new(path)?)?
+            .content_length();
+        let plain_size = Self::plain_size(path, cipher_size)?;
+
+        let (offset, size, start, len) = match Self::resolve_range(args, plain_size, cipher_size)? {
+            Some(v) => v,
+            None => return Ok(Box::new(std::io::Cursor::new(vec![]))),
+        };
+
+        let mut header = vec![0; HEADER_SIZE as usize];
+        self.inner
+            .blocking_read(&Self::inner_read_op(args, 0, HEADER_SIZE)?)?
+            .read_exact(&mut header)?;
+        let cipher = self.parse_header(path, &header, plain_size)?;
+
+        let r = self
+            .inner
+            .blocking_read(&Self::inner_read_op(args, start, len)?)?;
+        let r = BlockingDecryptReader::new(r, cipher, plain_size, offset);
+
+        Ok(Box::new(r.take(size)))
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let (header, cipher) = self.new_header(args.path(), args.size())?;
+        let r = BlockingEncryptReader::new(r.take(args.size()), header, cipher, args.size());
+
+        let op = OpWrite::new(args.path(), cipher_size(args.size()))?;
+        self.inner.blocking_write(&op, Box::new(r))?;
+
+        Ok(args.size())
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        let meta = self.inner.blocking_stat(args)?;
+        Self::plain_metadata(args.path(), meta)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.blocking_delete(args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let acc = Arc::new(self.clone());
+        let it = self.inner.blocking_list(args)?;
+
+        Ok(Box::new(it.map(move |de| de.map(|de| acc.plain_entry(de)))))
+    }
+}
+
+/// Poll inner reader until `buf[*filled..]` is full.
+fn poll_fill<R: BytesRead>(
+    r: &mut R,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+    filled: &mut usize,
+) -> Poll<Result<()>> {
+    while *filled < buf.len() {
+        let n = ready!(Pin::new(&mut *r).poll_read(cx, &mut buf[*filled..]))?;
+        if n == 0 {
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                anyhow!("reader got unexpected eof"),
+            )));
+        }
+        *filled += n;
+    }
+    Poll::Ready(Ok(()))
+}
+
+/// EncryptReader produces the encrypted object from plaintext reader.
+struct EncryptReader<R: BytesRead> {
+    inner: R,
+    cipher: ChunkCipher,
+    plain_size: u64,
+
+    /// Index of the next chunk to seal.
+    idx: u64,
+    /// Chunk that being filled.
+    chunk: Vec<u8>,
+    filled: usize,
+    /// Data that ready to be read.
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: BytesRead> EncryptReader<R> {
+    fn new(inner: R, header: Vec<u8>, cipher: ChunkCipher, plain_size: u64) -> Self {
+        Self {
+            inner,
+            cipher,
+            plain_size,
+            idx: 0,
+            chunk: Vec::new(),
+            filled: 0,
+            buf: header,
+            pos: 0,
+        }
+    }
+}
+
+impl<R: BytesRead> AsyncRead for EncryptReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.pos < this.buf.len() {
+                let n = min(buf.len(), this.buf.len() - this.pos);
+                buf[..n].copy_from_slice(&this.buf[this.pos..this.pos + n]);
+                this.pos += n;
+                return Poll::Ready(Ok(n));
+            }
+            if this.idx == this.cipher.chunks {
+                return Poll::Ready(Ok(0));
+            }
+
+            let size = chunk_plain_size(this.plain_size, this.idx);
+            this.chunk.resize(size, 0);
+            ready!(poll_fill(
+                &mut this.inner,
+                cx,
+                &mut this.chunk,
+                &mut this.filled
+            ))?;
+
+            this.cipher.seal(this.idx, &mut this.chunk)?;
+            std::mem::swap(&mut this.buf, &mut this.chunk);
+            this.pos = 0;
+            this.filled = 0;
+            this.idx += 1;
+        }
+    }
+}
+
+/// DecryptReader produces plaintext from the encrypted chunks that starting
+/// at the chunk containing `offset`.
+struct DecryptReader<R: BytesRead> {
+    inner: R,
+    cipher: ChunkCipher,
+    plain_size: u64,
+
+    /// Index of the next chunk to open.
+    idx: u64,
+    /// Plaintext to skip in the first chunk.
+    skip: usize,
+    /// Chunk that being filled.
+    chunk: Vec<u8>,
+    filled: usize,
+    /// Plaintext that ready to be read.
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: BytesRead> DecryptReader<R> {
+    fn new(inner: R, cipher: ChunkCipher, plain_size: u64, offset: u64) -> Self {
+        Self {
+            inner,
+            cipher,
+            plain_size,
+            idx: offset / CHUNK_SIZE,
+            skip: (offset % CHUNK_SIZE) as usize,
+            chunk: Vec::new(),
+            filled: 0,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl<R: BytesRead> AsyncRead for DecryptReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.pos < this.buf.len() {
+                let n = min(buf.len(), this.buf.len() - this.pos);
+                buf[..n].copy_from_slice(&this.buf[this.pos..this.pos + n]);
+                this.pos += n;
+                return Poll::Ready(Ok(n));
+            }
+            if this.idx == this.cipher.chunks {
+                return Poll::Ready(Ok(0));
+            }
+
+            let size = chunk_plain_size(this.plain_size, this.idx) + TAG_SIZE as usize;
+            this.chunk.resize(size, 0);
+            ready!(poll_fill(
+                &mut this.inner,
+                cx,
+                &mut this.chunk,
+                &mut this.filled
+            ))?;
+
+            this.cipher.open(this.idx, &mut this.chunk)?;
+            std::mem::swap(&mut this.buf, &mut this.chunk);
+            this.pos = std::mem::take(&mut this.skip);
+            this.filled = 0;
+            this.idx += 1;
+        }
+    }
+}
+
+/// Blocking version of [`EncryptReader`].
+struct BlockingEncryptReader<R: BlockingBytesRead> {
+    inner: R,
+    cipher: ChunkCipher,
+    plain_size: u64,
+
+    idx: u64,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: BlockingBytesRead> BlockingEncryptReader<R> {
+    fn new(inner: R, header: Vec<u8>, cipher: ChunkCipher, plain_size: u64) -> Self {
+        Self {
+            inner,
+            cipher,
+            plain_size,
+            idx: 0,
+            buf: header,
+            pos: 0,
+        }
+    }
+}
+
+impl<R: BlockingBytesRead> Read for BlockingEncryptReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.pos == self.buf.len() {
+            if self.idx == self.cipher.chunks {
+                return Ok(0);
+            }
+
+            self.buf
+                .resize(chunk_plain_size(self.plain_size, self.idx), 0);
+            self.inner.read_exact(&mut self.buf)?;
+            self.cipher.seal(self.idx, &mut self.buf)?;
+            self.pos = 0;
+            self.idx += 1;
+        }
+
+        let n = min(buf.len(), self.buf.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Blocking version of [`DecryptReader`].
+struct BlockingDecryptReader<R: BlockingBytesRead> {
+    inner: R,
+    cipher: ChunkCipher,
+    plain_size: u64,
+
+    idx: u64,
+    skip: usize,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: BlockingBytesRead> BlockingDecryptReader<R> {
+    fn new(inner: R, cipher: ChunkCipher, plain_size: u64, offset: u64) -> Self {
+        Self {
+            inner,
+            cipher,
+            plain_size,
+            idx: offset / CHUNK_SIZE,
+            skip: (offset % CHUNK_SIZE) as usize,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl<R: BlockingBytesRead> Read for BlockingDecryptReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        while self.pos == self.buf.len() {
+            if self.idx == self.cipher.chunks {
+                return Ok(0);
+            }
+
+            let size = chunk_plain_size(self.plain_size, self.idx) + TAG_SIZE as usize;
+            self.buf.resize(size, 0);
+            self.inner.read_exact(&mut self.buf)?;
+            self.cipher.open(self.idx, &mut self.buf)?;
+            self.pos = std::mem::take(&mut self.skip);
+            self.idx += 1;
+        }
+
+        let n = min(buf.len(), self.buf.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::Operator;
+    use crate::Scheme;
+
+    fn gen_content(size: usize) -> Vec<u8> {
+        (0..size).map(|v| (v % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_plain_size() {
+        for size in [
+            0,
+            1,
+            CHUNK_SIZE - 1,
+            CHUNK_SIZE,
+            CHUNK_SIZE + 1,
+            3 * CHUNK_SIZE,
+        ] {
+            assert_eq!(plain_size(cipher_size(size)), Some(size), "size {size}");
+        }
+
+        assert_eq!(plain_size(0), None);
+        assert_eq!(plain_size(HEADER_SIZE), None);
+        assert_eq!(plain_size(HEADER_SIZE + TAG_SIZE - 1), None);
+    }
+
+    #[tokio::test]
+    async fn test_encryption_layer() -> Result<()> {
+        let op = Operator::from_env(Scheme::Memory)?;
+        let eop = op
+            .clone()
+            .layer(EncryptionLayer::new(AesKeyProvider::new([1; 32])));
+
+        let content = gen_content(3 * CHUNK_SIZE as usize + 100);
+        eop.object("dir/test").write(content.clone()).await?;
+
+        // Stored content must be encrypted.
+        let raw = op.object("dir/test").read().await?;
+        assert_eq!(raw.len() as u64, cipher_size(content.len() as u64));
+        assert!(!raw.windows(100).any(|w| w == &content[..100]));
+
+        assert_eq!(eop.object("dir/test").read().await?, content);
+        assert_eq!(
+            eop.object("dir/test").metadata().await?.content_length(),
+            content.len() as u64
+        );
+        for (start, end) in [
+            (0, 10),
+            (100, CHUNK_SIZE + 100),
+            (CHUNK_SIZE - 1, CHUNK_SIZE + 1),
+            (2 * CHUNK_SIZE, 3 * CHUNK_SIZE + 100),
+            (3 * CHUNK_SIZE + 99, 3 * CHUNK_SIZE + 100),
+        ] {
+            let bs = eop.object("dir/test").range_read(start..end).await?;
+            assert_eq!(bs, content[start as usize..end as usize], "{start}..{end}");
+        }
+        let bs = eop.object("dir/test").range_read(CHUNK_SIZE..).await?;
+        assert_eq!(bs, content[CHUNK_SIZE as usize..]);
+
+        // Empty object.
+        eop.object("dir/empty").create().await?;
+        assert_eq!(eop.object("dir/empty").read().await?, Vec::<u8>::new());
+
+        let mut map = HashMap::new();
+        let mut ds = eop.object("dir/").list().await?;
+        while let Some(de) = ds.try_next().await? {
+            map.insert(de.path().to_string(), de.metadata().await?.content_length());
+        }
+        assert_eq!(map["dir/test"], content.len() as u64);
+        assert_eq!(map["dir/empty"], 0);
+
+        // Other key can't decrypt it.
+        let other = op
+            .clone()
+            .layer(EncryptionLayer::new(AesKeyProvider::new([2; 32])));
+        assert!(other.object("dir/test").read().await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encryption_layer_tampered() -> Result<()> {
+        let op = Operator::from_env(Scheme::Memory)?;
+        let eop = op
+            .clone()
+            .layer(EncryptionLayer::new(AesKeyProvider::new([1; 32])));
+
+        let content = gen_content(2 * CHUNK_SIZE as usize + 100);
+        eop.object("test").write(content.clone()).await?;
+        let raw = op.object("test").read().await?;
+
+        // Modified chunk.
+        let mut bs = raw.clone();
+        bs[HEADER_SIZE as usize + 10] ^= 1;
+        op.object("test").write(bs).await?;
+        let err = eop.object("test").read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        // Other chunks are still readable.
+        let bs = eop
+            .object("test")
+            .range_read(CHUNK_SIZE..CHUNK_SIZE + 10)
+            .await?;
+        assert_eq!(bs, content[CHUNK_SIZE as usize..CHUNK_SIZE as usize + 10]);
+
+        // Truncated at chunk boundary.
+        let bs = raw[..(HEADER_SIZE + 2 * (CHUNK_SIZE + TAG_SIZE)) as usize].to_vec();
+        op.object("test").write(bs).await?;
+        let err = eop.object("test").read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        // Moved to another path.
+        op.object("moved").write(raw).await?;
+        let err = eop.object("moved").read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encryption_layer_blocking() -> Result<()> {
+        let op = Operator::from_env(Scheme::Fs)?;
+        let eop = op
+            .clone()
+            .layer(EncryptionLayer::new(AesKeyProvider::new([1; 32])));
+
+        let path = format!("/tmp/{}", uuid::Uuid::new_v4());
+        let content = gen_content(CHUNK_SIZE as usize + 100);
+
+        eop.object(&path).blocking_write(content.clone())?;
+        let raw = op.object(&path).blocking_read()?;
+        assert_eq!(raw.len() as u64, cipher_size(content.len() as u64));
+
+        assert_eq!(eop.object(&path).blocking_read()?, content);
+        assert_eq!(
+            eop.object(&path).blocking_metadata()?.content_length(),
+            content.len() as u64
+        );
+        let bs = eop
+            .object(&path)
+            .blocking_range_read(CHUNK_SIZE - 10..CHUNK_SIZE + 10)?;
+        assert_eq!(
+            bs,
+            content[CHUNK_SIZE as usize - 10..CHUNK_SIZE as usize + 10]
+        );
+
+        op.object(&path).blocking_delete()?;
+        Ok(())
+    }
+}
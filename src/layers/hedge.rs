@@ -0,0 +1,435 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::future::Future;
+use std::io::Result;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::future::select;
+use futures::future::Either;
+use futures::pin_mut;
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+
+/// Max latency samples kept for every operation.
+const MAX_SAMPLES: usize = 1024;
+/// Min latency samples required before using observed percentile.
+const MIN_SAMPLES: usize = 32;
+
+/// HedgeLayer will add hedged requests for OpenDAL.
+///
+/// If `read` or `stat` doesn't respond in the delay, a duplicate request
+/// will be sent. The first succeeded response will be used and the other
+/// one will be cancelled.
+///
+/// The delay could be:
+///
+/// - fixed: set by [`HedgeLayer::new`].
+/// - observed: set by [`HedgeLayer::with_percentile`], the given percentile
+///   of recent latencies will be used as delay.
+///
+/// Hedged requests are limited by budget, which is the max ratio of hedged
+/// requests to all requests. Default to `0.1`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::layers::HedgeLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(
+///         HedgeLayer::new(Duration::from_millis(100))
+///             .with_percentile(0.95)
+///             .with_budget(0.05),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct HedgeLayer {
+    delay: Duration,
+    percentile: Option<f64>,
+    budget: f64,
+}
+
+impl HedgeLayer {
+    /// Create a new hedge layer which sends hedged requests after `delay`.
+    pub fn new(delay: Duration) -> Self {
+        HedgeLayer {
+            delay,
+            percentile: None,
+            budget: 0.1,
+        }
+    }
+
+    /// Use the percentile of observed latencies as delay.
+    ///
+    /// The delay set by [`HedgeLayer::new`] will be used until we have
+    /// enough samples.
+    ///
+    /// # Panics
+    ///
+    /// `percentile` must be in `(0.0, 1.0]`.
+    pub fn with_percentile(mut self, percentile: f64) -> Self {
+        assert!(
+            percentile > 0.0 && percentile <= 1.0,
+            "percentile must be in (0.0, 1.0]"
+        );
+
+        self.percentile = Some(percentile);
+        self
+    }
+
+    /// Set the max ratio of hedged requests to all requests.
+    ///
+    /// Set to `0.0` to disable hedged requests.
+    pub fn with_budget(mut self, budget: f64) -> Self {
+        self.budget = budget.max(0.0);
+        self
+    }
+}
+
+impl Layer for HedgeLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(HedgeAccessor {
+            inner,
+            layer: self.clone(),
+            requests: AtomicU64::new(0),
+            hedged_requests: AtomicU64::new(0),
+            read_latencies: Latencies::default(),
+            stat_latencies: Latencies::default(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct HedgeAccessor {
+    inner: Arc<dyn Accessor>,
+    layer: HedgeLayer,
+
+    requests: AtomicU64,
+    hedged_requests: AtomicU64,
+    read_latencies: Latencies,
+    stat_latencies: Latencies,
+}
+
+impl HedgeAccessor {
+    /// Try to acquire budget for a hedged request.
+    ///
+    /// At least one hedged request is allowed so that we can hedge while
+    /// there are only a few requests.
+    fn acquire_budget(&self) -> bool {
+        if self.layer.budget <= 0.0 {
+            return false;
+        }
+
+        let requests = self.requests.load(Ordering::Relaxed);
+        let hedged = self.hedged_requests.fetch_add(1, Ordering::Relaxed);
+        if (hedged + 1) as f64 <= (requests as f64 * self.layer.budget).max(1.0) {
+            return true;
+        }
+
+        self.hedged_requests.fetch_sub(1, Ordering::Relaxed);
+        false
+    }
+
+    async fn hedge<T, F, Fut>(&self, latencies: &Latencies, f: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+
+        let delay = self
+            .layer
+            .percentile
+            .and_then(|p| latencies.percentile(p))
+            .unwrap_or(self.layer.delay);
+
+        let start = Instant::now();
+        let primary = f();
+        pin_mut!(primary);
+
+        let res = match tokio::time::timeout(delay, &mut primary).await {
+            Ok(v) => v,
+            Err(_) if !self.acquire_budget() => primary.await,
+            Err(_) => {
+                let hedged = f();
+                pin_mut!(hedged);
+
+                match select(primary, hedged).await {
+                    Either::Left((Ok(v), _)) | Either::Right((Ok(v), _)) => Ok(v),
+                    // Wait for the other one if the first responded request
+                    // failed.
+                    Either::Left((Err(_), other)) => other.await,
+                    Either::Right((Err(_), other)) => other.await,
+                }
+            }
+        };
+
+        if res.is_ok() {
+            latencies.record(start.elapsed());
+        }
+        res
+    }
+}
+
+#[async_trait]
+impl Accessor for HedgeAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.create(args).await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        self.hedge(&self.read_latencies, || self.inner.read(args))
+            .await
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        self.inner.write(args, r).await
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.hedge(&self.read_latencies, || self.inner.read_stream(args))
+            .await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.inner.write_stream(args, s).await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.hedge(&self.stat_latencies, || self.inner.stat(args))
+            .await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.delete(args).await
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.rename(args).await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        self.inner.list(args).await
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.inner.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        self.inner.write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        self.inner.complete_multipart(args).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.inner.abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.blocking_create(args)
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.inner.blocking_read(args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.inner.blocking_write(args, r)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.blocking_stat(args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.blocking_delete(args)
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.blocking_rename(args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        self.inner.blocking_list(args)
+    }
+}
+
+/// Latencies keeps recent latencies of an operation.
+#[derive(Debug, Default)]
+struct Latencies(Mutex<VecDeque<Duration>>);
+
+impl Latencies {
+    fn record(&self, latency: Duration) {
+        let mut samples = self.0.lock();
+        if samples.len() >= MAX_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+    }
+
+    /// Returns `None` if we don't have enough samples.
+    fn percentile(&self, p: f64) -> Option<Duration> {
+        let mut samples: Vec<Duration> = {
+            let samples = self.0.lock();
+            if samples.len() < MIN_SAMPLES {
+                return None;
+            }
+            samples.iter().copied().collect()
+        };
+        samples.sort_unstable();
+
+        let idx = ((samples.len() as f64 * p).ceil() as usize).clamp(1, samples.len()) - 1;
+        Some(samples[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::sync::atomic::AtomicUsize;
+
+    use anyhow::anyhow;
+
+    use super::*;
+    use crate::ObjectMode;
+    use crate::Operator;
+
+    /// MockService will hang on the first `stat`, and fail on the first
+    /// `read`.
+    #[derive(Debug, Default)]
+    struct MockService {
+        stat_calls: AtomicUsize,
+        read_calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        fn metadata(&self) -> AccessorMetadata {
+            AccessorMetadata::default()
+        }
+
+        async fn read(&self, _: &OpRead) -> Result<BytesReader> {
+            if self.read_calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                return Err(Error::new(ErrorKind::Other, anyhow!("read failed")));
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok(Box::new(futures::io::Cursor::new(b"hello".to_vec())))
+        }
+
+        async fn stat(&self, _: &OpStat) -> Result<ObjectMetadata> {
+            if self.stat_calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            }
+            let mut meta = ObjectMetadata::default();
+            meta.set_mode(ObjectMode::FILE);
+            Ok(meta)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hedge() {
+        let srv = Arc::new(MockService::default());
+        let op = Operator::new(srv.clone())
+            .layer(HedgeLayer::new(Duration::from_millis(10)).with_budget(1.0));
+
+        let start = Instant::now();
+        op.object("test")
+            .metadata()
+            .await
+            .expect("stat must succeed");
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(srv.stat_calls.load(Ordering::SeqCst), 2);
+
+        // The primary request failed after hedged request sent, so we
+        // should use the hedged one.
+        let bs = op.object("test").read().await.expect("read must succeed");
+        assert_eq!(bs, b"hello");
+        assert_eq!(srv.read_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_hedge_budget() {
+        let srv = Arc::new(MockService::default());
+        let op = Operator::new(srv.clone())
+            .layer(HedgeLayer::new(Duration::from_millis(10)).with_budget(0.0));
+
+        op.object("test")
+            .read()
+            .await
+            .expect_err("read must fail without hedge");
+        assert_eq!(srv.read_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_latencies_percentile() {
+        let latencies = Latencies::default();
+        assert_eq!(latencies.percentile(0.9), None);
+
+        for i in 1..=100 {
+            latencies.record(Duration::from_millis(i));
+        }
+        assert_eq!(latencies.percentile(0.9), Some(Duration::from_millis(90)));
+        assert_eq!(latencies.percentile(1.0), Some(Duration::from_millis(100)));
+    }
+}
@@ -30,8 +30,9 @@ use crate::ops::{
     OpWriteMultipart,
 };
 use crate::ops::{OpDelete, PresignedRequest};
-use crate::ops::{OpRead, OpStat};
+use crate::ops::{OpRead, OpRename, OpStat};
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirIterator;
 use crate::DirStreamer;
 use crate::Layer;
@@ -156,6 +157,14 @@ impl Accessor for ImmutableIndexAccessor {
         self.inner.write(args, r).await
     }
 
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.inner.read_stream(args).await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.inner.write_stream(args, s).await
+    }
+
     async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
         self.inner.stat(args).await
     }
@@ -164,6 +173,10 @@ impl Accessor for ImmutableIndexAccessor {
         self.inner.delete(args).await
     }
 
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.rename(args).await
+    }
+
     async fn list(&self, args: &OpList) -> Result<DirStreamer> {
         let mut path = args.path();
         if path == "/" {
@@ -216,6 +229,10 @@ impl Accessor for ImmutableIndexAccessor {
         self.inner.blocking_delete(args)
     }
 
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.blocking_rename(args)
+    }
+
     fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
         let mut path = args.path();
         if path == "/" {
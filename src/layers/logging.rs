@@ -22,6 +22,7 @@ use std::task::Context;
 use std::task::Poll;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::AsyncRead;
 use futures::Stream;
 use log::debug;
@@ -38,6 +39,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpRename;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -47,6 +49,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirEntry;
 use crate::DirIterator;
 use crate::DirStreamer;
@@ -231,6 +234,81 @@ impl Accessor for LoggingAccessor {
             })
     }
 
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        debug!(
+            target: "opendal::services",
+            "service={} operation={} path={} offset={:?} size={:?} -> started",
+            self.scheme, Operation::Read, args.path(), args.offset(), args.size()
+        );
+
+        self.inner
+            .read_stream(args)
+            .await
+            .map(|v| {
+                debug!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} offset={:?} size={:?} -> got stream",
+                    self.scheme, Operation::Read, args.path(),
+                    args.offset(), args.size()
+                );
+                let s = LoggingBytesStream::new(self.scheme, Operation::Read, args.path(), v);
+                Box::new(s) as BytesStreamer
+            })
+            .map_err(|err| {
+                if err.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} offset={:?} size={:?} -> failed: {err:?}",
+                        self.scheme, Operation::Read, args.path(),args.offset(),  args.size());
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} offset={:?} size={:?} -> errored: {err:?}",
+                        self.scheme, Operation::Read, args.path(), args.offset(),  args.size());
+                };
+                err
+            })
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        debug!(
+            target: "opendal::services",
+            "service={} operation={} path={} size={:?} -> started",
+            self.scheme, Operation::Write, args.path(), args.size()
+        );
+
+        let stream = LoggingBytesStream::new(self.scheme, Operation::Write, args.path(), s);
+        let s = Box::new(stream) as BytesStreamer;
+
+        self.inner
+            .write_stream(args, s)
+            .await
+            .map(|v| {
+                debug!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} size={:?} -> written",
+                    self.scheme, Operation::Write, args.path(), args.size()
+                );
+                v
+            })
+            .map_err(|err| {
+                if err.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} size={:?} -> failed: {err:?}",
+                        self.scheme, Operation::Write, args.path(), args.size()
+                    );
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} size={:?} -> errored: {err:?}",
+                        self.scheme, Operation::Write, args.path(), args.size()
+                    );
+                };
+                err
+            })
+    }
+
     async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
         debug!(
             target: "opendal::services",
@@ -300,6 +378,39 @@ impl Accessor for LoggingAccessor {
             })
     }
 
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        debug!(
+            target: "opendal::services",
+            "service={} operation={} from={} to={} -> started",
+            self.scheme, Operation::Rename, args.from(), args.to()
+        );
+
+        self.inner
+            .rename(args)
+            .await
+            .map(|v| {
+                debug!(
+                    target: "opendal::services",
+                    "service={} operation={} from={} to={} -> finished",
+                    self.scheme, Operation::Rename, args.from(), args.to());
+                v
+            })
+            .map_err(|err| {
+                if err.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} from={} to={} -> failed: {err:?}",
+                        self.scheme, Operation::Rename, args.from(), args.to());
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} from={} to={} -> errored: {err:?}",
+                        self.scheme, Operation::Rename, args.from(), args.to());
+                };
+                err
+            })
+    }
+
     async fn list(&self, args: &OpList) -> Result<DirStreamer> {
         debug!(
             target: "opendal::services",
@@ -746,6 +857,41 @@ impl Accessor for LoggingAccessor {
             })
     }
 
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        debug!(
+            target: "opendal::services",
+            "service={} operation={} from={} to={} -> started",
+            self.scheme,
+            Operation::BlockingRename,
+            args.from(),
+            args.to()
+        );
+
+        self.inner
+            .blocking_rename(args)
+            .map(|v| {
+                debug!(
+                    target: "opendal::services",
+                    "service={} operation={} from={} to={} -> finished",
+                    self.scheme, Operation::BlockingRename, args.from(), args.to());
+                v
+            })
+            .map_err(|err| {
+                if err.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} from={} to={} -> failed: {err:?}",
+                        self.scheme, Operation::BlockingRename, args.from(), args.to());
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} from={} to={} -> errored: {err:?}",
+                        self.scheme, Operation::BlockingRename, args.from(), args.to());
+                };
+                err
+            })
+    }
+
     fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
         debug!(
             target: "opendal::services",
@@ -854,6 +1000,72 @@ impl AsyncRead for LoggingReader {
     }
 }
 
+/// `LoggingBytesStream` is a wrapper of `BytesStreamer`, with logging functionality.
+struct LoggingBytesStream {
+    scheme: Scheme,
+    path: String,
+    op: Operation,
+    has_read: u64,
+    inner: BytesStreamer,
+}
+
+impl LoggingBytesStream {
+    fn new(scheme: Scheme, op: Operation, path: &str, inner: BytesStreamer) -> Self {
+        Self {
+            scheme,
+            op,
+            path: path.to_string(),
+            has_read: 0,
+            inner,
+        }
+    }
+}
+
+impl Stream for LoggingBytesStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut (*self.inner)).poll_next(cx) {
+            Poll::Ready(Some(Ok(bs))) => {
+                self.has_read += bs.len() as u64;
+                trace!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} has_read={} -> {}: {}B",
+                    self.scheme, self.op, self.path, self.has_read, self.op, bs.len());
+                Poll::Ready(Some(Ok(bs)))
+            }
+            Poll::Ready(Some(Err(e))) => {
+                if e.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} has_read={} -> failed: {:?}",
+                        self.scheme, self.op, self.path, self.has_read, e);
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} has_read={} -> errored: {:?}",
+                        self.scheme, self.op, self.path, self.has_read, e);
+                }
+                Poll::Ready(Some(Err(e)))
+            }
+            Poll::Ready(None) => {
+                trace!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} has_read={} -> finished",
+                    self.scheme, self.op, self.path, self.has_read);
+                Poll::Ready(None)
+            }
+            Poll::Pending => {
+                trace!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} has_read={} -> pending",
+                    self.scheme, self.op, self.path, self.has_read);
+                Poll::Pending
+            }
+        }
+    }
+}
+
 /// `BlockingLoggingReader` is a wrapper of `BlockingBytesReader`, with logging functionality.
 struct BlockingLoggingReader {
     scheme: Scheme,
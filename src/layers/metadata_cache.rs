@@ -0,0 +1,692 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::ready;
+use futures::stream;
+use futures::Stream;
+use futures::StreamExt;
+use parking_lot::Mutex;
+
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::path::get_parent;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirEntry;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+
+/// MetadataCacheLayer will cache `stat` results and `list` results for
+/// OpenDAL.
+///
+/// - `stat` results will be cached for `ttl`.
+/// - `NotFound` results of `stat` will be cached for `negative_ttl`.
+/// - `list` results will be cached for `ttl` after the whole dir has been
+///   listed.
+///
+/// Cached entries of the path and its parents will be invalidated while
+/// creating, writing or deleting through this layer. Changes made by others
+/// will be visible after entries expired.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::layers::MetadataCacheLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(
+///         MetadataCacheLayer::new()
+///             .with_ttl(Duration::from_secs(300))
+///             .with_negative_ttl(Duration::from_secs(5))
+///             .with_capacity(10000),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct MetadataCacheLayer {
+    ttl: Duration,
+    negative_ttl: Duration,
+    capacity: usize,
+}
+
+impl MetadataCacheLayer {
+    /// Create a new metadata cache layer.
+    ///
+    /// Default to cache 10000 entries for 60s, and `NotFound` results for 1s.
+    pub fn new() -> Self {
+        MetadataCacheLayer {
+            ttl: Duration::from_secs(60),
+            negative_ttl: Duration::from_secs(1),
+            capacity: 10000,
+        }
+    }
+
+    /// Set the ttl of cached `stat` and `list` results.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set the ttl of cached `NotFound` results.
+    ///
+    /// Set to [`Duration::ZERO`] to disable negative caching.
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Set the max entries of `stat` results and `list` results.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+}
+
+impl Default for MetadataCacheLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Layer for MetadataCacheLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(MetadataCacheAccessor {
+            inner,
+            ttl: self.ttl,
+            negative_ttl: self.negative_ttl,
+            stats: Arc::new(TtlCache::new(self.capacity)),
+            lists: Arc::new(TtlCache::new(self.capacity)),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MetadataCacheAccessor {
+    inner: Arc<dyn Accessor>,
+    ttl: Duration,
+    negative_ttl: Duration,
+
+    /// `None` means the object is not found.
+    stats: Arc<TtlCache<Option<ObjectMetadata>>>,
+    /// Entries returned by inner accessor.
+    ///
+    /// We don't store entries returned to users here, since they hold the
+    /// accessor itself.
+    lists: Arc<TtlCache<Arc<Vec<DirEntry>>>>,
+}
+
+impl MetadataCacheAccessor {
+    /// Invalidate cached entries of this path and its parents.
+    fn invalidate(&self, path: &str) {
+        let mut path = path;
+        loop {
+            self.stats.remove(path);
+            self.lists.remove(path);
+
+            if path == "/" {
+                break;
+            }
+            path = get_parent(path);
+        }
+    }
+
+    fn cached_stat(&self, op: Operation, path: &str) -> Option<Result<ObjectMetadata>> {
+        self.stats.get(path).map(|v| {
+            v.ok_or_else(|| {
+                ObjectError::new(op, path, anyhow!("object not found (cached)"))
+                    .with_kind(ObjectErrorKind::ObjectNotFound)
+                    .into()
+            })
+        })
+    }
+
+    /// Cache the result of stat started at `generation`.
+    fn cache_stat(&self, path: &str, res: &Result<ObjectMetadata>, generation: u64) {
+        match res {
+            Ok(meta) => self
+                .stats
+                .insert(path, Some(meta.clone()), self.ttl, generation),
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                self.stats.insert(path, None, self.negative_ttl, generation)
+            }
+            Err(_) => {}
+        }
+    }
+
+    fn rewrap_entry(self: &Arc<Self>, mut de: DirEntry) -> DirEntry {
+        de.set_accessor(self.clone());
+        de
+    }
+}
+
+#[async_trait]
+impl Accessor for MetadataCacheAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        let res = self.inner.create(args).await;
+        self.invalidate(args.path());
+        res
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        self.inner.read(args).await
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        let res = self.inner.write(args, r).await;
+        self.invalidate(args.path());
+        res
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.inner.read_stream(args).await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        let res = self.inner.write_stream(args, s).await;
+        self.invalidate(args.path());
+        res
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        if let Some(res) = self.cached_stat(Operation::Stat, args.path()) {
+            return res;
+        }
+
+        let generation = self.stats.generation();
+        let res = self.inner.stat(args).await;
+        self.cache_stat(args.path(), &res, generation);
+        res
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        let res = self.inner.delete(args).await;
+        self.invalidate(args.path());
+        res
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        let res = self.inner.rename(args).await;
+        self.invalidate(args.from());
+        self.invalidate(args.to());
+        res
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let acc = Arc::new(self.clone());
+
+        if let Some(entries) = self.lists.get(args.path()) {
+            let entries: Vec<DirEntry> = entries.iter().cloned().collect();
+            return Ok(Box::new(
+                stream::iter(entries).map(move |de| Ok(acc.rewrap_entry(de))),
+            ));
+        }
+
+        let generation = self.lists.generation();
+        let ds = self.inner.list(args).await?;
+        let ds = CachingDir {
+            inner: ds,
+            entries: Some(Vec::new()),
+            path: args.path().to_string(),
+            ttl: self.ttl,
+            generation,
+            cache: self.lists.clone(),
+        };
+        Ok(Box::new(
+            ds.map(move |de| de.map(|de| acc.rewrap_entry(de))),
+        ))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.inner.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        self.inner.write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        let res = self.inner.complete_multipart(args).await;
+        self.invalidate(args.path());
+        res
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.inner.abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        let res = self.inner.blocking_create(args);
+        self.invalidate(args.path());
+        res
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.inner.blocking_read(args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let res = self.inner.blocking_write(args, r);
+        self.invalidate(args.path());
+        res
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        if let Some(res) = self.cached_stat(Operation::BlockingStat, args.path()) {
+            return res;
+        }
+
+        let generation = self.stats.generation();
+        let res = self.inner.blocking_stat(args);
+        self.cache_stat(args.path(), &res, generation);
+        res
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        let res = self.inner.blocking_delete(args);
+        self.invalidate(args.path());
+        res
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        let res = self.inner.blocking_rename(args);
+        self.invalidate(args.from());
+        self.invalidate(args.to());
+        res
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let acc = Arc::new(self.clone());
+
+        if let Some(entries) = self.lists.get(args.path()) {
+            let entries: Vec<DirEntry> = entries.iter().cloned().collect();
+            return Ok(Box::new(
+                entries.into_iter().map(move |de| Ok(acc.rewrap_entry(de))),
+            ));
+        }
+
+        let generation = self.lists.generation();
+        let it = self.inner.blocking_list(args)?;
+        let it = CachingDir {
+            inner: it,
+            entries: Some(Vec::new()),
+            path: args.path().to_string(),
+            ttl: self.ttl,
+            generation,
+            cache: self.lists.clone(),
+        };
+        Ok(Box::new(
+            it.map(move |de| de.map(|de| acc.rewrap_entry(de))),
+        ))
+    }
+}
+
+/// CachingDir wraps [`DirStreamer`] or [`DirIterator`] and will cache all
+/// entries after the whole dir has been listed without error.
+struct CachingDir<I> {
+    inner: I,
+    /// `None` means we will not cache this dir.
+    entries: Option<Vec<DirEntry>>,
+
+    path: String,
+    ttl: Duration,
+    /// Generation of cache when listing started.
+    generation: u64,
+    cache: Arc<TtlCache<Arc<Vec<DirEntry>>>>,
+}
+
+impl<I> CachingDir<I> {
+    /// Record the entry returned by inner, and cache all entries at the end.
+    fn observe(&mut self, v: &Option<Result<DirEntry>>) {
+        match v {
+            Some(Ok(de)) => {
+                if let Some(entries) = self.entries.as_mut() {
+                    entries.push(de.clone());
+                }
+            }
+            Some(Err(_)) => self.entries = None,
+            None => {
+                if let Some(entries) = self.entries.take() {
+                    self.cache
+                        .insert(&self.path, Arc::new(entries), self.ttl, self.generation);
+                }
+            }
+        }
+    }
+}
+
+impl Stream for CachingDir<DirStreamer> {
+    type Item = Result<DirEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let v = ready!(self.inner.poll_next_unpin(cx));
+        self.observe(&v);
+        Poll::Ready(v)
+    }
+}
+
+impl Iterator for CachingDir<DirIterator> {
+    type Item = Result<DirEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.inner.next();
+        self.observe(&v);
+        v
+    }
+}
+
+/// TtlCache is a simple cache with ttl and capacity.
+///
+/// Expired entries will be removed while reading or inserting into a full
+/// cache. If the cache is still full, the entry that expires first will be
+/// evicted.
+///
+/// Every removal bumps the generation of cache, values fetched before that
+/// could be stale and will not be inserted.
+#[derive(Debug)]
+struct TtlCache<V> {
+    capacity: usize,
+    entries: Mutex<Entries<V>>,
+}
+
+#[derive(Debug)]
+struct Entries<V> {
+    generation: u64,
+    /// Values with their expire time.
+    map: HashMap<String, (Instant, V)>,
+}
+
+impl<V: Clone> TtlCache<V> {
+    fn new(capacity: usize) -> Self {
+        TtlCache {
+            capacity,
+            entries: Mutex::new(Entries {
+                generation: 0,
+                map: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Get current generation, should be called before fetching the value.
+    fn generation(&self) -> u64 {
+        self.entries.lock().generation
+    }
+
+    fn get(&self, key: &str) -> Option<V> {
+        let entries = &mut self.entries.lock().map;
+        match entries.get(key) {
+            Some((expire_at, v)) if *expire_at > Instant::now() => Some(v.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Insert value fetched at `generation`, value will be dropped if the
+    /// cache has been changed since then.
+    fn insert(&self, key: &str, value: V, ttl: Duration, generation: u64) {
+        if self.capacity == 0 || ttl.is_zero() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut guard = self.entries.lock();
+        if guard.generation != generation {
+            return;
+        }
+        let entries = &mut guard.map;
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            entries.retain(|_, (expire_at, _)| *expire_at > now);
+        }
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (expire_at, _))| *expire_at)
+                .map(|(k, _)| k.clone());
+            if let Some(k) = oldest {
+                entries.remove(&k);
+            }
+        }
+        entries.insert(key.to_string(), (now + ttl, value));
+    }
+
+    fn remove(&self, key: &str) {
+        let mut entries = self.entries.lock();
+        entries.generation += 1;
+        entries.map.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::services::fs;
+    use crate::services::memory;
+    use crate::Operator;
+
+    /// MockService counts calls of `stat` and `list`.
+    #[derive(Debug)]
+    struct MockService {
+        inner: Arc<dyn Accessor>,
+        stat_calls: AtomicUsize,
+        list_calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        fn metadata(&self) -> AccessorMetadata {
+            self.inner.metadata()
+        }
+
+        async fn create(&self, args: &OpCreate) -> Result<()> {
+            self.inner.create(args).await
+        }
+
+        async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+            self.inner.write(args, r).await
+        }
+
+        async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+            self.stat_calls.fetch_add(1, Ordering::SeqCst);
+            self.inner.stat(args).await
+        }
+
+        async fn delete(&self, args: &OpDelete) -> Result<()> {
+            self.inner.delete(args).await
+        }
+
+        async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+            self.list_calls.fetch_add(1, Ordering::SeqCst);
+            self.inner.list(args).await
+        }
+
+        fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+            self.inner.blocking_write(args, r)
+        }
+
+        fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+            self.list_calls.fetch_add(1, Ordering::SeqCst);
+            self.inner.blocking_list(args)
+        }
+    }
+
+    fn new_operator() -> Result<(Operator, Arc<MockService>)> {
+        new_operator_with(Arc::new(memory::Builder::default().build()?))
+    }
+
+    fn new_operator_with(inner: Arc<dyn Accessor>) -> Result<(Operator, Arc<MockService>)> {
+        let srv = Arc::new(MockService {
+            inner,
+            stat_calls: AtomicUsize::new(0),
+            list_calls: AtomicUsize::new(0),
+        });
+        let op = Operator::new(srv.clone()).layer(MetadataCacheLayer::new());
+        Ok((op, srv))
+    }
+
+    #[tokio::test]
+    async fn test_stat_cache() -> Result<()> {
+        let (op, srv) = new_operator()?;
+        let o = op.object("dir/file");
+
+        // NotFound should be cached.
+        assert!(!o.is_exist().await?);
+        assert!(!o.is_exist().await?);
+        assert_eq!(srv.stat_calls.load(Ordering::SeqCst), 1);
+
+        // Write should invalidate cache.
+        o.write("hello").await?;
+        assert_eq!(o.metadata().await?.content_length(), 5);
+        assert_eq!(o.metadata().await?.content_length(), 5);
+        assert_eq!(srv.stat_calls.load(Ordering::SeqCst), 2);
+
+        // Delete should invalidate cache.
+        o.delete().await?;
+        assert!(!o.is_exist().await?);
+        assert_eq!(srv.stat_calls.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_cache() -> Result<()> {
+        let (op, srv) = new_operator()?;
+        op.object("dir/a").write("a").await?;
+
+        for _ in 0..2 {
+            let entries: Vec<DirEntry> = op.object("dir/").list().await?.try_collect().await?;
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].path(), "dir/a");
+        }
+        assert_eq!(srv.list_calls.load(Ordering::SeqCst), 1);
+
+        // Write through dir entry should also invalidate cache.
+        let entries: Vec<DirEntry> = op.object("dir/").list().await?.try_collect().await?;
+        entries[0].clone().into_object().delete().await?;
+        op.object("dir/b").write("b").await?;
+
+        let entries: Vec<DirEntry> = op.object("dir/").list().await?.try_collect().await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path(), "dir/b");
+        assert_eq!(srv.list_calls.load(Ordering::SeqCst), 2);
+
+        // List started before write should not be cached.
+        op.object("dir/c").write("c").await?;
+        let ds = op.object("dir/").list().await?;
+        op.object("dir/d").write("d").await?;
+        let _: Vec<DirEntry> = ds.try_collect().await?;
+        let entries: Vec<DirEntry> = op.object("dir/").list().await?.try_collect().await?;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(srv.list_calls.load(Ordering::SeqCst), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_blocking_list_cache() -> Result<()> {
+        let root = format!("/tmp/{}/", uuid::Uuid::new_v4());
+        let (op, srv) = new_operator_with(Arc::new(fs::Builder::default().root(&root).build()?))?;
+        op.object("dir/a").blocking_write("a")?;
+
+        for _ in 0..2 {
+            let entries: Vec<DirEntry> =
+                op.object("dir/").blocking_list()?.collect::<Result<_>>()?;
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].path(), "dir/a");
+        }
+        assert_eq!(srv.list_calls.load(Ordering::SeqCst), 1);
+
+        // Write should invalidate cache.
+        op.object("dir/b").blocking_write("b")?;
+        let entries: Vec<DirEntry> = op.object("dir/").blocking_list()?.collect::<Result<_>>()?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(srv.list_calls.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ttl_cache() {
+        let cache = TtlCache::new(2);
+        cache.insert("a", 1, Duration::from_secs(10), 0);
+        cache.insert("b", 2, Duration::from_secs(20), 0);
+        cache.insert("c", 3, Duration::from_secs(30), 0);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(2));
+        assert_eq!(cache.get("c"), Some(3));
+
+        cache.insert("d", 4, Duration::ZERO, 0);
+        assert_eq!(cache.get("d"), None);
+
+        // Values fetched before removal should not be inserted.
+        let generation = cache.generation();
+        cache.remove("e");
+        cache.insert("e", 5, Duration::from_secs(10), generation);
+        assert_eq!(cache.get("e"), None);
+        cache.insert("e", 5, Duration::from_secs(10), cache.generation());
+        assert_eq!(cache.get("e"), Some(5));
+    }
+}
@@ -23,7 +23,9 @@ use std::task::Poll;
 use std::time::Instant;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::AsyncRead;
+use futures::Stream;
 use metrics::counter;
 use metrics::histogram;
 use metrics::increment_counter;
@@ -37,6 +39,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpRename;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -46,6 +49,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirIterator;
 use crate::DirStreamer;
 use crate::Layer;
@@ -198,6 +202,58 @@ impl Accessor for MetricsAccessor {
         result
     }
 
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        increment_counter!(
+            METRIC_REQUESTS_TOTAL,
+            LABEL_SERVICE => self.meta.scheme().into_static(),
+            LABEL_OPERATION => Operation::Read.into_static(),
+        );
+
+        let start = Instant::now();
+        let result = self.inner.read_stream(args).await.map(|s| {
+            Box::new(MetricBytesStream::new(
+                self.meta.scheme(),
+                Operation::Read,
+                s,
+            )) as BytesStreamer
+        });
+        let dur = start.elapsed().as_secs_f64();
+
+        histogram!(
+            METRIC_REQUESTS_DURATION_SECONDS, dur,
+            LABEL_SERVICE => self.meta.scheme().into_static(),
+            LABEL_OPERATION => Operation::Read.into_static(),
+        );
+
+        result
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        increment_counter!(
+            METRIC_REQUESTS_TOTAL,
+            LABEL_SERVICE => self.meta.scheme().into_static(),
+            LABEL_OPERATION => Operation::Write.into_static(),
+        );
+
+        let s = Box::new(MetricBytesStream::new(
+            self.meta.scheme(),
+            Operation::Write,
+            s,
+        ));
+
+        let start = Instant::now();
+        let result = self.inner.write_stream(args, s).await;
+        let dur = start.elapsed().as_secs_f64();
+
+        histogram!(
+            METRIC_REQUESTS_DURATION_SECONDS, dur,
+            LABEL_SERVICE => self.meta.scheme().into_static(),
+            LABEL_OPERATION => Operation::Write.into_static(),
+        );
+
+        result
+    }
+
     async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
         increment_counter!(
             METRIC_REQUESTS_TOTAL,
@@ -238,6 +294,26 @@ impl Accessor for MetricsAccessor {
         result
     }
 
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        increment_counter!(
+            METRIC_REQUESTS_TOTAL,
+            LABEL_SERVICE => self.meta.scheme().into_static(),
+            LABEL_OPERATION => Operation::Rename.into_static(),
+        );
+
+        let start = Instant::now();
+        let result = self.inner.rename(args).await;
+        let dur = start.elapsed().as_secs_f64();
+
+        histogram!(
+            METRIC_REQUESTS_DURATION_SECONDS, dur,
+            LABEL_SERVICE => self.meta.scheme().into_static(),
+            LABEL_OPERATION => Operation::Rename.into_static(),
+        );
+
+        result
+    }
+
     async fn list(&self, args: &OpList) -> Result<DirStreamer> {
         increment_counter!(
             METRIC_REQUESTS_TOTAL,
@@ -472,6 +548,26 @@ impl Accessor for MetricsAccessor {
         result
     }
 
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        increment_counter!(
+            METRIC_REQUESTS_TOTAL,
+            LABEL_SERVICE => self.meta.scheme().into_static(),
+            LABEL_OPERATION => Operation::BlockingRename.into_static(),
+        );
+
+        let start = Instant::now();
+        let result = self.inner.blocking_rename(args);
+        let dur = start.elapsed().as_secs_f64();
+
+        histogram!(
+            METRIC_REQUESTS_DURATION_SECONDS, dur,
+            LABEL_SERVICE => self.meta.scheme().into_static(),
+            LABEL_OPERATION => Operation::BlockingRename.into_static(),
+        );
+
+        result
+    }
+
     fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
         increment_counter!(
             METRIC_REQUESTS_TOTAL,
@@ -556,6 +652,68 @@ impl AsyncRead for MetricReader {
     }
 }
 
+struct MetricBytesStream {
+    scheme: Scheme,
+    op: Operation,
+    inner: BytesStreamer,
+}
+
+impl MetricBytesStream {
+    fn new(scheme: Scheme, op: Operation, inner: BytesStreamer) -> Self {
+        Self { scheme, op, inner }
+    }
+}
+
+impl Stream for MetricBytesStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut (*self.inner))
+            .poll_next(cx)
+            .map(|res| match res {
+                Some(Ok(bs)) => {
+                    match self.op {
+                        Operation::Read => {
+                            counter!(
+                                METRIC_BYTES_READ_TOTAL, bs.len() as u64,
+                                LABEL_SERVICE => self.scheme.into_static(),
+                                LABEL_OPERATION => self.op.into_static(),
+                            );
+                        }
+                        Operation::Write => {
+                            counter!(
+                                METRIC_BYTES_WRITTEN_TOTAL, bs.len() as u64,
+                                LABEL_SERVICE => self.scheme.into_static(),
+                                LABEL_OPERATION => self.op.into_static(),
+                            );
+                        }
+                        _ => {
+                            unreachable!();
+                        }
+                    };
+                    Some(Ok(bs))
+                }
+                Some(Err(e)) => {
+                    if e.kind() == ErrorKind::Other {
+                        increment_counter!(
+                            METRIC_FAILURES_TOTAL,
+                            LABEL_SERVICE => self.scheme.into_static(),
+                            LABEL_OPERATION => self.op.into_static(),
+                        );
+                    } else {
+                        increment_counter!(
+                            METRIC_ERRORS_TOTAL,
+                            LABEL_SERVICE => self.scheme.into_static(),
+                            LABEL_OPERATION => self.op.into_static(),
+                        );
+                    }
+                    Some(Err(e))
+                }
+                None => None,
+            })
+    }
+}
+
 struct BlockingMetricReader {
     scheme: Scheme,
     op: Operation,
@@ -19,8 +19,18 @@
 //! - [`LoggingLayer`]: Add logging for OpenDAL.
 //! - [`MetricsLayer`]: Add metrics for OpenDAL, requires feature `layers-metrics`
 //! - [`RetryLayer`]: Add retry for OpenDAL, requires feature `layers-retry`
+//! - [`TimeoutLayer`]: Add timeout for OpenDAL.
+//! - [`HedgeLayer`]: Add hedged requests for OpenDAL.
 //! - [`TracingLayer`]: Add tracing for OpenDAL, requires feature `layers-tracing`
 //! - [`ImmutableIndexLayer`]: Add an immutable in-memory index for OpenDAL.
+//! - [`MetadataCacheLayer`]: Add metadata and listing cache for OpenDAL.
+//! - [`ReplicationLayer`]: Mirror changes to replicas for OpenDAL.
+//! - [`AclLayer`]: Add path based access control for OpenDAL.
+//! - [`QuotaLayer`]: Add storage quotas per prefix for OpenDAL.
+//! - [`TrashLayer`]: Move deleted objects into trash for OpenDAL.
+//! - [`CompressLayer`]: Add transparent compression for OpenDAL, requires feature `compress`
+//! - [`ChecksumLayer`]: Add end-to-end checksum verification for OpenDAL, requires feature `layers-checksum`
+//! - [`EncryptionLayer`]: Add client-side encryption for OpenDAL, requires feature `layers-encryption`
 
 mod layer;
 pub use layer::Layer;
@@ -38,6 +48,12 @@ mod retry;
 #[cfg(feature = "layers-retry")]
 pub use self::retry::RetryLayer;
 
+mod timeout;
+pub use timeout::TimeoutLayer;
+
+mod hedge;
+pub use hedge::HedgeLayer;
+
 #[cfg(feature = "layers-tracing")]
 mod tracing;
 #[cfg(feature = "layers-tracing")]
@@ -45,3 +61,52 @@ pub use self::tracing::TracingLayer;
 
 mod immutable_index;
 pub use immutable_index::ImmutableIndexLayer;
+
+mod metadata_cache;
+pub use metadata_cache::MetadataCacheLayer;
+
+mod acl;
+pub use acl::AclEffect;
+pub use acl::AclLayer;
+pub use acl::AclRule;
+
+mod quota;
+pub use quota::Quota;
+pub use quota::QuotaLayer;
+pub use quota::QuotaUsage;
+
+mod trash;
+pub use trash::Trash;
+pub use trash::TrashLayer;
+
+mod sub;
+pub(crate) use sub::SubLayer;
+
+mod read_only;
+pub(crate) use read_only::ReadOnlyLayer;
+
+mod replication;
+pub use replication::ReplicationAck;
+pub use replication::ReplicationLayer;
+pub use replication::ReplicationReport;
+
+#[cfg(feature = "compress")]
+mod compress;
+#[cfg(feature = "compress")]
+pub use self::compress::CompressLayer;
+
+#[cfg(feature = "layers-checksum")]
+mod checksum;
+#[cfg(feature = "layers-checksum")]
+pub use self::checksum::ChecksumAlgorithm;
+#[cfg(feature = "layers-checksum")]
+pub use self::checksum::ChecksumLayer;
+
+#[cfg(feature = "layers-encryption")]
+mod encryption;
+#[cfg(feature = "layers-encryption")]
+pub use self::encryption::AesKeyProvider;
+#[cfg(feature = "layers-encryption")]
+pub use self::encryption::EncryptionLayer;
+#[cfg(feature = "layers-encryption")]
+pub use self::encryption::KeyProvider;
@@ -0,0 +1,1044 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+use std::task::Poll;
+use std::task::Waker;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::future::poll_fn;
+use futures::TryStreamExt;
+use log::warn;
+use parking_lot::Condvar;
+use parking_lot::Mutex;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::accessor::AccessorCapability;
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::io_util::TopDownWalker;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignOperation;
+use crate::ops::PresignedRequest;
+use crate::path::normalize_path;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::Object;
+use crate::ObjectMetadata;
+use crate::ObjectMode;
+use crate::Operator;
+
+/// Limits of a prefix in [`QuotaLayer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Quota {
+    max_bytes: Option<u64>,
+    max_objects: Option<u64>,
+}
+
+impl Quota {
+    /// Create a new quota without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the max bytes of all objects.
+    pub fn with_max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Set the max count of objects.
+    pub fn with_max_objects(mut self, objects: u64) -> Self {
+        self.max_objects = Some(objects);
+        self
+    }
+}
+
+/// Usage of a prefix in [`QuotaLayer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuotaUsage {
+    bytes: u64,
+    objects: u64,
+}
+
+impl QuotaUsage {
+    /// Create a new usage.
+    pub fn new(bytes: u64, objects: u64) -> Self {
+        QuotaUsage { bytes, objects }
+    }
+
+    /// Bytes of all objects.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Count of objects.
+    pub fn objects(&self) -> u64 {
+        self.objects
+    }
+
+    fn apply(&mut self, bytes: i64, objects: i64) {
+        self.bytes = self.bytes.saturating_add_signed(bytes);
+        self.objects = self.objects.saturating_add_signed(objects);
+    }
+}
+
+/// QuotaLayer will enforce storage quotas per prefix for OpenDAL.
+///
+/// Bytes and object counts are tracked for every configured prefix, and
+/// writes that would exceed the quota will be rejected with
+/// [`ObjectErrorKind::QuotaExceeded`] before sending to services.
+///
+/// Usage could be initiated by walking services via
+/// [`QuotaLayer::init_usage`] or restored from persisted state via
+/// [`QuotaLayer::with_usage`]. Use [`QuotaLayer::usages`] to get the current
+/// usage for persisting.
+///
+/// # Notes
+///
+/// - Writes under configured prefixes will send an extra `stat` to get the
+///   previous size of overwritten objects.
+/// - Changes to the same path under configured prefixes are serialized, so
+///   that the previous size and usage are always updated together.
+/// - Written parts of multipart uploads are accounted until the upload is
+///   completed or aborted, then replaced by the size of completed object.
+///   Re-uploaded parts replace the size of the same part number.
+/// - Usage is only tracked for changes made via this layer, so presigned
+///   writes under configured prefixes are not supported.
+/// - `rename` is not supported, since it moves usage between prefixes.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::Quota;
+/// use opendal::layers::QuotaLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let op = Operator::from_env(Scheme::Memory)?;
+///
+/// let quota = QuotaLayer::new().with_quota(
+///     "tenants/a/",
+///     Quota::new().with_max_bytes(1024 * 1024).with_max_objects(1000),
+/// );
+/// quota.init_usage(&op).await?;
+///
+/// let op = op.layer(quota);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QuotaLayer {
+    quotas: Vec<(String, Quota)>,
+    usages: Arc<Mutex<HashMap<String, QuotaUsage>>>,
+    locks: Arc<PathLocks>,
+}
+
+impl QuotaLayer {
+    /// Create a new quota layer without quotas.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set quota for prefix, prefix will be normalized into dir path.
+    pub fn with_quota(mut self, prefix: &str, quota: Quota) -> Self {
+        let prefix = normalize_prefix(prefix);
+        self.usages.lock().entry(prefix.clone()).or_default();
+        self.quotas.retain(|(v, _)| v != &prefix);
+        self.quotas.push((prefix, quota));
+        self
+    }
+
+    /// Restore usage of prefix from persisted state.
+    pub fn with_usage(self, prefix: &str, usage: QuotaUsage) -> Self {
+        self.usages.lock().insert(normalize_prefix(prefix), usage);
+        self
+    }
+
+    /// Init usage of all configured prefixes by walking the operator.
+    ///
+    /// The operator should not be layered by this quota layer.
+    pub async fn init_usage(&self, op: &Operator) -> Result<()> {
+        for (prefix, _) in self.quotas.iter() {
+            let root = if prefix.is_empty() { "/" } else { prefix };
+
+            let mut usage = QuotaUsage::default();
+            let mut walker = TopDownWalker::new(Object::new(op.inner(), root));
+            loop {
+                let de = match walker.try_next().await {
+                    Ok(Some(de)) => de,
+                    Ok(None) => break,
+                    Err(err) if err.kind() == ErrorKind::NotFound => break,
+                    Err(err) => return Err(err),
+                };
+                if de.mode().is_dir() {
+                    continue;
+                }
+
+                let size = match de.content_length() {
+                    Some(v) => v,
+                    None => de.metadata().await?.content_length(),
+                };
+                usage.apply(size as i64, 1);
+            }
+
+            self.usages.lock().insert(prefix.clone(), usage);
+        }
+
+        Ok(())
+    }
+
+    /// Get usage of prefix.
+    pub fn usage(&self, prefix: &str) -> Option<QuotaUsage> {
+        self.usages.lock().get(&normalize_prefix(prefix)).copied()
+    }
+
+    /// Get usages of all prefixes.
+    pub fn usages(&self) -> HashMap<String, QuotaUsage> {
+        self.usages.lock().clone()
+    }
+}
+
+/// Normalize prefix into dir path, root will be empty so that it matches
+/// all paths.
+fn normalize_prefix(prefix: &str) -> String {
+    let mut prefix = normalize_path(prefix);
+    if prefix == "/" {
+        prefix.clear();
+    } else if !prefix.ends_with('/') {
+        prefix.push('/');
+    }
+    prefix
+}
+
+impl Layer for QuotaLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(QuotaAccessor {
+            inner,
+            quotas: Arc::new(self.quotas.clone()),
+            usages: self.usages.clone(),
+            locks: self.locks.clone(),
+            uploads: Arc::default(),
+        })
+    }
+}
+
+/// Locks of paths that are being changed.
+#[derive(Debug, Default)]
+struct PathLocks(Mutex<HashMap<String, Arc<PathLock>>>);
+
+impl PathLocks {
+    fn get(&self, path: &str) -> Arc<PathLock> {
+        self.0.lock().entry(path.to_string()).or_default().clone()
+    }
+
+    async fn lock(&self, path: &str) -> PathGuard<'_> {
+        let lock = self.get(path);
+        lock.lock().await;
+        PathGuard {
+            locks: self,
+            path: path.to_string(),
+            lock: Some(lock),
+        }
+    }
+
+    fn blocking_lock(&self, path: &str) -> PathGuard<'_> {
+        let lock = self.get(path);
+        lock.blocking_lock();
+        PathGuard {
+            locks: self,
+            path: path.to_string(),
+            lock: Some(lock),
+        }
+    }
+}
+
+/// PathLock could be waited by both async and blocking operations.
+#[derive(Debug, Default)]
+struct PathLock {
+    state: Mutex<PathLockState>,
+    cond: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct PathLockState {
+    locked: bool,
+    wakers: Vec<Waker>,
+}
+
+impl PathLock {
+    async fn lock(&self) {
+        poll_fn(|cx| {
+            let mut state = self.state.lock();
+            if state.locked {
+                state.wakers.push(cx.waker().clone());
+                return Poll::Pending;
+            }
+            state.locked = true;
+            Poll::Ready(())
+        })
+        .await
+    }
+
+    fn blocking_lock(&self) {
+        let mut state = self.state.lock();
+        while state.locked {
+            self.cond.wait(&mut state);
+        }
+        state.locked = true;
+    }
+
+    /// Unlock and wake up all waiters, the losers will wait again.
+    fn unlock(&self) {
+        let wakers = {
+            let mut state = self.state.lock();
+            state.locked = false;
+            std::mem::take(&mut state.wakers)
+        };
+        self.cond.notify_one();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// PathGuard will remove the lock of path while no one else is using it.
+struct PathGuard<'a> {
+    locks: &'a PathLocks,
+    path: String,
+    lock: Option<Arc<PathLock>>,
+}
+
+impl Drop for PathGuard<'_> {
+    fn drop(&mut self) {
+        // Locks are only cloned while holding the map, so no one could
+        // acquire this lock after checking the strong count.
+        let mut locks = self.locks.0.lock();
+        if let Some(lock) = self.lock.take() {
+            lock.unlock();
+        }
+        if locks
+            .get(&self.path)
+            .map(|v| Arc::strong_count(v) == 1)
+            .unwrap_or_default()
+        {
+            locks.remove(&self.path);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct QuotaAccessor {
+    inner: Arc<dyn Accessor>,
+    quotas: Arc<Vec<(String, Quota)>>,
+    usages: Arc<Mutex<HashMap<String, QuotaUsage>>>,
+    locks: Arc<PathLocks>,
+    /// Written bytes of every part in uncompleted multipart uploads.
+    uploads: Arc<Mutex<HashMap<String, HashMap<usize, u64>>>>,
+}
+
+impl QuotaAccessor {
+    fn is_limited(&self, path: &str) -> bool {
+        self.quotas
+            .iter()
+            .any(|(prefix, _)| path.starts_with(prefix))
+    }
+
+    /// Check if the changes are allowed by all matched quotas.
+    fn check(
+        &self,
+        usages: &HashMap<String, QuotaUsage>,
+        op: Operation,
+        path: &str,
+        bytes: i64,
+        objects: i64,
+    ) -> Result<()> {
+        for (prefix, quota) in self.quotas.iter() {
+            if !path.starts_with(prefix) {
+                continue;
+            }
+
+            let mut usage = usages.get(prefix).copied().unwrap_or_default();
+            usage.apply(bytes, objects);
+
+            let exceeded = match (quota.max_bytes, quota.max_objects) {
+                (Some(max), _) if bytes > 0 && usage.bytes > max => Some(("max_bytes", max)),
+                (_, Some(max)) if objects > 0 && usage.objects > max => Some(("max_objects", max)),
+                _ => None,
+            };
+
+            if let Some((limit, max)) = exceeded {
+                return Err(ObjectError::new(op, path, anyhow!("quota exceeded"))
+                    .with_kind(ObjectErrorKind::QuotaExceeded)
+                    .with_context("prefix", prefix.clone())
+                    .with_context(limit, max.to_string())
+                    .into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check and apply the changes to all matched prefixes.
+    fn reserve(&self, op: Operation, path: &str, bytes: i64, objects: i64) -> Result<()> {
+        let mut usages = self.usages.lock();
+        self.check(&usages, op, path, bytes, objects)?;
+        Self::apply(&mut usages, &self.quotas, path, bytes, objects);
+        Ok(())
+    }
+
+    /// Apply the changes to all matched prefixes without checking.
+    fn release(&self, path: &str, bytes: i64, objects: i64) {
+        Self::apply(&mut self.usages.lock(), &self.quotas, path, bytes, objects);
+    }
+
+    /// Adjust the reserved size of write to the actually written size.
+    fn reconcile(&self, args: &OpWrite, written: u64) {
+        if written != args.size() {
+            self.release(args.path(), written as i64 - args.size() as i64, 0);
+        }
+    }
+
+    fn apply(
+        usages: &mut HashMap<String, QuotaUsage>,
+        quotas: &[(String, Quota)],
+        path: &str,
+        bytes: i64,
+        objects: i64,
+    ) {
+        for (prefix, _) in quotas.iter().filter(|(v, _)| path.starts_with(v.as_str())) {
+            usages
+                .entry(prefix.clone())
+                .or_default()
+                .apply(bytes, objects);
+        }
+    }
+
+    /// Returns the size of existing object, `None` if not exist.
+    async fn previous_size(&self, path: &str) -> Result<Option<u64>> {
+        match self.inner.stat(&OpStat::new(path)?).await {
+            Ok(meta) if meta.mode() == ObjectMode::FILE => Ok(Some(meta.content_length())),
+            Ok(_) => Ok(None),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn blocking_previous_size(&self, path: &str) -> Result<Option<u64>> {
+        match self.inner.blocking_stat(&OpStat::new(path)?) {
+            Ok(meta) if meta.mode() == ObjectMode::FILE => Ok(Some(meta.content_length())),
+            Ok(_) => Ok(None),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Changes of bytes and objects after replacing `prev` with object in `size`.
+fn delta(prev: Option<u64>, size: u64) -> (i64, i64) {
+    match prev {
+        Some(prev) => (size as i64 - prev as i64, 0),
+        None => (size as i64, 1),
+    }
+}
+
+#[async_trait]
+impl Accessor for QuotaAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        meta.set_capabilities(meta.capabilities() - AccessorCapability::Rename);
+
+        meta
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        if args.mode() != ObjectMode::FILE || !self.is_limited(args.path()) {
+            return self.inner.create(args).await;
+        }
+
+        let _guard = self.locks.lock(args.path()).await;
+        let (bytes, objects) = delta(self.previous_size(args.path()).await?, 0);
+        self.reserve(Operation::Create, args.path(), bytes, objects)?;
+        self.inner.create(args).await.inspect_err(|_| {
+            self.release(args.path(), -bytes, -objects);
+        })
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        self.inner.read(args).await
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        if !self.is_limited(args.path()) {
+            return self.inner.write(args, r).await;
+        }
+
+        let _guard = self.locks.lock(args.path()).await;
+        let prev = self.previous_size(args.path()).await?;
+        let (bytes, objects) = delta(prev, args.size());
+        self.reserve(Operation::Write, args.path(), bytes, objects)?;
+        let n = self.inner.write(args, r).await.inspect_err(|_| {
+            self.release(args.path(), -bytes, -objects);
+        })?;
+        self.reconcile(args, n);
+        Ok(n)
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.inner.read_stream(args).await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        if !self.is_limited(args.path()) {
+            return self.inner.write_stream(args, s).await;
+        }
+
+        let _guard = self.locks.lock(args.path()).await;
+        let prev = self.previous_size(args.path()).await?;
+        let (bytes, objects) = delta(prev, args.size());
+        self.reserve(Operation::Write, args.path(), bytes, objects)?;
+        let n = self.inner.write_stream(args, s).await.inspect_err(|_| {
+            self.release(args.path(), -bytes, -objects);
+        })?;
+        self.reconcile(args, n);
+        Ok(n)
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.stat(args).await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        if !self.is_limited(args.path()) {
+            return self.inner.delete(args).await;
+        }
+
+        let _guard = self.locks.lock(args.path()).await;
+        let prev = self.previous_size(args.path()).await?;
+        self.inner.delete(args).await?;
+        if let Some(size) = prev {
+            self.release(args.path(), -(size as i64), -1);
+        }
+        Ok(())
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let ds = self.inner.list(args).await?;
+
+        // Objects converted from entries should be accounted too.
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        Ok(Box::new(ds.map_ok(move |mut de| {
+            de.set_accessor(this.clone());
+            de
+        })))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        // Presigned writes are sent without this layer, so they can't be
+        // accounted.
+        let limited = match args.operation() {
+            PresignOperation::Read(_) => false,
+            PresignOperation::Write(_) | PresignOperation::WriteMultipart(_) => {
+                self.is_limited(args.path())
+            }
+        };
+        if limited {
+            return Err(ObjectError::new(
+                Operation::Presign,
+                args.path(),
+                anyhow!("presign write is not supported under quota limited prefixes"),
+            )
+            .with_kind(ObjectErrorKind::Unsupported)
+            .into());
+        }
+
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.inner.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        if !self.is_limited(args.path()) {
+            return self.inner.write_multipart(args, r).await;
+        }
+
+        // Written parts are reserved until the upload is completed or
+        // aborted, re-uploaded part will replace the previous one.
+        let prev = {
+            let mut uploads = self.uploads.lock();
+            let parts = uploads.entry(args.upload_id().to_string()).or_default();
+            let prev = parts.get(&args.part_number()).copied();
+            let bytes = args.size() as i64 - prev.unwrap_or_default() as i64;
+            self.reserve(Operation::WriteMultipart, args.path(), bytes, 0)?;
+            parts.insert(args.part_number(), args.size());
+            prev
+        };
+
+        self.inner.write_multipart(args, r).await.inspect_err(|_| {
+            let mut uploads = self.uploads.lock();
+            let parts = uploads.entry(args.upload_id().to_string()).or_default();
+            match prev {
+                Some(v) => parts.insert(args.part_number(), v),
+                None => parts.remove(&args.part_number()),
+            };
+            let bytes = args.size() as i64 - prev.unwrap_or_default() as i64;
+            self.release(args.path(), -bytes, 0);
+        })
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        if !self.is_limited(args.path()) {
+            return self.inner.complete_multipart(args).await;
+        }
+
+        // Bytes of written parts have been reserved, reserve the object
+        // before completing.
+        let _guard = self.locks.lock(args.path()).await;
+        let prev = self.previous_size(args.path()).await?;
+        let (_, objects) = delta(prev, 0);
+        self.reserve(Operation::CompleteMultipart, args.path(), 0, objects)?;
+        if let Err(err) = self.inner.complete_multipart(args).await {
+            self.release(args.path(), 0, -objects);
+            return Err(err);
+        }
+        let written: u64 = self
+            .uploads
+            .lock()
+            .remove(args.upload_id())
+            .map(|parts| parts.values().sum())
+            .unwrap_or_default();
+
+        // Parts could be written by others, adjust to the size of completed
+        // object. The upload has been completed, so failing to stat should
+        // not fail it, reserved bytes of written parts will be kept instead.
+        match self.previous_size(args.path()).await {
+            Ok(size) => {
+                let (bytes, _) = delta(prev, size.unwrap_or_default());
+                self.release(args.path(), bytes - written as i64, 0);
+            }
+            Err(err) => warn!(
+                "quota failed to stat completed multipart upload {}, usage may be inaccurate: {err:?}",
+                args.path()
+            ),
+        }
+        Ok(())
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.inner.abort_multipart(args).await?;
+        if let Some(parts) = self.uploads.lock().remove(args.upload_id()) {
+            let written: u64 = parts.values().sum();
+            self.release(args.path(), -(written as i64), 0);
+        }
+        Ok(())
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        if args.mode() != ObjectMode::FILE || !self.is_limited(args.path()) {
+            return self.inner.blocking_create(args);
+        }
+
+        let _guard = self.locks.blocking_lock(args.path());
+        let (bytes, objects) = delta(self.blocking_previous_size(args.path())?, 0);
+        self.reserve(Operation::BlockingCreate, args.path(), bytes, objects)?;
+        self.inner.blocking_create(args).inspect_err(|_| {
+            self.release(args.path(), -bytes, -objects);
+        })
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.inner.blocking_read(args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        if !self.is_limited(args.path()) {
+            return self.inner.blocking_write(args, r);
+        }
+
+        let _guard = self.locks.blocking_lock(args.path());
+        let prev = self.blocking_previous_size(args.path())?;
+        let (bytes, objects) = delta(prev, args.size());
+        self.reserve(Operation::BlockingWrite, args.path(), bytes, objects)?;
+        let n = self.inner.blocking_write(args, r).inspect_err(|_| {
+            self.release(args.path(), -bytes, -objects);
+        })?;
+        self.reconcile(args, n);
+        Ok(n)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.blocking_stat(args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        if !self.is_limited(args.path()) {
+            return self.inner.blocking_delete(args);
+        }
+
+        let _guard = self.locks.blocking_lock(args.path());
+        let prev = self.blocking_previous_size(args.path())?;
+        self.inner.blocking_delete(args)?;
+        if let Some(size) = prev {
+            self.release(args.path(), -(size as i64), -1);
+        }
+        Ok(())
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let it = self.inner.blocking_list(args)?;
+
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        Ok(Box::new(it.map(move |de| {
+            de.map(|mut de| {
+                de.set_accessor(this.clone());
+                de
+            })
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::io::Cursor;
+
+    use super::*;
+    use crate::ops::OpWrite;
+    use crate::services::memory;
+
+    /// MockService delays writes and supports multipart by concatenating the
+    /// latest parts into memory.
+    ///
+    /// - Objects ended with `.half` will only keep the first half of content.
+    /// - Stat of existing objects ended with `.broken` will fail.
+    #[derive(Debug)]
+    struct MockService {
+        inner: Arc<dyn Accessor>,
+        uploads: Mutex<HashMap<String, HashMap<usize, u64>>>,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        fn metadata(&self) -> AccessorMetadata {
+            self.inner.metadata()
+        }
+
+        async fn write(&self, args: &OpWrite, mut r: BytesReader) -> Result<u64> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if !args.path().ends_with(".half") {
+                return self.inner.write(args, r).await;
+            }
+
+            let mut bs = Vec::new();
+            futures::AsyncReadExt::read_to_end(&mut r, &mut bs).await?;
+            bs.truncate(bs.len() / 2);
+            let args = OpWrite::new(args.path(), bs.len() as u64)?;
+            self.inner.write(&args, Box::new(Cursor::new(bs))).await
+        }
+
+        async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+            let meta = self.inner.stat(args).await?;
+            if args.path().ends_with(".broken") {
+                return Err(std::io::Error::new(ErrorKind::Other, "broken"));
+            }
+            Ok(meta)
+        }
+
+        async fn delete(&self, args: &OpDelete) -> Result<()> {
+            self.inner.delete(args).await
+        }
+
+        async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+            Ok(args.path().to_string())
+        }
+
+        async fn write_multipart(
+            &self,
+            args: &OpWriteMultipart,
+            _: BytesReader,
+        ) -> Result<ObjectPart> {
+            self.uploads
+                .lock()
+                .entry(args.upload_id().to_string())
+                .or_default()
+                .insert(args.part_number(), args.size());
+            Ok(ObjectPart::new(args.part_number(), "etag"))
+        }
+
+        async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+            let size: u64 = self
+                .uploads
+                .lock()
+                .remove(args.upload_id())
+                .unwrap_or_default()
+                .values()
+                .sum();
+            let bs = vec![0; size as usize];
+            self.inner
+                .write(&OpWrite::new(args.path(), size)?, Box::new(Cursor::new(bs)))
+                .await?;
+            Ok(())
+        }
+    }
+
+    fn new_mock_operator(quota: QuotaLayer) -> anyhow::Result<Operator> {
+        let srv = MockService {
+            inner: Arc::new(memory::Builder::default().build()?),
+            uploads: Mutex::default(),
+        };
+        Ok(Operator::new(Arc::new(srv)).layer(quota))
+    }
+
+    fn is_quota_exceeded(err: &std::io::Error) -> bool {
+        ObjectError::downcast(err).map(|v| v.kind()) == Some(ObjectErrorKind::QuotaExceeded)
+    }
+
+    #[tokio::test]
+    async fn test_quota() -> anyhow::Result<()> {
+        let quota = QuotaLayer::new().with_quota(
+            "tenant",
+            Quota::new().with_max_bytes(10).with_max_objects(2),
+        );
+        let op = Operator::new(memory::Builder::default().build()?).layer(quota.clone());
+
+        op.object("tenant/a").write("12345").await?;
+        assert_eq!(quota.usage("tenant"), Some(QuotaUsage::new(5, 1)));
+
+        // Overwrites are accounted by the previous size.
+        op.object("tenant/a").write("1234567").await?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(7, 1)));
+
+        let err = op
+            .object("tenant/b")
+            .write("1234")
+            .await
+            .expect_err("bytes must exceed");
+        assert!(is_quota_exceeded(&err), "{err}");
+        assert!(!op.object("tenant/b").is_exist().await?);
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(7, 1)));
+
+        op.object("tenant/b").write("1").await?;
+        let err = op
+            .object("tenant/c")
+            .write("1")
+            .await
+            .expect_err("objects must exceed");
+        assert!(is_quota_exceeded(&err), "{err}");
+
+        // Deletes release the quota.
+        op.object("tenant/a").delete().await?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(1, 1)));
+        op.object("tenant/c").write("123456789").await?;
+
+        // Paths out of prefixes are not limited.
+        op.object("other").write("12345678901").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_init_usage() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        op.object("tenant/a").write("12345").await?;
+        op.object("tenant/dir/b").write("123").await?;
+        op.object("other").write("1").await?;
+
+        let quota = QuotaLayer::new()
+            .with_quota("tenant/", Quota::new().with_max_bytes(10))
+            .with_quota("/", Quota::new());
+        quota.init_usage(&op).await?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(8, 2)));
+        assert_eq!(quota.usage("/"), Some(QuotaUsage::new(9, 3)));
+
+        let op = op.layer(quota.clone());
+        let err = op
+            .object("tenant/c")
+            .write("123")
+            .await
+            .expect_err("must exceed");
+        assert!(is_quota_exceeded(&err), "{err}");
+
+        let quota = QuotaLayer::new()
+            .with_quota("tenant/", Quota::new().with_max_bytes(10))
+            .with_usage("tenant/", QuotaUsage::new(1, 1));
+        assert_eq!(quota.usages()["tenant/"], QuotaUsage::new(1, 1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_write() -> anyhow::Result<()> {
+        let quota = QuotaLayer::new().with_quota("tenant", Quota::new());
+        let op = new_mock_operator(quota.clone())?;
+
+        let o = op.object("tenant/a");
+        let (a, b) = futures::join!(o.write("12345"), o.write("12345"));
+        a?;
+        b?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(5, 1)));
+
+        let (a, b) = futures::join!(o.delete(), o.delete());
+        a?;
+        b?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(0, 0)));
+        assert!(quota.locks.0.lock().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_multipart_retry() -> anyhow::Result<()> {
+        let quota = QuotaLayer::new().with_quota("tenant", Quota::new().with_max_bytes(10));
+        let op = new_mock_operator(quota.clone())?;
+
+        let mp = op.object("tenant/a").create_multipart().await?;
+        mp.write(1, vec![0; 6]).await?;
+        // Retried part should replace the previous one.
+        let first = mp.write(1, vec![0; 6]).await?;
+        let second = mp.write(2, vec![0; 4]).await?;
+        let err = mp
+            .write(3, vec![0; 1])
+            .await
+            .expect_err("bytes must exceed");
+        assert!(is_quota_exceeded(&err), "{err}");
+
+        mp.complete(vec![first, second]).await?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(10, 1)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_written_size() -> anyhow::Result<()> {
+        let quota = QuotaLayer::new().with_quota("tenant", Quota::new());
+        let op = new_mock_operator(quota.clone())?;
+
+        op.object("tenant/a.half").write("1234").await?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(2, 1)));
+
+        // Completed upload is kept even if it can't be stat.
+        let mp = op.object("tenant/b.broken").create_multipart().await?;
+        let part = mp.write(1, vec![0; 3]).await?;
+        mp.complete(vec![part]).await?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(5, 2)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_presign_write() -> anyhow::Result<()> {
+        let quota = QuotaLayer::new().with_quota("tenant", Quota::new());
+        let op = Operator::new(memory::Builder::default().build()?).layer(quota);
+
+        let err = op
+            .object("tenant/a")
+            .presign_write(time::Duration::hours(1))
+            .expect_err("must be unsupported");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert!(err.to_string().contains("quota"), "{err}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_path_lock() -> anyhow::Result<()> {
+        let locks = Arc::new(PathLocks::default());
+
+        // Blocking waiters should be woken up by async holders.
+        let guard = locks.lock("a").await;
+        let handle = std::thread::spawn({
+            let locks = locks.clone();
+            move || drop(locks.blocking_lock("a"))
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(guard);
+        handle.join().expect("must join");
+
+        // Async waiters should be woken up by blocking holders.
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handle = std::thread::spawn({
+            let locks = locks.clone();
+            move || {
+                let _guard = locks.blocking_lock("a");
+                tx.send(()).expect("must send");
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        });
+        rx.recv()?;
+        drop(locks.lock("a").await);
+        handle.join().expect("must join");
+        assert!(locks.0.lock().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_multipart() -> anyhow::Result<()> {
+        let quota = QuotaLayer::new().with_quota(
+            "tenant",
+            Quota::new().with_max_bytes(10).with_max_objects(1),
+        );
+        let op = new_mock_operator(quota.clone())?;
+
+        // Parts of uploads are reserved before completed.
+        let a = op.object("tenant/a").create_multipart().await?;
+        let b = op.object("tenant/b").create_multipart().await?;
+        let part_a = a.write(1, vec![0; 6]).await?;
+        let err = b.write(1, vec![0; 6]).await.expect_err("bytes must exceed");
+        assert!(is_quota_exceeded(&err), "{err}");
+        let part_b = b.write(1, vec![0; 4]).await?;
+        assert_eq!(quota.usage("tenant/"), Some(QuotaUsage::new(10, 0)));
+
+        // Only one of completes could take the last object.
+        let (ra, rb) = futures::join!(a.complete(vec![part_a]), b.complete(vec![part_b]));
+        assert!(ra.is_ok() != rb.is_ok());
+        let err = ra.err().or(rb.err()).expect("must have error");
+        assert!(is_quota_exceeded(&err), "{err}");
+        assert_eq!(quota.usage("tenant/").map(|v| v.objects), Some(1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+
+use crate::accessor::AccessorCapability;
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignOperation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+
+/// ReadOnlyLayer will reject all operations that change objects.
+///
+/// Used by [`Operator::read_only`][crate::Operator::read_only].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReadOnlyLayer;
+
+impl Layer for ReadOnlyLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(ReadOnlyAccessor { inner })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ReadOnlyAccessor {
+    inner: Arc<dyn Accessor>,
+}
+
+fn new_read_only_error(op: Operation, path: &str) -> std::io::Error {
+    ObjectError::new(op, path, anyhow!("operator is read only"))
+        .with_kind(ObjectErrorKind::PermissionDenied)
+        .into()
+}
+
+#[async_trait]
+impl Accessor for ReadOnlyAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        meta.set_capabilities(
+            meta.capabilities()
+                - AccessorCapability::Write
+                - AccessorCapability::Multipart
+                - AccessorCapability::Rename,
+        );
+
+        meta
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        Err(new_read_only_error(Operation::Create, args.path()))
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        self.inner.read(args).await
+    }
+
+    async fn write(&self, args: &OpWrite, _: BytesReader) -> Result<u64> {
+        Err(new_read_only_error(Operation::Write, args.path()))
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.inner.read_stream(args).await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, _: BytesStreamer) -> Result<u64> {
+        Err(new_read_only_error(Operation::Write, args.path()))
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.stat(args).await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        Err(new_read_only_error(Operation::Delete, args.path()))
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        Err(new_read_only_error(Operation::Rename, args.from()))
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let ds = self.inner.list(args).await?;
+
+        // Objects converted from entries should be read only too.
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        Ok(Box::new(ds.map_ok(move |mut de| {
+            de.set_accessor(this.clone());
+            de
+        })))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        match args.operation() {
+            PresignOperation::Read(_) => self.inner.presign(args),
+            _ => Err(new_read_only_error(Operation::Presign, args.path())),
+        }
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        Err(new_read_only_error(Operation::CreateMultipart, args.path()))
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, _: BytesReader) -> Result<ObjectPart> {
+        Err(new_read_only_error(Operation::WriteMultipart, args.path()))
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        Err(new_read_only_error(
+            Operation::CompleteMultipart,
+            args.path(),
+        ))
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        Err(new_read_only_error(Operation::AbortMultipart, args.path()))
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        Err(new_read_only_error(Operation::BlockingCreate, args.path()))
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.inner.blocking_read(args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, _: BlockingBytesReader) -> Result<u64> {
+        Err(new_read_only_error(Operation::BlockingWrite, args.path()))
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.blocking_stat(args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        Err(new_read_only_error(Operation::BlockingDelete, args.path()))
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        Err(new_read_only_error(Operation::BlockingRename, args.from()))
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let it = self.inner.blocking_list(args)?;
+
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        Ok(Box::new(it.map(move |de| {
+            de.map(|mut de| {
+                de.set_accessor(this.clone());
+                de
+            })
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use futures::TryStreamExt;
+
+    use crate::services::memory;
+    use crate::Operator;
+
+    #[tokio::test]
+    async fn test_read_only() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        op.object("dir/file").write("hello").await?;
+
+        let ro = op.read_only();
+        assert_eq!(ro.object("dir/file").read().await?, b"hello");
+        assert!(ro.object("dir/file").is_exist().await?);
+
+        let err = ro
+            .object("dir/file")
+            .write("world")
+            .await
+            .expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        let err = ro.object("dir/file").delete().await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        // Objects from list should be read only too.
+        let de = ro
+            .object("dir/")
+            .list()
+            .await?
+            .try_next()
+            .await?
+            .expect("must have entry");
+        let err = de.into_object().delete().await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(op.object("dir/file").read().await?, b"hello");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,813 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::io::Cursor;
+use futures::stream::FuturesUnordered;
+use futures::AsyncReadExt;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use log::debug;
+use log::warn;
+
+use crate::accessor::AccessorCapability;
+use crate::io_util::TopDownWalker;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::Object;
+use crate::ObjectMetadata;
+use crate::Operator;
+
+/// Acknowledgement policy of replicated writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationAck {
+    /// Return after primary and all replicas have succeeded.
+    All,
+    /// Return after the majority of primary and replicas have succeeded.
+    Quorum,
+    /// Return after primary has succeeded.
+    Primary,
+}
+
+/// ReplicationLayer will mirror changes to replicas for OpenDAL.
+///
+/// `write`, `write_stream`, `create` and `delete` will be applied to the
+/// primary (the operator this layer applied to) and all replicas. The
+/// operation will return once acknowledged by primary and [`ReplicationAck`],
+/// the remaining replication will continue in background. If primary
+/// failed or the ack can't be reached, the operation will fail after primary
+/// finished and unfinished replication will be cancelled, replicas that have
+/// finished could diverge from primary until [`ReplicationLayer::repair`].
+///
+/// `read`, `read_stream`, `stat` and `list` will go to primary first, and
+/// fallback to replicas one by one if primary failed. `NotFound` from primary
+/// will be returned directly, since replicas could be lagging behind.
+///
+/// # Notes
+///
+/// - Content of `write` will be buffered in memory so that it can be sent
+///   to all replicas.
+/// - Background replication requires a tokio runtime, otherwise remaining
+///   replication will be finished before returning.
+/// - Blocking operations are always replicated synchronously.
+/// - Multipart uploads are only applied to primary, please use
+///   [`ReplicationLayer::repair`] to sync them to replicas.
+/// - `rename` is not supported, since replicas may not support it.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::ReplicationAck;
+/// use opendal::layers::ReplicationLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let replica = Operator::from_env(Scheme::Memory).expect("must init");
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(
+///         ReplicationLayer::new()
+///             .with_replica(replica)
+///             .with_ack(ReplicationAck::Quorum),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReplicationLayer {
+    replicas: Vec<Arc<dyn Accessor>>,
+    ack: ReplicationAck,
+}
+
+impl Default for ReplicationLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplicationLayer {
+    /// Create a new replication layer without replicas.
+    ///
+    /// Writes will be acknowledged by all replicas by default.
+    pub fn new() -> Self {
+        ReplicationLayer {
+            replicas: Vec::new(),
+            ack: ReplicationAck::All,
+        }
+    }
+
+    /// Add a replica operator.
+    pub fn with_replica(mut self, op: Operator) -> Self {
+        self.replicas.push(op.inner());
+        self
+    }
+
+    /// Set the acknowledgement policy of replicated writes.
+    pub fn with_ack(mut self, ack: ReplicationAck) -> Self {
+        self.ack = ack;
+        self
+    }
+
+    /// Repair replicas by comparing their listings with primary under path.
+    ///
+    /// Files that missing or differ in content length (or content md5 if
+    /// both sides have) will be copied from primary, and files that not
+    /// exist in primary will be removed from replicas.
+    ///
+    /// Returns `NotFound` if path doesn't exist in primary.
+    pub async fn repair(&self, primary: &Operator, path: &str) -> Result<ReplicationReport> {
+        let primary = primary.inner();
+        let expected = list_files(primary.clone(), path).await?;
+
+        let mut report = ReplicationReport::default();
+        for replica in self.replicas.iter() {
+            let mut actual = match list_files(replica.clone(), path).await {
+                Ok(v) => v,
+                // Replica could be empty.
+                Err(err) if err.kind() == ErrorKind::NotFound => HashMap::new(),
+                Err(err) => return Err(err),
+            };
+
+            for (path, meta) in expected.iter() {
+                let matched = match actual.remove(path) {
+                    Some(v) => {
+                        v.content_length() == meta.content_length()
+                            && match (v.content_md5(), meta.content_md5()) {
+                                (Some(a), Some(b)) => a == b,
+                                _ => true,
+                            }
+                    }
+                    None => false,
+                };
+                if matched {
+                    continue;
+                }
+
+                let bs = Object::new(primary.clone(), path).read().await?;
+                Object::new(replica.clone(), path).write(bs).await?;
+                debug!("replication repair copied {path}");
+                report.copied.push(path.clone());
+            }
+
+            for path in actual.into_keys() {
+                Object::new(replica.clone(), &path).delete().await?;
+                debug!("replication repair removed {path}");
+                report.removed.push(path);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Report of [`ReplicationLayer::repair`].
+#[derive(Debug, Clone, Default)]
+pub struct ReplicationReport {
+    copied: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl ReplicationReport {
+    /// Paths copied from primary to replicas.
+    pub fn copied(&self) -> &[String] {
+        &self.copied
+    }
+
+    /// Paths removed from replicas.
+    pub fn removed(&self) -> &[String] {
+        &self.removed
+    }
+}
+
+/// List all files under path with their metadata.
+async fn list_files(acc: Arc<dyn Accessor>, path: &str) -> Result<HashMap<String, ObjectMetadata>> {
+    let mut files = HashMap::new();
+
+    let mut walker = TopDownWalker::new(Object::new(acc, path));
+    loop {
+        let de = match walker.try_next().await? {
+            Some(de) => de,
+            None => break,
+        };
+        if de.mode().is_dir() {
+            continue;
+        }
+
+        let path = de.path().to_string();
+        let meta = de.into_object().metadata().await?;
+        files.insert(path, meta);
+    }
+
+    Ok(files)
+}
+
+impl Layer for ReplicationLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(ReplicationAccessor {
+            inner,
+            replicas: self.replicas.clone(),
+            ack: self.ack,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ReplicationAccessor {
+    inner: Arc<dyn Accessor>,
+    replicas: Vec<Arc<dyn Accessor>>,
+    ack: ReplicationAck,
+}
+
+impl ReplicationAccessor {
+    /// Count of acknowledgements required by ack policy.
+    fn required(&self) -> usize {
+        let total = self.replicas.len() + 1;
+        match self.ack {
+            ReplicationAck::All => total,
+            ReplicationAck::Quorum => total / 2 + 1,
+            ReplicationAck::Primary => 1,
+        }
+    }
+
+    /// Apply `f` to primary and all replicas, return once acknowledged.
+    ///
+    /// Unfinished replicas will be driven to finish in background if the
+    /// operation succeeded, or cancelled if failed. Primary is always
+    /// awaited before returning.
+    async fn replicate<F, Fut>(&self, f: F) -> Result<()>
+    where
+        F: Fn(Arc<dyn Accessor>) -> Fut,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let mut futs: FuturesUnordered<BoxFuture<'static, (bool, Result<()>)>> =
+            FuturesUnordered::new();
+        let fut = f(self.inner.clone());
+        futs.push(Box::pin(async move { (true, fut.await) }));
+        for replica in self.replicas.iter() {
+            let fut = f(replica.clone());
+            futs.push(Box::pin(async move { (false, fut.await) }));
+        }
+
+        let total = futs.len();
+        let required = self.required();
+        let (mut acked, mut failed) = (0, 0);
+        let (mut primary_done, mut primary_acked) = (false, false);
+        let mut res = Ok(());
+        while let Some((is_primary, r)) = futs.next().await {
+            match r {
+                // Replicas should not keep changes that primary rejected, drop
+                // unfinished operations instead of driving them in background.
+                Err(err) if is_primary => return Err(err),
+                Err(err) => {
+                    failed += 1;
+                    warn!("replication failed on replica: {err:?}");
+                    res = Err(err);
+                }
+                // Only primary's ack counts under primary policy.
+                Ok(_) if self.ack == ReplicationAck::Primary && !is_primary => {}
+                Ok(_) => {
+                    acked += 1;
+                    primary_acked |= is_primary;
+                }
+            }
+            primary_done |= is_primary;
+
+            if primary_acked && acked >= required {
+                res = Ok(());
+                break;
+            }
+            // Ack can't be reached anymore, but primary's result must be
+            // known before returning, unfinished replicas will be cancelled.
+            if failed > total - required && primary_done {
+                return res;
+            }
+        }
+
+        // Only replicas are left here, since primary must have acked.
+        if !futs.is_empty() {
+            let fut = async move {
+                while let Some((_, r)) = futs.next().await {
+                    if let Err(err) = r {
+                        warn!("background replication failed: {err:?}");
+                    }
+                }
+            };
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    handle.spawn(fut);
+                }
+                // No runtime to drive them in background, finish them before
+                // returning instead.
+                Err(_) => fut.await,
+            }
+        }
+
+        res
+    }
+
+    /// Apply `f` to primary and all replicas one by one.
+    ///
+    /// Replicas will not be touched if primary failed.
+    fn blocking_replicate<F>(&self, f: F) -> Result<()>
+    where
+        F: Fn(&Arc<dyn Accessor>) -> Result<()>,
+    {
+        f(&self.inner)?;
+
+        let mut acked = 1;
+        let mut res = Ok(());
+        for replica in self.replicas.iter() {
+            match f(replica) {
+                Ok(_) => acked += 1,
+                Err(err) => {
+                    warn!("replication failed on replica: {err:?}");
+                    res = Err(err);
+                }
+            }
+        }
+
+        match self.ack {
+            ReplicationAck::Primary => Ok(()),
+            _ if acked >= self.required() => Ok(()),
+            _ => res,
+        }
+    }
+
+    /// Try primary first and fallback to replicas if failed with errors
+    /// other than `NotFound`.
+    async fn fallback<T, F, Fut>(&self, f: F) -> Result<T>
+    where
+        F: Fn(Arc<dyn Accessor>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let err = match f(self.inner.clone()).await {
+            Ok(v) => return Ok(v),
+            // Replicas could be lagging behind primary, don't bring deleted
+            // objects back.
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(err),
+            Err(err) => err,
+        };
+
+        for replica in self.replicas.iter() {
+            match f(replica.clone()).await {
+                Ok(v) => {
+                    warn!("primary failed, fallback to replica: {err:?}");
+                    return Ok(v);
+                }
+                Err(err) => debug!("replica failed: {err:?}"),
+            }
+        }
+
+        Err(err)
+    }
+
+    fn blocking_fallback<T, F>(&self, f: F) -> Result<T>
+    where
+        F: Fn(&Arc<dyn Accessor>) -> Result<T>,
+    {
+        let err = match f(&self.inner) {
+            Ok(v) => return Ok(v),
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(err),
+            Err(err) => err,
+        };
+
+        for replica in self.replicas.iter() {
+            match f(replica) {
+                Ok(v) => {
+                    warn!("primary failed, fallback to replica: {err:?}");
+                    return Ok(v);
+                }
+                Err(err) => debug!("replica failed: {err:?}"),
+            }
+        }
+
+        Err(err)
+    }
+
+    async fn replicate_write(&self, args: &OpWrite, bs: Bytes) -> Result<u64> {
+        let size = bs.len() as u64;
+        self.replicate(|acc| {
+            let (args, bs) = (args.clone(), bs.clone());
+            async move {
+                let r: BytesReader = Box::new(Cursor::new(bs));
+                acc.write(&args, r).await.map(|_| ())
+            }
+        })
+        .await?;
+
+        Ok(size)
+    }
+}
+
+#[async_trait]
+impl Accessor for ReplicationAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        meta.set_capabilities(meta.capabilities() - AccessorCapability::Rename);
+
+        meta
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        self.replicate(|acc| {
+            let args = args.clone();
+            async move { acc.create(&args).await }
+        })
+        .await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        self.fallback(|acc| async move { acc.read(args).await })
+            .await
+    }
+
+    async fn write(&self, args: &OpWrite, mut r: BytesReader) -> Result<u64> {
+        let mut bs = Vec::with_capacity(args.size() as usize);
+        r.read_to_end(&mut bs).await?;
+
+        self.replicate_write(args, Bytes::from(bs)).await
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.fallback(|acc| async move { acc.read_stream(args).await })
+            .await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        let chunks: Vec<Bytes> = s.try_collect().await?;
+
+        self.replicate_write(args, Bytes::from(chunks.concat()))
+            .await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.fallback(|acc| async move { acc.stat(args).await })
+            .await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        self.replicate(|acc| {
+            let args = args.clone();
+            async move { acc.delete(&args).await }
+        })
+        .await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        let ds = self
+            .fallback(|acc| async move { acc.list(args).await })
+            .await?;
+
+        Ok(Box::new(ds.map_ok(move |mut de| {
+            de.set_accessor(this.clone());
+            de
+        })))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.inner.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        self.inner.write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        self.inner.complete_multipart(args).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.inner.abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        self.blocking_replicate(|acc| acc.blocking_create(args))
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.blocking_fallback(|acc| acc.blocking_read(args))
+    }
+
+    fn blocking_write(&self, args: &OpWrite, mut r: BlockingBytesReader) -> Result<u64> {
+        let mut bs = Vec::with_capacity(args.size() as usize);
+        r.read_to_end(&mut bs)?;
+        let bs = Bytes::from(bs);
+
+        self.blocking_replicate(|acc| {
+            let r: BlockingBytesReader = Box::new(std::io::Cursor::new(bs.clone()));
+            acc.blocking_write(args, r).map(|_| ())
+        })?;
+
+        Ok(bs.len() as u64)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.blocking_fallback(|acc| acc.blocking_stat(args))
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        self.blocking_replicate(|acc| acc.blocking_delete(args))
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        let it = self.blocking_fallback(|acc| acc.blocking_list(args))?;
+
+        Ok(Box::new(it.map(move |de| {
+            de.map(|mut de| {
+                de.set_accessor(this.clone());
+                de
+            })
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::services::fs;
+    use crate::services::memory;
+
+    /// Accessor that always returns errors.
+    #[derive(Debug)]
+    struct BrokenService;
+
+    #[async_trait]
+    impl Accessor for BrokenService {
+        fn metadata(&self) -> AccessorMetadata {
+            AccessorMetadata::default()
+        }
+
+        async fn read(&self, _: &OpRead) -> Result<BytesReader> {
+            Err(std::io::Error::new(ErrorKind::Other, "broken"))
+        }
+
+        async fn write(&self, _: &OpWrite, _: BytesReader) -> Result<u64> {
+            Err(std::io::Error::new(ErrorKind::Other, "broken"))
+        }
+
+        async fn stat(&self, _: &OpStat) -> Result<ObjectMetadata> {
+            Err(std::io::Error::new(ErrorKind::Other, "broken"))
+        }
+    }
+
+    /// Accessor that delays writes.
+    #[derive(Debug)]
+    struct SlowService {
+        inner: Arc<dyn Accessor>,
+    }
+
+    #[async_trait]
+    impl Accessor for SlowService {
+        fn metadata(&self) -> AccessorMetadata {
+            self.inner.metadata()
+        }
+
+        async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.inner.write(args, r).await
+        }
+
+        async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+            self.inner.stat(args).await
+        }
+    }
+
+    fn new_memory() -> Operator {
+        Operator::new(memory::Builder::default().build().expect("must init"))
+    }
+
+    #[tokio::test]
+    async fn test_replicate_all() -> anyhow::Result<()> {
+        let (primary, r1, r2) = (new_memory(), new_memory(), new_memory());
+        let op = primary.clone().layer(
+            ReplicationLayer::new()
+                .with_replica(r1.clone())
+                .with_replica(r2.clone()),
+        );
+
+        op.object("file").write("hello").await?;
+        for o in [&primary, &r1, &r2] {
+            assert_eq!(o.object("file").read().await?, b"hello");
+        }
+
+        op.object("file").delete().await?;
+        for o in [&primary, &r1, &r2] {
+            assert!(!o.object("file").is_exist().await?);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replicate_ack() -> anyhow::Result<()> {
+        let broken = Operator::new(BrokenService);
+
+        let op = new_memory().layer(
+            ReplicationLayer::new()
+                .with_replica(new_memory())
+                .with_replica(broken.clone()),
+        );
+        assert!(op.object("file").write("hello").await.is_err());
+
+        let op = new_memory().layer(
+            ReplicationLayer::new()
+                .with_replica(new_memory())
+                .with_replica(broken.clone())
+                .with_ack(ReplicationAck::Quorum),
+        );
+        op.object("file").write("hello").await?;
+
+        let op = broken.clone().layer(
+            ReplicationLayer::new()
+                .with_replica(new_memory())
+                .with_ack(ReplicationAck::Primary),
+        );
+        assert!(op.object("file").write("hello").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replicate_primary_failed() -> anyhow::Result<()> {
+        let replica = new_memory();
+        let slow = Operator::new(SlowService {
+            inner: replica.inner(),
+        });
+
+        for ack in [ReplicationAck::All, ReplicationAck::Quorum] {
+            let op = Operator::new(BrokenService).layer(
+                ReplicationLayer::new()
+                    .with_replica(slow.clone())
+                    .with_replica(slow.clone())
+                    .with_ack(ack),
+            );
+            assert!(op.object("file").write("hello").await.is_err());
+            op.object("file")
+                .blocking_write("hello")
+                .expect_err("must fail");
+        }
+
+        // Replication should not continue in background.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!replica.object("file").is_exist().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replicate_replica_failed() -> anyhow::Result<()> {
+        let primary = new_memory();
+        let slow = Operator::new(SlowService {
+            inner: primary.inner(),
+        });
+
+        // Primary must be finished before returning the replica's error.
+        let op = slow.layer(ReplicationLayer::new().with_replica(Operator::new(BrokenService)));
+        assert!(op.object("file").write("hello").await.is_err());
+        assert_eq!(primary.object("file").read().await?, b"hello");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replicate_without_runtime() -> anyhow::Result<()> {
+        let replica = new_memory();
+        let op = new_memory().layer(
+            ReplicationLayer::new()
+                .with_replica(replica.clone())
+                .with_ack(ReplicationAck::Primary),
+        );
+
+        futures::executor::block_on(op.object("file").write("hello"))?;
+        let bs = futures::executor::block_on(replica.object("file").read())?;
+        assert_eq!(bs, b"hello");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replicate_in_background() -> anyhow::Result<()> {
+        let replica = new_memory();
+        let op = new_memory().layer(
+            ReplicationLayer::new()
+                .with_replica(replica.clone())
+                .with_ack(ReplicationAck::Primary),
+        );
+
+        op.object("file").write("hello").await?;
+        for _ in 0..100 {
+            if replica.object("file").is_exist().await? {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("file must be replicated in background")
+    }
+
+    #[tokio::test]
+    async fn test_read_fallback() -> anyhow::Result<()> {
+        let replica = new_memory();
+        replica.object("file").write("hello").await?;
+
+        let op = Operator::new(BrokenService).layer(ReplicationLayer::new().with_replica(replica));
+        assert_eq!(op.object("file").read().await?, b"hello");
+        assert_eq!(op.object("file").metadata().await?.content_length(), 5);
+
+        // NotFound from primary should not fallback to lagging replicas.
+        let (primary, replica) = (new_memory(), new_memory());
+        replica.object("file").write("deleted").await?;
+        let op = primary.layer(ReplicationLayer::new().with_replica(replica));
+        let err = op.object("file").read().await.expect_err("must not found");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(!op.object("file").is_exist().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_repair() -> anyhow::Result<()> {
+        let (primary, replica) = (new_memory(), new_memory());
+        primary.object("a").write("a").await?;
+        primary.object("dir/b").write("b").await?;
+        replica.object("dir/b").write("stale").await?;
+        replica.object("c").write("c").await?;
+
+        let layer = ReplicationLayer::new().with_replica(replica.clone());
+        let report = layer.repair(&primary, "/").await?;
+
+        let mut copied = report.copied().to_vec();
+        copied.sort();
+        assert_eq!(copied, vec!["a", "dir/b"]);
+        assert_eq!(report.removed(), ["c"]);
+        assert_eq!(replica.object("dir/b").read().await?, b"b");
+        assert!(!replica.object("c").is_exist().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_repair_primary_not_found() -> anyhow::Result<()> {
+        let root = format!("/tmp/{}/", uuid::Uuid::new_v4());
+        let primary = Operator::new(fs::Builder::default().root(&root).build()?);
+        let replica = new_memory();
+        replica.object("dir/a").write("a").await?;
+
+        let layer = ReplicationLayer::new().with_replica(replica.clone());
+        let err = layer
+            .repair(&primary, "dir/")
+            .await
+            .expect_err("must not found");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(replica.object("dir/a").is_exist().await?);
+
+        Ok(())
+    }
+}
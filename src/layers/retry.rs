@@ -13,7 +13,6 @@
 // limitations under the License.
 
 use std::fmt::Debug;
-use std::io::ErrorKind;
 use std::io::Result;
 use std::sync::Arc;
 use std::thread::sleep;
@@ -22,6 +21,7 @@ use async_trait::async_trait;
 use backon::Backoff;
 use backon::Retryable;
 
+use crate::error::is_temporary_error;
 use crate::multipart::ObjectPart;
 use crate::ops::OpAbortMultipart;
 use crate::ops::OpCompleteMultipart;
@@ -31,6 +31,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpRename;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -39,6 +40,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirIterator;
 use crate::DirStreamer;
 use crate::Layer;
@@ -46,6 +48,8 @@ use crate::ObjectMetadata;
 
 /// RetryLayer will add retry for OpenDAL.
 ///
+/// Only temporary errors will be retried, see [`crate::ObjectError::is_temporary`].
+///
 /// # Examples
 ///
 /// ```
@@ -114,35 +118,51 @@ where
     async fn create(&self, args: &OpCreate) -> Result<()> {
         { || self.inner.create(args) }
             .retry(self.backoff.clone())
-            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .when(is_temporary_error)
             .await
     }
     async fn read(&self, args: &OpRead) -> Result<BytesReader> {
         { || self.inner.read(args) }
             .retry(self.backoff.clone())
-            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .when(is_temporary_error)
             .await
     }
     async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
         // Write can't retry, until can reset this reader.
         self.inner.write(args, r).await
     }
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        { || self.inner.read_stream(args) }
+            .retry(self.backoff.clone())
+            .when(is_temporary_error)
+            .await
+    }
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        // Write can't retry, until can reset this stream.
+        self.inner.write_stream(args, s).await
+    }
     async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
         { || self.inner.stat(args) }
             .retry(self.backoff.clone())
-            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .when(is_temporary_error)
             .await
     }
     async fn delete(&self, args: &OpDelete) -> Result<()> {
         { || self.inner.delete(args) }
             .retry(self.backoff.clone())
-            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .when(is_temporary_error)
+            .await
+    }
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        { || self.inner.rename(args) }
+            .retry(self.backoff.clone())
+            .when(is_temporary_error)
             .await
     }
     async fn list(&self, args: &OpList) -> Result<DirStreamer> {
         { || self.inner.list(args) }
             .retry(self.backoff.clone())
-            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .when(is_temporary_error)
             .await
     }
 
@@ -153,7 +173,7 @@ where
     async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
         { || self.inner.create_multipart(args) }
             .retry(self.backoff.clone())
-            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .when(is_temporary_error)
             .await
     }
     async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
@@ -163,13 +183,13 @@ where
     async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
         { || self.inner.complete_multipart(args) }
             .retry(self.backoff.clone())
-            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .when(is_temporary_error)
             .await
     }
     async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
         { || self.inner.abort_multipart(args) }
             .retry(self.backoff.clone())
-            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .when(is_temporary_error)
             .await
     }
 
@@ -184,10 +204,10 @@ where
             match res {
                 Ok(v) => return Ok(v),
                 Err(err) => {
-                    let kind = err.kind();
+                    let temporary = is_temporary_error(&err);
                     e = Some(err);
 
-                    if kind == ErrorKind::Interrupted {
+                    if temporary {
                         sleep(dur);
                         continue;
                     } else {
@@ -211,10 +231,10 @@ where
             match res {
                 Ok(v) => return Ok(v),
                 Err(err) => {
-                    let kind = err.kind();
+                    let temporary = is_temporary_error(&err);
                     e = Some(err);
 
-                    if kind == ErrorKind::Interrupted {
+                    if temporary {
                         sleep(dur);
                         continue;
                     } else {
@@ -242,10 +262,10 @@ where
             match res {
                 Ok(v) => return Ok(v),
                 Err(err) => {
-                    let kind = err.kind();
+                    let temporary = is_temporary_error(&err);
                     e = Some(err);
 
-                    if kind == ErrorKind::Interrupted {
+                    if temporary {
                         sleep(dur);
                         continue;
                     } else {
@@ -269,10 +289,37 @@ where
             match res {
                 Ok(v) => return Ok(v),
                 Err(err) => {
-                    let kind = err.kind();
+                    let temporary = is_temporary_error(&err);
+                    e = Some(err);
+
+                    if temporary {
+                        sleep(dur);
+                        continue;
+                    } else {
+                        return Err(e.unwrap());
+                    }
+                }
+            }
+        }
+
+        Err(e.unwrap())
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        let retry = self.backoff.clone();
+
+        let mut e = None;
+
+        for dur in retry {
+            let res = self.inner.blocking_rename(args);
+
+            match res {
+                Ok(v) => return Ok(v),
+                Err(err) => {
+                    let temporary = is_temporary_error(&err);
                     e = Some(err);
 
-                    if kind == ErrorKind::Interrupted {
+                    if temporary {
                         sleep(dur);
                         continue;
                     } else {
@@ -296,10 +343,10 @@ where
             match res {
                 Ok(v) => return Ok(v),
                 Err(err) => {
-                    let kind = err.kind();
+                    let temporary = is_temporary_error(&err);
                     e = Some(err);
 
-                    if kind == ErrorKind::Interrupted {
+                    if temporary {
                         sleep(dur);
                         continue;
                     } else {
@@ -325,8 +372,10 @@ mod tests {
     use tokio::sync::Mutex;
 
     use crate::error::other;
+    use crate::http_util::new_response_consume_error;
     use crate::layers::RetryLayer;
     use crate::ops::OpRead;
+    use crate::ops::Operation;
     use crate::Accessor;
     use crate::BytesReader;
     use crate::Operator;
@@ -347,6 +396,11 @@ mod tests {
                     io::ErrorKind::Interrupted,
                     anyhow!("retryable_error"),
                 )),
+                "consume_error" => Err(new_response_consume_error(
+                    Operation::Read,
+                    args.path(),
+                    io::Error::new(io::ErrorKind::Interrupted, anyhow!("connection reset")),
+                )),
                 _ => Err(other(anyhow!("not_retryable_error"))),
             }
         }
@@ -385,4 +439,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_retry_consume_error() -> anyhow::Result<()> {
+        let srv = Arc::new(MockService::default());
+
+        let backoff = ConstantBackoff::default()
+            .with_delay(Duration::from_micros(1))
+            .with_max_times(3);
+        let op = Operator::new(srv.clone()).layer(RetryLayer::new(backoff));
+
+        let result = op.object("consume_error").read().await;
+        assert!(result.is_err());
+        // Interrupted while consuming the body is retryable.
+        assert_eq!(*srv.attempt.lock().await, 4);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,379 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::path::has_parent_segment;
+use crate::path::normalize_path;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirEntry;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+
+/// SubLayer will scope all operations under a sub dir.
+///
+/// Used by [`Operator::sub`][crate::Operator::sub].
+#[derive(Debug, Clone)]
+pub(crate) struct SubLayer {
+    prefix: String,
+}
+
+impl SubLayer {
+    /// Create a new sub layer, path will be normalized into dir path.
+    pub(crate) fn new(path: &str) -> Self {
+        let mut prefix = normalize_path(path);
+        if prefix == "/" {
+            prefix.clear();
+        } else if !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+
+        SubLayer { prefix }
+    }
+}
+
+impl Layer for SubLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(SubAccessor {
+            inner,
+            prefix: self.prefix.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SubAccessor {
+    inner: Arc<dyn Accessor>,
+    prefix: String,
+}
+
+impl SubAccessor {
+    /// Build the path in inner accessor.
+    ///
+    /// Paths with `..` are rejected since they could escape the sub dir.
+    fn build_abs_path(&self, op: Operation, path: &str) -> Result<String> {
+        if has_parent_segment(path) {
+            return Err(
+                ObjectError::new(op, path, anyhow!("path must not contain `..`"))
+                    .with_kind(ObjectErrorKind::PermissionDenied)
+                    .into(),
+            );
+        }
+
+        if path == "/" {
+            if self.prefix.is_empty() {
+                return Ok("/".to_string());
+            }
+            return Ok(self.prefix.clone());
+        }
+
+        Ok(format!("{}{path}", self.prefix))
+    }
+
+    /// Build the path relative to sub dir from inner path.
+    fn build_rel_path(&self, path: &str) -> String {
+        match path.strip_prefix(&self.prefix) {
+            Some("") => "/".to_string(),
+            Some(v) => v.to_string(),
+            None => path.to_string(),
+        }
+    }
+
+    fn rel_entry(&self, mut de: DirEntry) -> DirEntry {
+        de.set_path(&self.build_rel_path(de.path()));
+        de.set_accessor(Arc::new(self.clone()));
+        de
+    }
+}
+
+#[async_trait]
+impl Accessor for SubAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        let root = format!("{}/{}", meta.root().trim_end_matches('/'), self.prefix);
+        meta.set_root(&root);
+
+        meta
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::Create, args.path())?);
+        self.inner.create(&args).await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::Read, args.path())?);
+        self.inner.read(&args).await
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::Write, args.path())?);
+        self.inner.write(&args, r).await
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::Read, args.path())?);
+        self.inner.read_stream(&args).await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::Write, args.path())?);
+        self.inner.write_stream(&args, s).await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::Stat, args.path())?);
+        self.inner.stat(&args).await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::Delete, args.path())?);
+        self.inner.delete(&args).await
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        let args = OpRename::new(
+            &self.build_abs_path(Operation::Rename, args.from())?,
+            &self.build_abs_path(Operation::Rename, args.to())?,
+        )?;
+        self.inner.rename(&args).await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::List, args.path())?);
+        let ds = self.inner.list(&args).await?;
+
+        let this = self.clone();
+        Ok(Box::new(ds.map_ok(move |de| this.rel_entry(de))))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::Presign, args.path())?);
+        self.inner.presign(&args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::CreateMultipart, args.path())?);
+        self.inner.create_multipart(&args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::WriteMultipart, args.path())?);
+        self.inner.write_multipart(&args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::CompleteMultipart, args.path())?);
+        self.inner.complete_multipart(&args).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::AbortMultipart, args.path())?);
+        self.inner.abort_multipart(&args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::BlockingCreate, args.path())?);
+        self.inner.blocking_create(&args)
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::BlockingRead, args.path())?);
+        self.inner.blocking_read(&args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::BlockingWrite, args.path())?);
+        self.inner.blocking_write(&args, r)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::BlockingStat, args.path())?);
+        self.inner.blocking_stat(&args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::BlockingDelete, args.path())?);
+        self.inner.blocking_delete(&args)
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        let args = OpRename::new(
+            &self.build_abs_path(Operation::BlockingRename, args.from())?,
+            &self.build_abs_path(Operation::BlockingRename, args.to())?,
+        )?;
+        self.inner.blocking_rename(&args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let args = args
+            .clone()
+            .with_path(&self.build_abs_path(Operation::BlockingList, args.path())?);
+        let it = self.inner.blocking_list(&args)?;
+
+        let this = self.clone();
+        Ok(Box::new(it.map(move |de| de.map(|de| this.rel_entry(de)))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use futures::TryStreamExt;
+
+    use crate::services::fs;
+    use crate::services::memory;
+    use crate::Operator;
+
+    #[test]
+    fn test_sub_layer_prefix() {
+        let cases = vec![
+            ("/", ""),
+            ("", ""),
+            ("tenant", "tenant/"),
+            ("/tenant/a/", "tenant/a/"),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(super::SubLayer::new(input).prefix, expected, "{input}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sub() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        op.object("outside").write("outside").await?;
+
+        let sub = op.sub("tenant/a");
+        assert_eq!(sub.metadata().root(), "/tenant/a/");
+
+        sub.object("dir/file").write("hello").await?;
+        assert_eq!(op.object("tenant/a/dir/file").read().await?, b"hello");
+        assert!(!sub.object("outside").is_exist().await?);
+
+        let entries: Vec<_> = sub.object("dir/").list().await?.try_collect().await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path(), "dir/file");
+        assert_eq!(entries[0].clone().into_object().read().await?, b"hello");
+
+        // Sub of sub should be nested.
+        let nested = sub.sub("dir");
+        assert_eq!(nested.metadata().root(), "/tenant/a/dir/");
+        assert_eq!(nested.object("file").read().await?, b"hello");
+
+        // Paths with `..` should not escape the sub dir.
+        for path in ["../b/x", "dir/../../outside", ".."] {
+            let err = sub.object(path).read().await.expect_err("must be denied");
+            assert_eq!(err.kind(), ErrorKind::PermissionDenied, "{path}");
+        }
+        let err = sub
+            .object("../b/x")
+            .write("escaped")
+            .await
+            .expect_err("must be denied");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert!(!op.object("tenant/b/x").is_exist().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sub_rename() -> anyhow::Result<()> {
+        let root = format!("/tmp/{}/", uuid::Uuid::new_v4());
+        let op = Operator::new(fs::Builder::default().root(&root).build()?);
+        let sub = op.sub("tenant/a");
+        assert!(sub.metadata().can_rename());
+
+        sub.object("from").write("hello").await?;
+        sub.object("from").rename("dir/to").await?;
+        assert_eq!(op.object("tenant/a/dir/to").read().await?, b"hello");
+        assert!(!op.object("tenant/a/from").is_exist().await?);
+
+        let err = sub
+            .object("dir/to")
+            .rename("../b/to")
+            .await
+            .expect_err("must be denied");
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        op.batch().remove_all("/").await?;
+        Ok(())
+    }
+}
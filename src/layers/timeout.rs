@@ -0,0 +1,724 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::future::select;
+use futures::future::Either;
+use futures::pin_mut;
+use futures::ready;
+use futures::AsyncRead;
+use futures::Stream;
+use parking_lot::Mutex;
+use tokio::time::sleep;
+use tokio::time::sleep_until;
+use tokio::time::Instant;
+use tokio::time::Sleep;
+
+use crate::error::ObjectError;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Layer;
+use crate::ObjectMetadata;
+
+/// TimeoutLayer will add timeout for OpenDAL.
+///
+/// - Deadline: async operations must finish in the given duration, which
+///   could be set for all operations or for every [`Operation`].
+/// - Idle timeout: readers, byte streams and dir streams returned by
+///   operations must make progress in the given duration. For `write`, the
+///   input must be consumed by services in the given duration.
+///
+/// Timeout errors are temporary [`ObjectError`](crate::ObjectError)s with
+/// [`ErrorKind::TimedOut`] by default, so they will be retried by
+/// `RetryLayer`. Use [`TimeoutLayer::with_error_kind`] to return another kind
+/// like [`ErrorKind::Interrupted`] instead.
+///
+/// # Notes
+///
+/// Blocking operations and `presign` are not affected by this layer.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::layers::TimeoutLayer;
+/// use opendal::ops::Operation;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(
+///         TimeoutLayer::new()
+///             .with_timeout(Duration::from_secs(10))
+///             .with_operation_timeout(Operation::List, Duration::from_secs(60))
+///             .with_idle_timeout(Duration::from_secs(5)),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeoutLayer {
+    timeout: Option<Duration>,
+    operation_timeouts: HashMap<Operation, Duration>,
+    idle_timeout: Option<Duration>,
+    error_kind: ErrorKind,
+}
+
+impl TimeoutLayer {
+    /// Create a new timeout layer without any timeout.
+    pub fn new() -> Self {
+        TimeoutLayer {
+            timeout: None,
+            operation_timeouts: HashMap::new(),
+            idle_timeout: None,
+            error_kind: ErrorKind::TimedOut,
+        }
+    }
+
+    /// Set deadline for all async operations.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set deadline for the given operation, which overrides the deadline
+    /// set by `with_timeout`.
+    ///
+    /// `read_stream` and `write_stream` share the deadline of
+    /// [`Operation::Read`] and [`Operation::Write`].
+    pub fn with_operation_timeout(mut self, op: Operation, timeout: Duration) -> Self {
+        self.operation_timeouts.insert(op, timeout);
+        self
+    }
+
+    /// Set idle timeout for readers, streams and dir streams.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the [`ErrorKind`] of timeout errors.
+    ///
+    /// Default to [`ErrorKind::TimedOut`].
+    pub fn with_error_kind(mut self, kind: ErrorKind) -> Self {
+        self.error_kind = kind;
+        self
+    }
+}
+
+impl Default for TimeoutLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Layer for TimeoutLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(TimeoutAccessor {
+            inner,
+            layer: self.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct TimeoutAccessor {
+    inner: Arc<dyn Accessor>,
+    layer: TimeoutLayer,
+}
+
+impl TimeoutAccessor {
+    /// Run the future with the deadline of given operation.
+    async fn with_deadline<T>(
+        &self,
+        op: Operation,
+        path: &str,
+        fut: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let timeout = match self
+            .layer
+            .operation_timeouts
+            .get(&op)
+            .or(self.layer.timeout.as_ref())
+        {
+            None => return fut.await,
+            Some(v) => *v,
+        };
+
+        match tokio::time::timeout(timeout, fut).await {
+            Ok(v) => v,
+            Err(_) => Err(new_timeout_error(
+                self.layer.error_kind,
+                op,
+                path,
+                anyhow!("operation is not finished in {timeout:?}"),
+            )),
+        }
+    }
+
+    /// Run the future until it's finished or the input has not been
+    /// consumed for the idle timeout.
+    async fn with_idle_watch<T>(
+        &self,
+        op: Operation,
+        path: &str,
+        idle: Duration,
+        activity: Arc<Mutex<Activity>>,
+        fut: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let watchdog = async {
+            loop {
+                let (last, finished) = {
+                    let activity = activity.lock();
+                    (activity.last, activity.finished)
+                };
+                // Services could take a while to respond after all input
+                // consumed, and that will be bounded by deadline instead.
+                if finished {
+                    return futures::future::pending().await;
+                }
+                let deadline = last + idle;
+                if Instant::now() >= deadline {
+                    return;
+                }
+                sleep_until(deadline).await;
+            }
+        };
+
+        pin_mut!(fut, watchdog);
+        match select(fut, watchdog).await {
+            Either::Left((v, _)) => v,
+            Either::Right(_) => Err(new_timeout_error(
+                self.layer.error_kind,
+                op,
+                path,
+                anyhow!("input is not consumed in {idle:?}"),
+            )),
+        }
+    }
+
+    fn idle_reader(&self, op: Operation, path: &str, r: BytesReader) -> BytesReader {
+        match self.layer.idle_timeout {
+            None => r,
+            Some(idle) => Box::new(IdleTimeoutWrapper::new(
+                r,
+                idle,
+                self.layer.error_kind,
+                op,
+                path,
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl Accessor for TimeoutAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        self.with_deadline(Operation::Create, args.path(), self.inner.create(args))
+            .await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let r = self
+            .with_deadline(Operation::Read, args.path(), self.inner.read(args))
+            .await?;
+        Ok(self.idle_reader(Operation::Read, args.path(), r))
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        let idle = match self.layer.idle_timeout {
+            None => {
+                return self
+                    .with_deadline(Operation::Write, args.path(), self.inner.write(args, r))
+                    .await
+            }
+            Some(v) => v,
+        };
+
+        let (r, activity) = ActivityWrapper::new(r);
+        let fut = self.with_idle_watch(
+            Operation::Write,
+            args.path(),
+            idle,
+            activity,
+            self.inner.write(args, Box::new(r)),
+        );
+        self.with_deadline(Operation::Write, args.path(), fut).await
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        let s = self
+            .with_deadline(Operation::Read, args.path(), self.inner.read_stream(args))
+            .await?;
+        Ok(match self.layer.idle_timeout {
+            None => s,
+            Some(idle) => Box::new(IdleTimeoutWrapper::new(
+                s,
+                idle,
+                self.layer.error_kind,
+                Operation::Read,
+                args.path(),
+            )),
+        })
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        let idle = match self.layer.idle_timeout {
+            None => {
+                return self
+                    .with_deadline(
+                        Operation::Write,
+                        args.path(),
+                        self.inner.write_stream(args, s),
+                    )
+                    .await
+            }
+            Some(v) => v,
+        };
+
+        let (s, activity) = ActivityWrapper::new(s);
+        let fut = self.with_idle_watch(
+            Operation::Write,
+            args.path(),
+            idle,
+            activity,
+            self.inner.write_stream(args, Box::new(s)),
+        );
+        self.with_deadline(Operation::Write, args.path(), fut).await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.with_deadline(Operation::Stat, args.path(), self.inner.stat(args))
+            .await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        self.with_deadline(Operation::Delete, args.path(), self.inner.delete(args))
+            .await
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        self.with_deadline(Operation::Rename, args.from(), self.inner.rename(args))
+            .await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let ds = self
+            .with_deadline(Operation::List, args.path(), self.inner.list(args))
+            .await?;
+        Ok(match self.layer.idle_timeout {
+            None => ds,
+            Some(idle) => Box::new(IdleTimeoutWrapper::new(
+                ds,
+                idle,
+                self.layer.error_kind,
+                Operation::List,
+                args.path(),
+            )),
+        })
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.with_deadline(
+            Operation::CreateMultipart,
+            args.path(),
+            self.inner.create_multipart(args),
+        )
+        .await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        let idle = match self.layer.idle_timeout {
+            None => {
+                return self
+                    .with_deadline(
+                        Operation::WriteMultipart,
+                        args.path(),
+                        self.inner.write_multipart(args, r),
+                    )
+                    .await
+            }
+            Some(v) => v,
+        };
+
+        let (r, activity) = ActivityWrapper::new(r);
+        let fut = self.with_idle_watch(
+            Operation::WriteMultipart,
+            args.path(),
+            idle,
+            activity,
+            self.inner.write_multipart(args, Box::new(r)),
+        );
+        self.with_deadline(Operation::WriteMultipart, args.path(), fut)
+            .await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        self.with_deadline(
+            Operation::CompleteMultipart,
+            args.path(),
+            self.inner.complete_multipart(args),
+        )
+        .await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.with_deadline(
+            Operation::AbortMultipart,
+            args.path(),
+            self.inner.abort_multipart(args),
+        )
+        .await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.blocking_create(args)
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.inner.blocking_read(args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.inner.blocking_write(args, r)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.blocking_stat(args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        self.inner.blocking_delete(args)
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.blocking_rename(args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        self.inner.blocking_list(args)
+    }
+}
+
+fn new_timeout_error(kind: ErrorKind, op: Operation, path: &str, err: anyhow::Error) -> Error {
+    Error::new(kind, ObjectError::new(op, path, err).with_temporary(true))
+}
+
+/// IdleTimeoutWrapper will return error if inner reader or stream doesn't
+/// make progress during the idle timeout.
+struct IdleTimeoutWrapper<T> {
+    inner: T,
+    idle: Duration,
+    sleep: Option<Pin<Box<Sleep>>>,
+
+    error_kind: ErrorKind,
+    op: Operation,
+    path: String,
+}
+
+impl<T> IdleTimeoutWrapper<T> {
+    fn new(inner: T, idle: Duration, error_kind: ErrorKind, op: Operation, path: &str) -> Self {
+        IdleTimeoutWrapper {
+            inner,
+            idle,
+            sleep: None,
+            error_kind,
+            op,
+            path: path.to_string(),
+        }
+    }
+
+    /// Poll the idle timer, returns error if it's expired.
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<Error> {
+        let idle = self.idle;
+        let timer = self.sleep.get_or_insert_with(|| Box::pin(sleep(idle)));
+        ready!(timer.as_mut().poll(cx));
+        self.sleep = None;
+
+        Poll::Ready(new_timeout_error(
+            self.error_kind,
+            self.op,
+            &self.path,
+            anyhow!("no progress in {idle:?}"),
+        ))
+    }
+}
+
+impl<T> AsyncRead for IdleTimeoutWrapper<T>
+where
+    T: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        if let Poll::Ready(v) = Pin::new(&mut self.inner).poll_read(cx, buf) {
+            self.sleep = None;
+            return Poll::Ready(v);
+        }
+
+        self.poll_idle(cx).map(Err)
+    }
+}
+
+impl<T, I> Stream for IdleTimeoutWrapper<T>
+where
+    T: Stream<Item = Result<I>> + Unpin,
+{
+    type Item = Result<I>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Poll::Ready(v) = Pin::new(&mut self.inner).poll_next(cx) {
+            self.sleep = None;
+            return Poll::Ready(v);
+        }
+
+        self.poll_idle(cx).map(|err| Some(Err(err)))
+    }
+}
+
+/// Activity records the progress of input.
+struct Activity {
+    last: Instant,
+    finished: bool,
+}
+
+/// ActivityWrapper will record the activity while input is consumed.
+struct ActivityWrapper<T> {
+    inner: T,
+    activity: Arc<Mutex<Activity>>,
+}
+
+impl<T> ActivityWrapper<T> {
+    fn new(inner: T) -> (Self, Arc<Mutex<Activity>>) {
+        let activity = Arc::new(Mutex::new(Activity {
+            last: Instant::now(),
+            finished: false,
+        }));
+
+        (
+            ActivityWrapper {
+                inner,
+                activity: activity.clone(),
+            },
+            activity,
+        )
+    }
+
+    fn record(&self, finished: bool) {
+        let mut activity = self.activity.lock();
+        activity.last = Instant::now();
+        activity.finished = finished;
+    }
+}
+
+impl<T> AsyncRead for ActivityWrapper<T>
+where
+    T: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let n = ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        self.record(n == 0 && !buf.is_empty());
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl<T, I> Stream for ActivityWrapper<T>
+where
+    T: Stream<Item = Result<I>> + Unpin,
+{
+    type Item = Result<I>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let v = ready!(Pin::new(&mut self.inner).poll_next(cx));
+        self.record(v.is_none());
+        Poll::Ready(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::io::Cursor;
+    use futures::stream;
+    use futures::AsyncReadExt;
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::Operator;
+
+    /// MockService will hang on `stat`, and return readers or streams that
+    /// hang after the first chunk.
+    #[derive(Debug, Clone, Default)]
+    struct MockService;
+
+    #[async_trait]
+    impl Accessor for MockService {
+        fn metadata(&self) -> AccessorMetadata {
+            AccessorMetadata::default()
+        }
+
+        async fn read(&self, _: &OpRead) -> Result<BytesReader> {
+            let r = Cursor::new(b"hello".to_vec()).chain(PendingReader);
+            Ok(Box::new(r))
+        }
+
+        async fn write(&self, _: &OpWrite, mut r: BytesReader) -> Result<u64> {
+            let mut bs = Vec::new();
+            r.read_to_end(&mut bs).await?;
+            // Pretend that services take a while to respond.
+            sleep(Duration::from_millis(100)).await;
+            Ok(bs.len() as u64)
+        }
+
+        async fn stat(&self, _: &OpStat) -> Result<ObjectMetadata> {
+            futures::future::pending().await
+        }
+
+        async fn list(&self, _: &OpList) -> Result<DirStreamer> {
+            Ok(Box::new(stream::pending()))
+        }
+    }
+
+    struct PendingReader;
+
+    impl AsyncRead for PendingReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            _: &mut [u8],
+        ) -> Poll<Result<usize>> {
+            Poll::Pending
+        }
+    }
+
+    fn new_operator() -> Operator {
+        Operator::new(MockService).layer(
+            TimeoutLayer::new()
+                .with_timeout(Duration::from_millis(500))
+                .with_operation_timeout(Operation::Stat, Duration::from_millis(10))
+                .with_idle_timeout(Duration::from_millis(50)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_deadline() {
+        let op = new_operator();
+
+        let err = op
+            .object("test")
+            .metadata()
+            .await
+            .expect_err("must timeout");
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        assert!(ObjectError::downcast(&err)
+            .expect("must be object error")
+            .is_temporary());
+    }
+
+    #[tokio::test]
+    async fn test_idle_reader() {
+        let op = new_operator();
+
+        let mut r = op.object("test").reader().await.expect("must read");
+        let mut buf = [0; 5];
+        r.read_exact(&mut buf).await.expect("must read first chunk");
+        assert_eq!(&buf, b"hello");
+
+        let err = r.read(&mut buf).await.expect_err("must timeout");
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_idle_list() {
+        let op = Operator::new(MockService).layer(
+            TimeoutLayer::new()
+                .with_idle_timeout(Duration::from_millis(10))
+                .with_error_kind(ErrorKind::Interrupted),
+        );
+
+        let mut ds = op.object("test/").list().await.expect("must list");
+        let err = ds
+            .next()
+            .await
+            .expect("must have entry")
+            .expect_err("must timeout");
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+    }
+
+    #[tokio::test]
+    async fn test_idle_write() {
+        let op = new_operator();
+
+        // Services take longer than idle timeout to respond after consuming
+        // the whole input, which is not considered as idle.
+        op.object("test")
+            .write(vec![0; 1024])
+            .await
+            .expect("write must succeed");
+
+        let err = op
+            .object("test")
+            .write_from(5, Cursor::new(b"hello".to_vec()).chain(PendingReader))
+            .await
+            .expect_err("must timeout");
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+}
@@ -21,6 +21,7 @@ use std::task::Context;
 use std::task::Poll;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::AsyncRead;
 use tracing::Span;
 
@@ -33,6 +34,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpRename;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -41,6 +43,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirEntry;
 use crate::DirIterator;
 use crate::DirStreamer;
@@ -100,6 +103,20 @@ impl Accessor for TracingAccessor {
         self.inner.write(args, r).await
     }
 
+    #[tracing::instrument]
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.inner
+            .read_stream(args)
+            .await
+            .map(|s| Box::new(TracingBytesStream::new(Span::current(), s)) as BytesStreamer)
+    }
+
+    #[tracing::instrument(skip(s))]
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        let s = Box::new(TracingBytesStream::new(Span::current(), s));
+        self.inner.write_stream(args, s).await
+    }
+
     #[tracing::instrument]
     async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
         self.inner.stat(args).await
@@ -110,6 +127,11 @@ impl Accessor for TracingAccessor {
         self.inner.delete(args).await
     }
 
+    #[tracing::instrument]
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.rename(args).await
+    }
+
     #[tracing::instrument]
     async fn list(&self, args: &OpList) -> Result<DirStreamer> {
         self.inner
@@ -171,6 +193,11 @@ impl Accessor for TracingAccessor {
         self.inner.blocking_delete(args)
     }
 
+    #[tracing::instrument]
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.blocking_rename(args)
+    }
+
     #[tracing::instrument]
     fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
         self.inner
@@ -201,6 +228,26 @@ impl AsyncRead for TracingReader {
     }
 }
 
+struct TracingBytesStream {
+    span: Span,
+    inner: BytesStreamer,
+}
+
+impl TracingBytesStream {
+    fn new(span: Span, inner: BytesStreamer) -> Self {
+        Self { span, inner }
+    }
+}
+
+impl futures::Stream for TracingBytesStream {
+    type Item = Result<Bytes>;
+
+    #[tracing::instrument(parent=&self.span, skip(self))]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut (*self.inner)).poll_next(cx)
+    }
+}
+
 struct BlockingTracingReader {
     span: Span,
     inner: BlockingBytesReader,
@@ -0,0 +1,708 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use log::debug;
+use log::warn;
+use time::OffsetDateTime;
+
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::io_util::TopDownWalker;
+use crate::multipart::ObjectPart;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::path::get_basename;
+use crate::path::get_parent;
+use crate::path::normalize_path;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Object;
+use crate::ObjectMetadata;
+use crate::ObjectMode;
+use crate::Operator;
+
+/// TrashLayer will move deleted objects into trash for OpenDAL.
+///
+/// `delete` on a file will move it into the trash prefix instead of
+/// removing it. Apply this layer by [`TrashLayer::apply`] to get a [`Trash`],
+/// deleted objects could be brought back by [`Trash::restore`] and removed
+/// permanently by [`Trash::purge`].
+///
+/// TrashLayer doesn't implement [`Layer`][crate::Layer], since
+/// [`Operator`] can't reach a layer inside its stack to restore or purge,
+/// deleted objects would be unreachable via [`Operator::layer`].
+///
+/// Every deletion is kept as an entry with unique id (deletion time in
+/// unix nanoseconds), trash is organized as:
+///
+/// - `<prefix>files/<path>/<id>`: content of the deleted object.
+/// - `<prefix>info/<path>/<id>`: deletion time in unix seconds.
+///
+/// The trash prefix will be hidden from `list` unless listing inside it.
+///
+/// # Notes
+///
+/// - Objects are moved by [`Accessor::rename`] if services support it
+///   (like `fs`), otherwise by copy and delete.
+/// - Deleting a path twice will keep both objects, and the latest one
+///   will be restored first.
+/// - Dirs and objects inside trash will be deleted directly.
+/// - Layers applied after this layer are not used by [`Trash`], paths
+///   passed to it are the paths seen by this layer.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::layers::TrashLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let (op, trash) = TrashLayer::new().apply(Operator::from_env(Scheme::Memory)?);
+///
+/// op.object("test").write("Hello, World!").await?;
+/// op.object("test").delete().await?;
+///
+/// trash.restore("test").await?;
+/// trash.purge(Duration::from_secs(7 * 24 * 3600)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrashLayer {
+    prefix: String,
+}
+
+impl Default for TrashLayer {
+    fn default() -> Self {
+        Self {
+            prefix: ".trash/".to_string(),
+        }
+    }
+}
+
+impl TrashLayer {
+    /// Create a new trash layer with default prefix `.trash/`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the trash prefix, prefix will be normalized into dir path.
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        let mut prefix = normalize_path(prefix);
+        assert!(prefix != "/", "trash prefix must not be root");
+        if !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+        self.prefix = prefix;
+        self
+    }
+
+    /// Apply this layer to the operator, returns the layered operator and
+    /// the [`Trash`] to manage deleted objects.
+    pub fn apply(self, op: Operator) -> (Operator, Trash) {
+        let inner = op.inner();
+        let trash = Trash {
+            inner: inner.clone(),
+            prefix: self.prefix.clone(),
+        };
+        let op = Operator::new(TrashAccessor {
+            inner,
+            prefix: self.prefix,
+        });
+        (op, trash)
+    }
+}
+
+/// Trash manages objects deleted via [`TrashLayer`], returned by
+/// [`TrashLayer::apply`].
+#[derive(Debug, Clone)]
+pub struct Trash {
+    inner: Arc<dyn Accessor>,
+    prefix: String,
+}
+
+impl Trash {
+    /// Restore the latest deleted object at `path` from trash.
+    ///
+    /// Returns [`ObjectErrorKind::ObjectAlreadyExists`] if `path` exists, and
+    /// [`ObjectErrorKind::ObjectNotFound`] if no deleted object in trash.
+    pub async fn restore(&self, path: &str) -> Result<()> {
+        let acc = &self.inner;
+        let path = normalize_path(path);
+
+        match acc.stat(&OpStat::new(&path)?).await {
+            Ok(_) => {
+                return Err(
+                    ObjectError::new(Operation::Write, &path, anyhow!("object exists"))
+                        .with_kind(ObjectErrorKind::ObjectAlreadyExists)
+                        .into(),
+                )
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        let id = self.latest_entry(&path).await?.ok_or_else(|| {
+            ObjectError::new(Operation::Read, &path, anyhow!("object not found in trash"))
+                .with_kind(ObjectErrorKind::ObjectNotFound)
+        })?;
+
+        move_object(acc, &files_path(&self.prefix, &path, &id), &path).await?;
+        acc.delete(&OpDelete::new(&info_path(&self.prefix, &path, &id))?)
+            .await?;
+        debug!("trash restored {path} from {id}");
+        Ok(())
+    }
+
+    /// Returns the id of latest trash entry of `path`.
+    async fn latest_entry(&self, path: &str) -> Result<Option<String>> {
+        let dir = format!("{}/", files_path(&self.prefix, path, ""));
+        let entries: Vec<_> = match Object::new(self.inner.clone(), &dir).list().await {
+            Ok(ds) => ds.try_collect().await?,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        // Entries of paths under `path` are dirs here, skip them.
+        Ok(entries
+            .into_iter()
+            .filter(|de| !de.mode().is_dir())
+            .map(|de| de.name().to_string())
+            .max())
+    }
+
+    /// Remove objects deleted before `older_than` from trash permanently,
+    /// returns the count of purged objects.
+    pub async fn purge(&self, older_than: Duration) -> Result<usize> {
+        let acc = &self.inner;
+        let info_prefix = format!("{}info/", self.prefix);
+        let older_than = i64::try_from(older_than.as_secs()).unwrap_or(i64::MAX);
+        let deadline = OffsetDateTime::now_utc()
+            .unix_timestamp()
+            .saturating_sub(older_than);
+
+        let mut purged = 0;
+        let mut walker = TopDownWalker::new(Object::new(acc.clone(), &info_prefix));
+        loop {
+            let de = match walker.try_next().await {
+                Ok(Some(de)) => de,
+                Ok(None) => break,
+                // Trash could be empty.
+                Err(err) if err.kind() == ErrorKind::NotFound => break,
+                Err(err) => return Err(err),
+            };
+            if de.mode().is_dir() {
+                continue;
+            }
+
+            let info = de.path().to_string();
+            let bs = Object::new(acc.clone(), &info).read().await?;
+            let deleted_at = match String::from_utf8_lossy(&bs).trim().parse::<i64>() {
+                Ok(v) => v,
+                Err(err) => {
+                    warn!("trash info {info} is invalid: {err}");
+                    continue;
+                }
+            };
+            if deleted_at > deadline {
+                continue;
+            }
+
+            let rel = &info[info_prefix.len()..];
+            let (path, id) = (get_parent(rel).trim_end_matches('/'), get_basename(rel));
+            acc.delete(&OpDelete::new(&files_path(&self.prefix, path, id))?)
+                .await?;
+            acc.delete(&OpDelete::new(&info)?).await?;
+            debug!("trash purged {path} from {id}");
+            purged += 1;
+        }
+
+        Ok(purged)
+    }
+}
+
+fn files_path(prefix: &str, path: &str, id: &str) -> String {
+    format!("{prefix}files/{path}/{id}")
+}
+
+fn info_path(prefix: &str, path: &str, id: &str) -> String {
+    format!("{prefix}info/{path}/{id}")
+}
+
+/// Returns a new entry id and its deletion info.
+fn new_entry() -> (String, Vec<u8>) {
+    let now = OffsetDateTime::now_utc();
+    (
+        // Padded so that ids are ordered by deletion time.
+        format!("{:020}", now.unix_timestamp_nanos()),
+        now.unix_timestamp().to_string().into_bytes(),
+    )
+}
+
+async fn move_object(acc: &Arc<dyn Accessor>, from: &str, to: &str) -> Result<()> {
+    // Rename could still be unsupported for some paths, like paths across
+    // mount points of router.
+    if acc.metadata().can_rename() {
+        match acc.rename(&OpRename::new(from, to)?).await {
+            Err(err) if err.kind() == ErrorKind::Unsupported => {}
+            res => return res,
+        }
+    }
+
+    let meta = acc.stat(&OpStat::new(from)?).await?;
+    let r = acc.read(&OpRead::new(from, ..)?).await?;
+    acc.write(&OpWrite::new(to, meta.content_length())?, r)
+        .await?;
+    acc.delete(&OpDelete::new(from)?).await
+}
+
+fn blocking_move_object(acc: &Arc<dyn Accessor>, from: &str, to: &str) -> Result<()> {
+    if acc.metadata().can_rename() {
+        match acc.blocking_rename(&OpRename::new(from, to)?) {
+            Err(err) if err.kind() == ErrorKind::Unsupported => {}
+            res => return res,
+        }
+    }
+
+    let meta = acc.blocking_stat(&OpStat::new(from)?)?;
+    let r = acc.blocking_read(&OpRead::new(from, ..)?)?;
+    acc.blocking_write(&OpWrite::new(to, meta.content_length())?, r)?;
+    acc.blocking_delete(&OpDelete::new(from)?)
+}
+
+#[derive(Debug, Clone)]
+struct TrashAccessor {
+    inner: Arc<dyn Accessor>,
+    prefix: String,
+}
+
+impl TrashAccessor {
+    fn in_trash(&self, path: &str) -> bool {
+        path.starts_with(&self.prefix)
+    }
+
+    /// Returns true if the path should be moved into trash.
+    async fn is_trashable(&self, path: &str) -> Result<bool> {
+        if self.in_trash(path) {
+            return Ok(false);
+        }
+
+        match self.inner.stat(&OpStat::new(path)?).await {
+            Ok(meta) => Ok(meta.mode() == ObjectMode::FILE),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn blocking_is_trashable(&self, path: &str) -> Result<bool> {
+        if self.in_trash(path) {
+            return Ok(false);
+        }
+
+        match self.inner.blocking_stat(&OpStat::new(path)?) {
+            Ok(meta) => Ok(meta.mode() == ObjectMode::FILE),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Hide trash from listing unless listing inside it.
+    fn is_visible(&self, list_path: &str, path: &str) -> bool {
+        self.in_trash(list_path) || !self.in_trash(path)
+    }
+}
+
+#[async_trait]
+impl Accessor for TrashAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.create(args).await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        self.inner.read(args).await
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        self.inner.write(args, r).await
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        self.inner.read_stream(args).await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.inner.write_stream(args, s).await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.stat(args).await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        let path = args.path();
+        if !self.is_trashable(path).await? {
+            return self.inner.delete(args).await;
+        }
+
+        // Write info first so that content in trash always has its info.
+        let (id, info) = new_entry();
+        let info_path = info_path(&self.prefix, path, &id);
+        self.inner
+            .write(
+                &OpWrite::new(&info_path, info.len() as u64)?,
+                Box::new(futures::io::Cursor::new(info)),
+            )
+            .await?;
+        if let Err(err) = move_object(&self.inner, path, &files_path(&self.prefix, path, &id)).await
+        {
+            if let Err(e) = self.inner.delete(&OpDelete::new(&info_path)?).await {
+                warn!("trash info {info_path} cleanup failed: {e}");
+            }
+            return Err(err);
+        }
+        debug!("trash moved {path} to {id}");
+        Ok(())
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let ds = self.inner.list(args).await?;
+
+        // Objects converted from entries should be trashed too.
+        let this = Arc::new(self.clone());
+        let list_path = args.path().to_string();
+        Ok(Box::new(ds.try_filter_map(move |mut de| {
+            let de = if this.is_visible(&list_path, de.path()) {
+                de.set_accessor(this.clone());
+                Some(de)
+            } else {
+                None
+            };
+            futures::future::ok(de)
+        })))
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.rename(args).await
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        self.inner.presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.inner.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        self.inner.write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        self.inner.complete_multipart(args).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.inner.abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        self.inner.blocking_create(args)
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.inner.blocking_read(args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.inner.blocking_write(args, r)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        self.inner.blocking_stat(args)
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        let path = args.path();
+        if !self.blocking_is_trashable(path)? {
+            return self.inner.blocking_delete(args);
+        }
+
+        let (id, info) = new_entry();
+        let info_path = info_path(&self.prefix, path, &id);
+        self.inner.blocking_write(
+            &OpWrite::new(&info_path, info.len() as u64)?,
+            Box::new(std::io::Cursor::new(info)),
+        )?;
+        if let Err(err) =
+            blocking_move_object(&self.inner, path, &files_path(&self.prefix, path, &id))
+        {
+            if let Err(e) = self.inner.blocking_delete(&OpDelete::new(&info_path)?) {
+                warn!("trash info {info_path} cleanup failed: {e}");
+            }
+            return Err(err);
+        }
+        debug!("trash moved {path} to {id}");
+        Ok(())
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        self.inner.blocking_rename(args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let it = self.inner.blocking_list(args)?;
+
+        let this = Arc::new(self.clone());
+        let list_path = args.path().to_string();
+        Ok(Box::new(it.filter_map(move |de| match de {
+            Ok(mut de) if this.is_visible(&list_path, de.path()) => {
+                de.set_accessor(this.clone());
+                Some(Ok(de))
+            }
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::fs;
+    use crate::services::memory;
+
+    /// Returns names of files in dir, empty if dir not exist.
+    async fn list_files(op: &Operator, dir: &str) -> anyhow::Result<Vec<String>> {
+        let entries: Vec<_> = match op.object(dir).list().await {
+            Ok(ds) => ds.try_collect().await?,
+            Err(err) if err.kind() == ErrorKind::NotFound => vec![],
+            Err(err) => return Err(err.into()),
+        };
+        let mut names: Vec<_> = entries
+            .iter()
+            .filter(|de| !de.mode().is_dir())
+            .map(|de| de.name().to_string())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    fn error_kind(err: &std::io::Error) -> Option<ObjectErrorKind> {
+        ObjectError::downcast(err).map(|v| v.kind())
+    }
+
+    #[tokio::test]
+    async fn test_delete_and_restore() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let (trashed, trash) = TrashLayer::new().apply(op.clone());
+
+        trashed.object("dir/test").write("Hello, World!").await?;
+        trashed.object("keep").write("keep").await?;
+        trashed.object("dir/test").delete().await?;
+        assert!(!trashed.object("dir/test").is_exist().await?);
+        let ids = list_files(&op, ".trash/files/dir/test/").await?;
+        assert_eq!(ids.len(), 1);
+        assert_eq!(
+            op.object(&format!(".trash/files/dir/test/{}", ids[0]))
+                .read()
+                .await?,
+            b"Hello, World!"
+        );
+        assert_eq!(list_files(&op, ".trash/info/dir/test/").await?, ids);
+
+        // Trash is hidden from list.
+        let entries: Vec<_> = trashed.object("/").list().await?.try_collect().await?;
+        let paths: Vec<_> = entries.iter().map(|de| de.path().to_string()).collect();
+        assert_eq!(paths, vec!["keep".to_string()]);
+        let entries: Vec<_> = trashed
+            .object(".trash/")
+            .list()
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(entries.len(), 2);
+
+        // Existing objects will not be overwritten by restore.
+        trashed.object("dir/test").write("Hello, Trash!").await?;
+        let err = trash
+            .restore("dir/test")
+            .await
+            .expect_err("restore must not overwrite");
+        assert_eq!(error_kind(&err), Some(ObjectErrorKind::ObjectAlreadyExists));
+
+        // Deleted twice, the latest one will be restored first.
+        trashed.object("dir/test").delete().await?;
+        assert_eq!(list_files(&op, ".trash/files/dir/test/").await?.len(), 2);
+        trash.restore("dir/test").await?;
+        assert_eq!(trashed.object("dir/test").read().await?, b"Hello, Trash!");
+        trashed.object("dir/test").delete().await?;
+        trash.restore("dir/test").await?;
+        assert_eq!(trashed.object("dir/test").read().await?, b"Hello, Trash!");
+        assert_eq!(list_files(&op, ".trash/files/dir/test/").await?, ids);
+        assert_eq!(list_files(&op, ".trash/info/dir/test/").await?, ids);
+
+        let err = trash
+            .restore("not_exist")
+            .await
+            .expect_err("restore must fail");
+        assert_eq!(error_kind(&err), Some(ObjectErrorKind::ObjectNotFound));
+
+        // Objects inside trash are deleted directly.
+        trashed.object(".trash/other").write("x").await?;
+        trashed.object(".trash/other").delete().await?;
+        assert!(list_files(&op, ".trash/files/.trash/other/")
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remove_all_and_purge() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let (trashed, trash) = TrashLayer::new().with_prefix("/recycle").apply(op.clone());
+
+        trashed.object("data/a").write("a").await?;
+        trashed.object("data/b/c").write("c").await?;
+        trashed.batch().remove_all("data/").await?;
+        assert_eq!(list_files(&op, "recycle/files/data/a/").await?.len(), 1);
+        assert_eq!(list_files(&op, "recycle/files/data/b/c/").await?.len(), 1);
+
+        assert_eq!(trash.purge(Duration::from_secs(3600)).await?, 0);
+        assert_eq!(trash.purge(Duration::MAX).await?, 0);
+        assert_eq!(trash.purge(Duration::ZERO).await?, 2);
+        assert!(list_files(&op, "recycle/files/data/a/").await?.is_empty());
+        assert!(list_files(&op, "recycle/info/data/b/c/").await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nested_paths_on_fs() -> anyhow::Result<()> {
+        let root = format!("/tmp/{}/", uuid::Uuid::new_v4());
+        let op = Operator::new(fs::Builder::default().root(&root).build()?);
+        assert!(op.metadata().can_rename());
+        let (trashed, trash) = TrashLayer::new().apply(op.clone());
+
+        // `a` and `a/b` could not be both files on fs.
+        trashed.object("a").write("a").await?;
+        trashed.object("a").delete().await?;
+        trashed.object("a/b").write("b").await?;
+        trashed.object("a/b").delete().await?;
+        trashed.object("c").write("c").await?;
+        trashed.object("c").blocking_delete()?;
+        assert_eq!(list_files(&op, ".trash/files/a/").await?.len(), 1);
+        assert_eq!(list_files(&op, ".trash/files/a/b/").await?.len(), 1);
+        assert_eq!(list_files(&op, ".trash/files/c/").await?.len(), 1);
+
+        trash.restore("a/b").await?;
+        assert_eq!(op.object("a/b").read().await?, b"b");
+        trashed.object("a/b").delete().await?;
+        op.object("a/").delete().await?;
+        trash.restore("a").await?;
+        assert_eq!(op.object("a").read().await?, b"a");
+        trash.restore("c").await?;
+        assert_eq!(op.object("c").read().await?, b"c");
+
+        op.batch().remove_all("/").await?;
+        Ok(())
+    }
+
+    /// BrokenService fails all reads.
+    #[derive(Debug)]
+    struct BrokenService {
+        inner: Arc<dyn Accessor>,
+    }
+
+    #[async_trait]
+    impl Accessor for BrokenService {
+        fn metadata(&self) -> AccessorMetadata {
+            self.inner.metadata()
+        }
+
+        async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+            Err(ObjectError::new(Operation::Read, args.path(), anyhow!("broken")).into())
+        }
+
+        async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+            self.inner.write(args, r).await
+        }
+
+        async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+            self.inner.stat(args).await
+        }
+
+        async fn delete(&self, args: &OpDelete) -> Result<()> {
+            self.inner.delete(args).await
+        }
+
+        async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+            self.inner.list(args).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_move_failed() -> anyhow::Result<()> {
+        let op = Operator::new(Arc::new(BrokenService {
+            inner: Arc::new(memory::Builder::default().build()?),
+        }));
+        let (trashed, _) = TrashLayer::new().apply(op.clone());
+
+        trashed.object("test").write("Hello, World!").await?;
+        trashed
+            .object("test")
+            .delete()
+            .await
+            .expect_err("delete must fail");
+        assert!(trashed.object("test").is_exist().await?);
+        assert!(list_files(&op, ".trash/info/test/").await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -43,8 +43,8 @@
 //! ## Dependencies features
 //!
 //! - `compress`: Enable object decompress read support.
-//! - `rustls`: Use rustls instead openssl for https connection
-//! - `serde`: Implement serde::{Serialize,Deserialize} for ObjectMetadata.
+//! - `rustls`: Use rustls instead openssl for https connection, required by extra root certificates of blocking client
+//! - `serde`: Implement serde::{Serialize,Deserialize} for ObjectMetadata, Operation and AclLayer.
 //!
 //! # Example
 //!
@@ -121,6 +121,7 @@ pub use io::BytesRead;
 pub use io::BytesReader;
 pub use io::BytesSink;
 pub use io::BytesStream;
+pub use io::BytesStreamer;
 pub use io::BytesWrite;
 pub use io::BytesWriter;
 
@@ -147,6 +148,14 @@ pub use multipart::ObjectPart;
 mod scheme;
 pub use scheme::Scheme;
 
+mod error;
+pub use error::ObjectError;
+pub use error::ObjectErrorKind;
+
+mod http_util;
+pub use http_util::HttpClient;
+pub use http_util::HttpClientBuilder;
+
 // Public modules, they will be accessed via `opendal::io_util::Xxxx`
 pub mod io_util;
 pub mod layers;
@@ -157,8 +166,6 @@ pub mod services;
 // Private modules, internal use only.
 //
 // Please don't export any type from this module.
-mod error;
-mod http_util;
 mod path;
 
 #[cfg(test)]
@@ -178,7 +185,7 @@ mod tests {
         assert_eq!(16, size_of::<BatchOperator>());
         assert_eq!(128, size_of::<DirEntry>());
         assert_eq!(40, size_of::<Object>());
-        assert_eq!(80, size_of::<ObjectMetadata>());
+        assert_eq!(128, size_of::<ObjectMetadata>());
         assert_eq!(1, size_of::<ObjectMode>());
         assert_eq!(64, size_of::<ObjectMultipart>());
         assert_eq!(32, size_of::<ObjectPart>());
@@ -17,9 +17,11 @@ use std::fmt::Display;
 use std::fmt::Formatter;
 use std::io::ErrorKind;
 use std::io::Result;
+use std::ops::Range;
 use std::ops::RangeBounds;
 use std::sync::Arc;
 
+use futures::future::try_join_all;
 use futures::io;
 use futures::io::Cursor;
 #[cfg(feature = "serde")]
@@ -30,11 +32,28 @@ use time::Duration;
 use time::OffsetDateTime;
 
 use crate::io::BytesRead;
+use crate::io::BytesStream;
+use crate::io_util::blocking_seekable_read;
+use crate::io_util::merge_ranges;
+use crate::io_util::parallel_read;
+#[cfg(feature = "compress")]
+use crate::io_util::seekable_decompress_read;
 use crate::io_util::seekable_read;
+use crate::io_util::split_ranges;
+#[cfg(feature = "compress")]
+use crate::io_util::BlockingDecompressReader;
+use crate::io_util::BlockingSeekableReader;
 #[cfg(feature = "compress")]
 use crate::io_util::CompressAlgorithm;
 #[cfg(feature = "compress")]
+use crate::io_util::CompressReader;
+#[cfg(feature = "compress")]
 use crate::io_util::DecompressReader;
+use crate::io_util::ParallelReader;
+#[cfg(feature = "compress")]
+use crate::io_util::SeekableCompressFormat;
+#[cfg(feature = "compress")]
+use crate::io_util::SeekableDecompressReader;
 use crate::io_util::SeekableReader;
 use crate::multipart::ObjectMultipart;
 use crate::ops::BytesRange;
@@ -44,6 +63,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpRename;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::PresignedRequest;
@@ -54,6 +74,9 @@ use crate::BlockingBytesRead;
 use crate::DirIterator;
 use crate::DirStreamer;
 
+/// Ranges closer than this gap will be merged into one read by default.
+const DEFAULT_RANGES_MERGE_GAP: u64 = 1024 * 1024;
+
 /// Handler for all object related operations.
 #[derive(Clone, Debug)]
 pub struct Object {
@@ -398,6 +421,121 @@ impl Object {
         Ok(buffer)
     }
 
+    /// Read multiple ranges of object into bytes.
+    ///
+    /// Ranges closer than 1 MiB will be merged into one read operation, and
+    /// merged operations will be sent concurrently. The returning bytes are
+    /// in the same order of input ranges.
+    ///
+    /// Use [`Object::read_ranges_with`] to specify the merge gap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.write(vec![0; 4096]).await?;
+    /// let bss = o.read_ranges(&[0..1024, 2048..4096]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_ranges(&self, ranges: &[Range<u64>]) -> Result<Vec<Vec<u8>>> {
+        self.read_ranges_with(ranges, DEFAULT_RANGES_MERGE_GAP)
+            .await
+    }
+
+    /// Read multiple ranges of object into bytes with specified merge gap.
+    ///
+    /// Ranges whose distance is not larger than `gap` will be merged into
+    /// one read operation. Set `gap` to `0` to only merge overlapping
+    /// and adjacent ranges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.write(vec![0; 4096]).await?;
+    /// let bss = o.read_ranges_with(&[0..1024, 2048..4096], 4096).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_ranges_with(&self, ranges: &[Range<u64>], gap: u64) -> Result<Vec<Vec<u8>>> {
+        let merged = merge_ranges(ranges, gap);
+        let contents = try_join_all(merged.iter().map(|r| self.range_read(r.clone()))).await?;
+
+        Ok(split_ranges(ranges, &merged, &contents))
+    }
+
+    /// Read multiple ranges of object into bytes.
+    ///
+    /// This function is the blocking version of [`Object::read_ranges`],
+    /// merged operations will be sent one by one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.blocking_write(vec![0; 4096])?;
+    /// let bss = o.blocking_read_ranges(&[0..1024, 2048..4096])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn blocking_read_ranges(&self, ranges: &[Range<u64>]) -> Result<Vec<Vec<u8>>> {
+        self.blocking_read_ranges_with(ranges, DEFAULT_RANGES_MERGE_GAP)
+    }
+
+    /// Read multiple ranges of object into bytes with specified merge gap.
+    ///
+    /// This function is the blocking version of [`Object::read_ranges_with`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.blocking_write(vec![0; 4096])?;
+    /// let bss = o.blocking_read_ranges_with(&[0..1024, 2048..4096], 4096)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn blocking_read_ranges_with(
+        &self,
+        ranges: &[Range<u64>],
+        gap: u64,
+    ) -> Result<Vec<Vec<u8>>> {
+        let merged = merge_ranges(ranges, gap);
+        let contents = merged
+            .iter()
+            .map(|r| self.blocking_range_read(r.clone()))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(split_ranges(ranges, &merged, &contents))
+    }
+
     /// Create a new reader which can read the whole object.
     ///
     /// # Examples
@@ -467,6 +605,59 @@ impl Object {
         self.acc.read(&op).await
     }
 
+    /// Create a new stream which can read the whole object.
+    ///
+    /// Services like s3 will return chunks from the response body directly,
+    /// no extra copy will happen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use futures::TryStreamExt;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.write(vec![0; 4096]).await?;
+    /// let mut s = o.stream().await?;
+    /// while let Some(bs) = s.try_next().await? {
+    ///     println!("got {} bytes", bs.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stream(&self) -> Result<impl BytesStream> {
+        self.range_stream(..).await
+    }
+
+    /// Create a new stream which can read the specified range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use futures::TryStreamExt;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.write(vec![0; 4096]).await?;
+    /// let s = o.range_stream(1024..2048).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn range_stream(&self, range: impl RangeBounds<u64>) -> Result<impl BytesStream> {
+        let op = OpRead::new(self.path(), range)?;
+        self.acc.read_stream(&op).await
+    }
+
     /// Create a new reader which can read the specified range.
     ///
     /// # Examples
@@ -501,7 +692,12 @@ impl Object {
     /// state which maintains the reader contents:
     ///
     /// - Seeking is pure in memory operation.
-    /// - Every first read after seeking will start a new read operation on backend.
+    /// - Reading after seeking inside the internal buffer or read ahead window will
+    ///   reuse the current stream.
+    /// - Otherwise, the first read after seeking will start a new read operation on backend.
+    ///
+    /// Use [`SeekableReader::with_buffer_capacity`] and [`SeekableReader::with_read_ahead`]
+    /// to adjust the windows.
     ///
     /// This operation is neither async nor returning result, because real IO happens while
     /// users call `read` or `seek`.
@@ -526,6 +722,68 @@ impl Object {
         seekable_read(self, range)
     }
 
+    /// Create a reader which fetches the whole object in chunks concurrently.
+    ///
+    /// # Notes
+    ///
+    /// - At most `concurrency` chunks will be fetched at the same time, and
+    ///   memory usage is bounded by `(concurrency + 1) * chunk_size`.
+    /// - Every chunk will be retried individually on temporary errors.
+    /// - The object's etag will be pinned across chunks, so that a concurrent
+    ///   overwrite will be detected on services that support `If-Match`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.write(vec![0; 4096]).await?;
+    /// let r = o.parallel_reader(1024, 4).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn parallel_reader(
+        &self,
+        chunk_size: u64,
+        concurrency: usize,
+    ) -> Result<ParallelReader> {
+        parallel_read(self, chunk_size, concurrency).await
+    }
+
+    /// Create a reader which implements Read and Seek inside specified range.
+    ///
+    /// This function is the blocking version of [`Object::seekable_reader`].
+    ///
+    /// # Notes
+    ///
+    /// - `SeekFrom::End` will stat the object lazily if the range size is unknown.
+    /// - Use [`BlockingSeekableReader::with_buffer_capacity`] and
+    ///   [`BlockingSeekableReader::with_read_ahead`] to adjust the windows.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// # let o = op.object("path/to/file");
+    /// let r = o.blocking_seekable_reader(1024..2048);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn blocking_seekable_reader(&self, range: impl RangeBounds<u64>) -> BlockingSeekableReader {
+        blocking_seekable_read(self, range)
+    }
+
     /// Read the whole object into a bytes with auto detected compress algorithm.
     ///
     /// If we can't find the correct algorithm, we return `Ok(None)` instead.
@@ -663,6 +921,212 @@ impl Object {
         Ok(DecompressReader::new(r, algo))
     }
 
+    /// Read the whole object into a bytes with auto detected compress algorithm.
+    ///
+    /// If we can't find the correct algorithm, we return `Ok(None)` instead.
+    ///
+    /// This is the blocking version of [`Object::decompress_read`].
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Fs)?;
+    /// let o = op.object("path/to/file.gz");
+    /// let bs = o.blocking_decompress_read()?.expect("must read succeed");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub fn blocking_decompress_read(&self) -> Result<Option<Vec<u8>>> {
+        let algo = match CompressAlgorithm::from_path(self.path()) {
+            None => return Ok(None),
+            Some(algo) => algo,
+        };
+
+        self.blocking_decompress_read_with(algo).map(Some)
+    }
+
+    /// Create a blocking reader with auto-detected compress algorithm.
+    ///
+    /// If we can't find the correct algorithm, we will return `Ok(None)`.
+    ///
+    /// This is the blocking version of [`Object::decompress_reader`].
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Fs)?;
+    /// let o = op.object("path/to/file.gz");
+    /// let r = o.blocking_decompress_reader()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub fn blocking_decompress_reader(&self) -> Result<Option<impl BlockingBytesRead>> {
+        let algo = match CompressAlgorithm::from_path(self.path()) {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let r = self.blocking_reader()?;
+
+        Ok(Some(BlockingDecompressReader::new(r, algo)))
+    }
+
+    /// Read the whole object into a bytes with specific compress algorithm.
+    ///
+    /// This is the blocking version of [`Object::decompress_read_with`].
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::io_util::CompressAlgorithm;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Fs)?;
+    /// let o = op.object("path/to/file.gz");
+    /// let bs = o.blocking_decompress_read_with(CompressAlgorithm::Gzip)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub fn blocking_decompress_read_with(&self, algo: CompressAlgorithm) -> Result<Vec<u8>> {
+        let mut r = self.blocking_decompress_reader_with(algo)?;
+        let mut bs = Vec::new();
+
+        std::io::copy(&mut r, &mut bs)?;
+
+        Ok(bs)
+    }
+
+    /// Create a blocking reader with specific compress algorithm.
+    ///
+    /// This is the blocking version of [`Object::decompress_reader_with`].
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::io_util::CompressAlgorithm;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Fs)?;
+    /// let o = op.object("path/to/file.gz");
+    /// let r = o.blocking_decompress_reader_with(CompressAlgorithm::Gzip)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub fn blocking_decompress_reader_with(
+        &self,
+        algo: CompressAlgorithm,
+    ) -> Result<impl BlockingBytesRead> {
+        let r = self.blocking_reader()?;
+
+        Ok(BlockingDecompressReader::new(r, algo))
+    }
+
+    /// Compress bytes with specific compress algorithm and write into object.
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::io_util::CompressAlgorithm;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file.gz");
+    /// o.compress_write_with(CompressAlgorithm::Gzip, vec![0; 4096])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub async fn compress_write_with(
+        &self,
+        algo: CompressAlgorithm,
+        bs: impl Into<Vec<u8>>,
+    ) -> Result<()> {
+        let r = CompressReader::new(Cursor::new(bs.into()), algo);
+        let mut bs = Cursor::new(Vec::new());
+        io::copy(r, &mut bs).await?;
+
+        self.write(bs.into_inner()).await
+    }
+
+    /// Create a reader which supports seek over the decompressed content of
+    /// object in seekable compress format.
+    ///
+    /// Only the frames that contain requested data will be read and
+    /// decompressed.
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::io_util::SeekableCompressFormat;
+    /// # use opendal::Scheme;
+    /// # use futures::AsyncSeekExt;
+    /// # use std::io::SeekFrom;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file.bgz");
+    /// let mut r = o
+    ///     .seekable_decompress_reader(SeekableCompressFormat::Bgzf)
+    ///     .await?;
+    /// r.seek(SeekFrom::Start(1024)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub async fn seekable_decompress_reader(
+        &self,
+        format: SeekableCompressFormat,
+    ) -> Result<SeekableDecompressReader> {
+        seekable_decompress_read(self, format).await
+    }
+
     /// Write bytes into object.
     ///
     /// # Notes
@@ -763,6 +1227,40 @@ impl Object {
         Ok(())
     }
 
+    /// Write data into object from a [`BytesStream`].
+    ///
+    /// Services like s3 will send chunks of the stream directly, no extra
+    /// copy will happen.
+    ///
+    /// # Notes
+    ///
+    /// - Write will make sure all bytes has been written, or an error will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// let s = stream::iter(vec![Ok(Bytes::from("Hello, ")), Ok(Bytes::from("World!"))]);
+    /// let _ = o.write_stream(13, s).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_stream(&self, size: u64, s: impl BytesStream + 'static) -> Result<()> {
+        let op = OpWrite::new(self.path(), size)?;
+        let _ = self.acc.write_stream(&op, Box::new(s)).await?;
+        Ok(())
+    }
+
     /// Write data into object from a [`BlockingBytesRead`].
     ///
     /// # Notes
@@ -853,6 +1351,63 @@ impl Object {
         self.acc.blocking_delete(op)
     }
 
+    /// Rename object to another path.
+    ///
+    /// # Notes
+    ///
+    /// - Target object will be overwritten if exists.
+    /// - Only file objects could be renamed.
+    /// - Return [`ErrorKind::Unsupported`] if underlying services don't
+    ///   support rename, check [`AccessorMetadata::can_rename`][crate::AccessorMetadata::can_rename]
+    ///   before using.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Fs)?;
+    /// op.object("test").rename("test.bak").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rename(&self, to: &str) -> Result<()> {
+        let op = &OpRename::new(self.path(), &normalize_path(to))?;
+
+        self.acc.rename(op).await
+    }
+
+    /// Rename object to another path.
+    ///
+    /// # Notes
+    ///
+    /// - Target object will be overwritten if exists.
+    /// - Only file objects could be renamed.
+    /// - Return [`ErrorKind::Unsupported`] if underlying services don't
+    ///   support rename, check [`AccessorMetadata::can_rename`][crate::AccessorMetadata::can_rename]
+    ///   before using.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Fs)?;
+    /// op.object("test").blocking_rename("test.bak")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn blocking_rename(&self, to: &str) -> Result<()> {
+        let op = &OpRename::new(self.path(), &normalize_path(to))?;
+
+        self.acc.blocking_rename(op)
+    }
+
     /// List current dir object.
     ///
     /// This function will create a new [`DirStreamer`] handle to list objects.
@@ -1131,6 +1686,8 @@ pub struct ObjectMetadata {
 
     content_length: u64,
     content_md5: Option<String>,
+    content_crc32c: Option<String>,
+    content_sha256: Option<String>,
     last_modified: Option<OffsetDateTime>,
     etag: Option<String>,
 }
@@ -1174,6 +1731,30 @@ impl ObjectMetadata {
         self
     }
 
+    /// Base64 encoded big-endian CRC32C of this object.
+    ///
+    /// Only services that store CRC32C like gcs and s3 will set this value.
+    pub fn content_crc32c(&self) -> Option<&str> {
+        self.content_crc32c.as_deref()
+    }
+
+    pub(crate) fn set_content_crc32c(&mut self, content_crc32c: &str) -> &mut Self {
+        self.content_crc32c = Some(content_crc32c.to_string());
+        self
+    }
+
+    /// Base64 encoded SHA-256 of this object.
+    ///
+    /// Only services that store SHA-256 like s3 will set this value.
+    pub fn content_sha256(&self) -> Option<&str> {
+        self.content_sha256.as_deref()
+    }
+
+    pub(crate) fn set_content_sha256(&mut self, content_sha256: &str) -> &mut Self {
+        self.content_sha256 = Some(content_sha256.to_string());
+        self
+    }
+
     /// Last modified of this object.
     ///
     /// `Last-Modified` is defined by [RFC 7232](https://httpwg.org/specs/rfc7232.html#header.last-modified)
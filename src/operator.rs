@@ -27,6 +27,8 @@ use crate::error::other;
 use crate::error::BackendError;
 use crate::io_util::BottomUpWalker;
 use crate::io_util::TopDownWalker;
+use crate::layers::ReadOnlyLayer;
+use crate::layers::SubLayer;
 use crate::services;
 use crate::Accessor;
 use crate::AccessorMetadata;
@@ -126,6 +128,13 @@ impl Operator {
     pub fn from_iter(scheme: Scheme, it: impl Iterator<Item = (String, String)>) -> Result<Self> {
         let op = match scheme {
             Scheme::Azblob => services::azblob::Backend::from_iter(it)?.into(),
+            #[cfg(feature = "services-erasure")]
+            Scheme::Erasure => {
+                return Err(other(BackendError::new(
+                    HashMap::default(),
+                    anyhow!("erasure service must be built by erasure::Builder with shards"),
+                )))
+            }
             Scheme::Fs => services::fs::Backend::from_iter(it)?.into(),
             #[cfg(feature = "services-hdfs")]
             Scheme::Hdfs => services::hdfs::Backend::from_iter(it)?.into(),
@@ -139,6 +148,24 @@ impl Operator {
             Scheme::Gcs => services::gcs::Backend::from_iter(it)?.into(),
             Scheme::S3 => services::s3::Backend::from_iter(it)?.into(),
             Scheme::Obs => services::obs::Backend::from_iter(it)?.into(),
+            Scheme::Overlay => {
+                return Err(other(BackendError::new(
+                    HashMap::default(),
+                    anyhow!("overlay service must be built by overlay::Builder with layers"),
+                )))
+            }
+            Scheme::Router => {
+                return Err(other(BackendError::new(
+                    HashMap::default(),
+                    anyhow!("router service must be built by router::Builder with mounts"),
+                )))
+            }
+            Scheme::Shard => {
+                return Err(other(BackendError::new(
+                    HashMap::default(),
+                    anyhow!("shard service must be built by shard::Builder with shards"),
+                )))
+            }
             Scheme::Custom(v) => {
                 return Err(other(BackendError::new(
                     HashMap::default(),
@@ -228,7 +255,58 @@ impl Operator {
         }
     }
 
-    fn inner(&self) -> Arc<dyn Accessor> {
+    /// Create a new operator which scopes all operations under `path`.
+    ///
+    /// The returned operator shares the same accessor and layers with this
+    /// operator, and the root in its metadata will be adjusted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// use opendal::Operator;
+    /// use opendal::Scheme;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let op = Operator::from_env(Scheme::Memory)?;
+    /// let tenant = op.sub("tenants/a/");
+    /// // Will write into `tenants/a/file` of `op`.
+    /// tenant.object("file").write("hello").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn sub(&self, path: &str) -> Self {
+        self.clone().layer(SubLayer::new(path))
+    }
+
+    /// Create a new read-only operator which shares the same accessor and
+    /// layers with this operator.
+    ///
+    /// All write, delete and multipart operations will return
+    /// `PermissionDenied` error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// use opendal::Operator;
+    /// use opendal::Scheme;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let op = Operator::from_env(Scheme::Memory)?.read_only();
+    /// assert!(op.object("file").write("hello").await.is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn read_only(&self) -> Self {
+        self.clone().layer(ReadOnlyLayer)
+    }
+
+    pub(crate) fn inner(&self) -> Arc<dyn Accessor> {
         self.accessor.clone()
     }
 
@@ -76,6 +76,14 @@ impl OpCreate {
         &self.path
     }
 
+    /// Replace the path of option.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
     /// Get object mode from option.
     pub fn mode(&self) -> ObjectMode {
         self.mode
@@ -34,4 +34,12 @@ impl OpDelete {
     pub fn path(&self) -> &str {
         &self.path
     }
+
+    /// Replace the path of option.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
 }
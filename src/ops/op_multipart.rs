@@ -51,6 +51,14 @@ impl OpCreateMultipart {
     pub fn path(&self) -> &str {
         &self.path
     }
+
+    /// Replace the path of option.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
 }
 
 /// Args for `write_multipart` operation.
@@ -90,6 +98,14 @@ impl OpWriteMultipart {
         &self.path
     }
 
+    /// Replace the path of option.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
     /// Get upload_id from option.
     pub fn upload_id(&self) -> &str {
         &self.upload_id
@@ -141,6 +157,14 @@ impl OpCompleteMultipart {
         &self.path
     }
 
+    /// Replace the path of option.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
     /// Get upload_id from option.
     pub fn upload_id(&self) -> &str {
         &self.upload_id
@@ -185,6 +209,14 @@ impl OpAbortMultipart {
         &self.path
     }
 
+    /// Replace the path of option.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
     /// Get upload_id from option.
     pub fn upload_id(&self) -> &str {
         &self.upload_id
@@ -45,6 +45,20 @@ impl OpPresign {
         }
     }
 
+    /// Replace the path of op.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.op = match self.op {
+            PresignOperation::Read(v) => PresignOperation::Read(v.with_path(path)),
+            PresignOperation::Write(v) => PresignOperation::Write(v.with_path(path)),
+            PresignOperation::WriteMultipart(v) => {
+                PresignOperation::WriteMultipart(v.with_path(path))
+            }
+        };
+        self
+    }
+
     /// Get operation from op.
     pub fn operation(&self) -> &PresignOperation {
         &self.op
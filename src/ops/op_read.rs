@@ -30,6 +30,7 @@ pub struct OpRead {
     path: String,
     offset: Option<u64>,
     size: Option<u64>,
+    if_match: Option<String>,
 }
 
 impl OpRead {
@@ -51,6 +52,7 @@ impl OpRead {
             path: path.to_string(),
             offset: br.offset(),
             size: br.size(),
+            if_match: None,
         })
     }
 
@@ -71,6 +73,7 @@ impl OpRead {
             path: path.to_string(),
             offset,
             size,
+            if_match: None,
         })
     }
 
@@ -79,6 +82,14 @@ impl OpRead {
         &self.path
     }
 
+    /// Replace the path of option.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
     /// Get offset from option.
     pub fn offset(&self) -> Option<u64> {
         self.offset
@@ -88,4 +99,18 @@ impl OpRead {
     pub fn size(&self) -> Option<u64> {
         self.size
     }
+
+    /// Only read the object if its etag matches the given one.
+    ///
+    /// Services that support conditional read will send it as `If-Match`
+    /// header, others will ignore it.
+    pub fn with_if_match(mut self, etag: &str) -> Self {
+        self.if_match = Some(etag.to_string());
+        self
+    }
+
+    /// Get if_match from option.
+    pub fn if_match(&self) -> Option<&str> {
+        self.if_match.as_deref()
+    }
 }
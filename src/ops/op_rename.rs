@@ -0,0 +1,62 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Result;
+
+use anyhow::anyhow;
+
+use crate::error::other;
+use crate::error::ObjectError;
+use crate::ops::Operation;
+
+/// Args for `rename` operation.
+///
+/// The paths must be normalized.
+#[derive(Debug, Clone, Default)]
+pub struct OpRename {
+    from: String,
+    to: String,
+}
+
+impl OpRename {
+    /// Create a new `OpRename`.
+    ///
+    /// If input paths are not file paths, an error will be returned.
+    pub fn new(from: &str, to: &str) -> Result<Self> {
+        for path in [from, to] {
+            if path.ends_with('/') {
+                return Err(other(ObjectError::new(
+                    Operation::Rename,
+                    path,
+                    anyhow!("Is a directory"),
+                )));
+            }
+        }
+
+        Ok(Self {
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+    }
+
+    /// Get source path from option.
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// Get target path from option.
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+}
@@ -27,6 +27,9 @@ use crate::ops::Operation;
 pub struct OpWrite {
     path: String,
     size: u64,
+    content_md5: Option<String>,
+    content_crc32c: Option<String>,
+    content_sha256: Option<String>,
 }
 
 impl OpWrite {
@@ -45,6 +48,7 @@ impl OpWrite {
         Ok(Self {
             path: path.to_string(),
             size,
+            ..Default::default()
         })
     }
 
@@ -53,8 +57,58 @@ impl OpWrite {
         &self.path
     }
 
+    /// Replace the path of option.
+    ///
+    /// The path must be normalized.
+    pub(crate) fn with_path(mut self, path: &str) -> Self {
+        self.path = path.to_string();
+        self
+    }
+
     /// Get size from option.
     pub fn size(&self) -> u64 {
         self.size
     }
+
+    /// Set the base64 encoded MD5 of content.
+    ///
+    /// Services that support it will send it as `Content-MD5` header so that
+    /// corrupted content will be rejected, others will ignore it.
+    pub fn with_content_md5(mut self, v: &str) -> Self {
+        self.content_md5 = Some(v.to_string());
+        self
+    }
+
+    /// Get content_md5 from option.
+    pub fn content_md5(&self) -> Option<&str> {
+        self.content_md5.as_deref()
+    }
+
+    /// Set the base64 encoded big-endian CRC32C of content.
+    ///
+    /// Services that support it will send it along with content, others
+    /// will ignore it.
+    pub fn with_content_crc32c(mut self, v: &str) -> Self {
+        self.content_crc32c = Some(v.to_string());
+        self
+    }
+
+    /// Get content_crc32c from option.
+    pub fn content_crc32c(&self) -> Option<&str> {
+        self.content_crc32c.as_deref()
+    }
+
+    /// Set the base64 encoded SHA-256 of content.
+    ///
+    /// Services that support it will send it along with content, others
+    /// will ignore it.
+    pub fn with_content_sha256(mut self, v: &str) -> Self {
+        self.content_sha256 = Some(v.to_string());
+        self
+    }
+
+    /// Get content_sha256 from option.
+    pub fn content_sha256(&self) -> Option<&str> {
+        self.content_sha256.as_deref()
+    }
 }
@@ -15,8 +15,15 @@
 use std::fmt::Display;
 use std::fmt::Formatter;
 
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
 /// Operation is the name for APIs in `Accessor`.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 #[non_exhaustive]
 pub enum Operation {
     /// Operation for [`crate::Accessor::metadata`]
@@ -33,6 +40,8 @@ pub enum Operation {
     Delete,
     /// Operation for [`crate::Accessor::list`]
     List,
+    /// Operation for [`crate::Accessor::rename`]
+    Rename,
     /// Operation for [`crate::Accessor::presign`]
     Presign,
     /// Operation for [`crate::Accessor::create_multipart`]
@@ -55,6 +64,8 @@ pub enum Operation {
     BlockingDelete,
     /// Operation for [`crate::Accessor::blocking_list`]
     BlockingList,
+    /// Operation for [`crate::Accessor::blocking_rename`]
+    BlockingRename,
 }
 
 impl Operation {
@@ -62,6 +73,21 @@ impl Operation {
     pub fn into_static(self) -> &'static str {
         self.into()
     }
+
+    /// Convert blocking operation into its async one, other operations
+    /// will be returned as is.
+    pub(crate) fn into_async(self) -> Self {
+        match self {
+            Operation::BlockingCreate => Operation::Create,
+            Operation::BlockingRead => Operation::Read,
+            Operation::BlockingWrite => Operation::Write,
+            Operation::BlockingStat => Operation::Stat,
+            Operation::BlockingDelete => Operation::Delete,
+            Operation::BlockingList => Operation::List,
+            Operation::BlockingRename => Operation::Rename,
+            v => v,
+        }
+    }
 }
 
 impl Default for Operation {
@@ -81,6 +107,7 @@ impl Display for Operation {
             Operation::Stat => write!(f, "stat"),
             Operation::Delete => write!(f, "delete"),
             Operation::List => write!(f, "list"),
+            Operation::Rename => write!(f, "rename"),
 
             Operation::Presign => write!(f, "presign"),
 
@@ -95,6 +122,7 @@ impl Display for Operation {
             Operation::BlockingStat => write!(f, "blocking_stat"),
             Operation::BlockingDelete => write!(f, "blocking_delete"),
             Operation::BlockingList => write!(f, "blocking_list"),
+            Operation::BlockingRename => write!(f, "blocking_rename"),
         }
     }
 }
@@ -109,6 +137,7 @@ impl From<Operation> for &'static str {
             Operation::Stat => "stat",
             Operation::Delete => "delete",
             Operation::List => "list",
+            Operation::Rename => "rename",
             Operation::Presign => "presign",
             Operation::CreateMultipart => "create_multipart",
             Operation::WriteMultipart => "write_multipart",
@@ -120,6 +149,7 @@ impl From<Operation> for &'static str {
             Operation::BlockingStat => "blocking_stat",
             Operation::BlockingDelete => "blocking_delete",
             Operation::BlockingList => "blocking_list",
+            Operation::BlockingRename => "blocking_rename",
         }
     }
 }
@@ -174,6 +174,29 @@ pub fn get_basename(path: &str) -> &str {
     }
 }
 
+/// Check if path contains `..` segment, which could escape from its parent
+/// on services like fs.
+pub(crate) fn has_parent_segment(path: &str) -> bool {
+    path.split('/').any(|v| v == "..")
+}
+
+/// Get parent from path.
+///
+/// - `abc/def` => `abc/`
+/// - `abc/def/` => `abc/`
+/// - `abc` => `/`
+/// - `/` => `/`
+pub fn get_parent(path: &str) -> &str {
+    if path == "/" {
+        return "/";
+    }
+
+    match path.trim_end_matches('/').rfind('/') {
+        Some(idx) => &path[..idx + 1],
+        None => "/",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,4 +307,36 @@ mod tests {
             assert_eq!(actual, expect, "{}", name)
         }
     }
+
+    #[test]
+    fn test_get_parent() {
+        let cases = vec![
+            ("file path", "abc", "/"),
+            ("dir path", "abc/", "/"),
+            ("root path", "/", "/"),
+            ("file in dir", "abc/def", "abc/"),
+            ("dir in dir", "abc/def/", "abc/"),
+            ("nested file", "abc/def/ghi", "abc/def/"),
+        ];
+
+        for (name, input, expect) in cases {
+            assert_eq!(get_parent(input), expect, "{}", name)
+        }
+    }
+
+    #[test]
+    fn test_has_parent_segment() {
+        let cases = vec![
+            ("normal path", "abc/def", false),
+            ("dots in name", "abc/..def/x..", false),
+            ("parent dir", "../abc", true),
+            ("nested parent dir", "abc/../def", true),
+            ("trailing parent dir", "abc/..", true),
+            ("parent dir path", "abc/../", true),
+        ];
+
+        for (name, input, expect) in cases {
+            assert_eq!(has_parent_segment(input), expect, "{}", name)
+        }
+    }
 }
@@ -29,6 +29,9 @@ use std::str::FromStr;
 pub enum Scheme {
     /// [azblob][crate::services::azblob]: Azure Storage Blob services.
     Azblob,
+    /// [erasure][crate::services::erasure]: Erasure coded storage across services.
+    #[cfg(feature = "services-erasure")]
+    Erasure,
     /// [fs][crate::services::fs]: POSIX alike file system.
     Fs,
     /// [gcs][crate::services::gcs]: Google Cloud Storage backend.
@@ -50,8 +53,14 @@ pub enum Scheme {
     Memory,
     /// [obs][crate::services::obs]: Huawei Cloud OBS services.
     Obs,
+    /// [overlay][crate::services::overlay]: Overlay a writable service on top of a read-only service.
+    Overlay,
+    /// [router][crate::services::router]: Dispatch paths to mounted services.
+    Router,
     /// [s3][crate::services::s3]: AWS S3 alike services.
     S3,
+    /// [shard][crate::services::shard]: Spread paths across services by hashing.
+    Shard,
     /// Custom that allow users to implement services outside of OpenDAL.
     ///
     /// # NOTE
@@ -78,6 +87,8 @@ impl Display for Scheme {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Scheme::Azblob => write!(f, "azblob"),
+            #[cfg(feature = "services-erasure")]
+            Scheme::Erasure => write!(f, "erasure"),
             Scheme::Fs => write!(f, "fs"),
             #[cfg(feature = "services-hdfs")]
             Scheme::Hdfs => write!(f, "hdfs"),
@@ -90,7 +101,10 @@ impl Display for Scheme {
             Scheme::Ipmfs => write!(f, "ipmfs"),
             Scheme::Memory => write!(f, "memory"),
             Scheme::Obs => write!(f, "obs"),
+            Scheme::Overlay => write!(f, "overlay"),
+            Scheme::Router => write!(f, "router"),
             Scheme::S3 => write!(f, "s3"),
+            Scheme::Shard => write!(f, "shard"),
             Scheme::Custom(v) => write!(f, "{v}"),
         }
     }
@@ -103,6 +117,8 @@ impl FromStr for Scheme {
         let s = s.to_lowercase();
         match s.as_str() {
             "azblob" => Ok(Scheme::Azblob),
+            #[cfg(feature = "services-erasure")]
+            "erasure" => Ok(Scheme::Erasure),
             "fs" => Ok(Scheme::Fs),
             "gcs" => Ok(Scheme::Gcs),
             #[cfg(feature = "services-hdfs")]
@@ -115,7 +131,10 @@ impl FromStr for Scheme {
             "ipmfs" => Ok(Scheme::Ipmfs),
             "memory" => Ok(Scheme::Memory),
             "obs" => Ok(Scheme::Obs),
+            "overlay" => Ok(Scheme::Overlay),
+            "router" => Ok(Scheme::Router),
             "s3" => Ok(Scheme::S3),
+            "shard" => Ok(Scheme::Shard),
             _ => Ok(Scheme::Custom(Box::leak(s.into_boxed_str()))),
         }
     }
@@ -125,6 +144,8 @@ impl From<Scheme> for &'static str {
     fn from(v: Scheme) -> Self {
         match v {
             Scheme::Azblob => "azblob",
+            #[cfg(feature = "services-erasure")]
+            Scheme::Erasure => "erasure",
             Scheme::Fs => "fs",
             Scheme::Gcs => "gcs",
             #[cfg(feature = "services-hdfs")]
@@ -137,7 +158,10 @@ impl From<Scheme> for &'static str {
             Scheme::Ipmfs => "ipmfs",
             Scheme::Memory => "memory",
             Scheme::Obs => "obs",
+            Scheme::Overlay => "overlay",
+            Scheme::Router => "router",
             Scheme::S3 => "s3",
+            Scheme::Shard => "shard",
             Scheme::Custom(v) => v,
         }
     }
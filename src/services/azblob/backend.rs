@@ -38,17 +38,21 @@ use crate::accessor::AccessorMetadata;
 use crate::error::other;
 use crate::error::BackendError;
 use crate::error::ObjectError;
+use crate::http_util::insert_header;
 use crate::http_util::new_request_build_error;
 use crate::http_util::new_request_send_error;
 use crate::http_util::new_request_sign_error;
 use crate::http_util::new_response_consume_error;
 use crate::http_util::parse_content_length;
+use crate::http_util::parse_content_md5;
 use crate::http_util::parse_error_response;
 use crate::http_util::parse_etag;
 use crate::http_util::parse_last_modified;
 use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::object::ObjectMetadata;
 use crate::ops::BytesRange;
 use crate::ops::OpCreate;
@@ -62,6 +66,7 @@ use crate::path::build_abs_path;
 use crate::path::normalize_root;
 use crate::Accessor;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirStreamer;
 use crate::ObjectMode;
 use crate::Scheme;
@@ -76,6 +81,8 @@ pub struct Builder {
     endpoint: Option<String>,
     account_name: Option<String>,
     account_key: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -155,6 +162,15 @@ impl Builder {
         self
     }
 
+    /// Set http client used by this service.
+    ///
+    /// Default: a new [`HttpClient`](crate::HttpClient) with default configs.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+
+        self
+    }
+
     /// Consume builder to build an azblob backend.
     pub fn build(&mut self) -> Result<Backend> {
         info!("backend build started: {:?}", &self);
@@ -186,7 +202,7 @@ impl Builder {
             ("endpoint".to_string(), endpoint.to_string()),
         ]);
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         let mut signer_builder = Signer::builder();
         if let (Some(name), Some(key)) = (&self.account_name, &self.account_key) {
@@ -223,8 +239,12 @@ pub struct Backend {
 impl Backend {
     pub(crate) fn from_iter(it: impl Iterator<Item = (String, String)>) -> Result<Self> {
         let mut builder = Builder::default();
+        let mut http_client = HttpClient::builder();
 
         for (k, v) in it {
+            if http_client.parse_option(&k, &v)? {
+                continue;
+            }
             let v = v.as_str();
             match k.as_ref() {
                 "root" => builder.root(v),
@@ -236,6 +256,7 @@ impl Backend {
             };
         }
 
+        builder.http_client(http_client.build()?);
         builder.build()
     }
 }
@@ -288,14 +309,22 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(args).await?;
+
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
         let p = build_abs_path(&self.root, args.path());
 
-        let resp = self.get_blob(&p, args.offset(), args.size()).await?;
+        let resp = self
+            .get_blob(&p, args.offset(), args.size(), args.if_match())
+            .await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, args.path(), er);
@@ -305,9 +334,20 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(args, Box::new(into_stream(r, 8 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
         let p = build_abs_path(&self.root, args.path());
 
-        let mut req = self.put_blob_request(&p, Some(args.size()), AsyncBody::Reader(r))?;
+        let mut req = self.put_blob_request(&p, Some(args.size()), AsyncBody::Stream(s))?;
+
+        // Set md5 of content so that service will reject corrupted content.
+        if let Some(v) = args.content_md5() {
+            insert_header(&mut req, "content-md5", v)
+                .map_err(|e| new_request_build_error(Operation::Write, &p, e))?;
+        }
 
         self.signer
             .sign(&mut req)
@@ -365,7 +405,13 @@ impl Accessor for Backend {
                     .map_err(|e| other(ObjectError::new(Operation::Stat, &p, e)))?
                 {
                     m.set_etag(v);
-                    m.set_content_md5(v.trim_matches('"'));
+                }
+
+                // ETag of azblob is not the md5 of content.
+                if let Some(v) = parse_content_md5(resp.headers())
+                    .map_err(|e| other(ObjectError::new(Operation::Stat, &p, e)))?
+                {
+                    m.set_content_md5(v);
                 }
 
                 if let Some(v) = parse_last_modified(resp.headers())
@@ -430,6 +476,7 @@ impl Backend {
         path: &str,
         offset: Option<u64>,
         size: Option<u64>,
+        if_match: Option<&str>,
     ) -> Result<Response<AsyncBody>> {
         let url = format!(
             "{}/{}/{}",
@@ -447,6 +494,10 @@ impl Backend {
             );
         }
 
+        if let Some(etag) = if_match {
+            req = req.header(http::header::IF_MATCH, etag);
+        }
+
         let mut req = req
             .body(AsyncBody::Empty)
             .map_err(|e| new_request_build_error(Operation::Read, path, e))?;
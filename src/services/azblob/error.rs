@@ -13,30 +13,138 @@
 // limitations under the License.
 
 use std::io::Error;
-use std::io::ErrorKind;
 
 use anyhow::anyhow;
-use http::StatusCode;
+use bytes::Buf;
+use quick_xml::de;
+use serde::Deserialize;
 
 use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::http_util::parse_error_kind;
 use crate::http_util::ErrorResponse;
 use crate::ops::Operation;
 
-/// Parse error response into io::Error.
-///
-/// # TODO
+/// AzblobError is the error returned by azure blob service.
 ///
-/// In the future, we may have our own error struct.
+/// ref: https://learn.microsoft.com/en-us/rest/api/storageservices/status-and-error-codes2
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct AzblobError {
+    code: String,
+    message: String,
+}
+
+/// Parse error response into io::Error.
 pub fn parse_error(op: Operation, path: &str, er: ErrorResponse) -> Error {
-    let kind = match er.status_code() {
-        StatusCode::NOT_FOUND => ErrorKind::NotFound,
-        StatusCode::FORBIDDEN => ErrorKind::PermissionDenied,
-        StatusCode::INTERNAL_SERVER_ERROR
-        | StatusCode::BAD_GATEWAY
-        | StatusCode::SERVICE_UNAVAILABLE
-        | StatusCode::GATEWAY_TIMEOUT => ErrorKind::Interrupted,
-        _ => ErrorKind::Other,
+    let mut err = ObjectError::new(op, path, anyhow!("{er}"))
+        .with_kind(parse_error_kind(er.status_code()))
+        .with_context("service", "azblob");
+
+    if let Some(v) = er
+        .headers()
+        .get("x-ms-request-id")
+        .and_then(|v| v.to_str().ok())
+    {
+        err = err.with_context("request_id", v);
+    }
+
+    // Azblob will return error code in `x-ms-error-code` for HEAD requests
+    // which don't have response body.
+    let mut azblob_err: AzblobError = de::from_reader(er.body().reader()).unwrap_or_default();
+    if azblob_err.code.is_empty() {
+        if let Some(v) = er
+            .headers()
+            .get("x-ms-error-code")
+            .and_then(|v| v.to_str().ok())
+        {
+            azblob_err.code = v.to_string();
+        }
+    }
+
+    err = match azblob_err.code.as_str() {
+        "BlobNotFound" | "ContainerNotFound" => err.with_kind(ObjectErrorKind::ObjectNotFound),
+        "BlobAlreadyExists" | "ContainerAlreadyExists" => {
+            err.with_kind(ObjectErrorKind::ObjectAlreadyExists)
+        }
+        "AuthenticationFailed" | "AuthorizationFailure" | "AuthorizationPermissionMismatch" => {
+            err.with_kind(ObjectErrorKind::PermissionDenied)
+        }
+        "ConditionNotMet" => err.with_kind(ObjectErrorKind::ConditionNotMatch),
+        "ServerBusy" => err.with_kind(ObjectErrorKind::RateLimited),
+        "InternalError" | "OperationTimedOut" => err.with_kind(ObjectErrorKind::ServiceUnavailable),
+        _ => err,
     };
+    if !azblob_err.code.is_empty() || !azblob_err.message.is_empty() {
+        let request_id = err.request_id().map(|v| v.to_string());
+        err = err.with_source(er.describe(
+            &azblob_err.code,
+            &azblob_err.message,
+            request_id.as_deref(),
+        ));
+    }
+    if !azblob_err.code.is_empty() {
+        err = err.with_context("code", azblob_err.code);
+    }
+    if !azblob_err.message.is_empty() {
+        err = err.with_context("message", azblob_err.message);
+    }
+
+    err.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Response;
+    use http::StatusCode;
+
+    use super::*;
+    use crate::http_util::parse_error_response;
+    use crate::http_util::AsyncBody;
+
+    #[tokio::test]
+    async fn test_parse_error() {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?>
+<Error>
+  <Code>BlobAlreadyExists</Code>
+  <Message>The specified blob already exists.</Message>
+</Error>"#;
+        let resp = Response::builder()
+            .status(StatusCode::CONFLICT)
+            .header("x-ms-request-id", "c4dd8e3a-b01e-0007-4e4a-9c4aa5000000")
+            .body(AsyncBody::Bytes(body.into()))
+            .expect("must build");
+        let er = parse_error_response(resp).await.expect("must parse");
+
+        let err = parse_error(Operation::Write, "a", er);
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+
+        let oe = ObjectError::downcast(&err).expect("must be object error");
+        assert_eq!(oe.kind(), ObjectErrorKind::ObjectAlreadyExists);
+        assert_eq!(oe.service_code(), Some("BlobAlreadyExists"));
+        assert_eq!(
+            oe.service_message(),
+            Some("The specified blob already exists.")
+        );
+        assert_eq!(
+            oe.request_id(),
+            Some("c4dd8e3a-b01e-0007-4e4a-9c4aa5000000")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_from_header() {
+        let resp = Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header("x-ms-error-code", "ServerBusy")
+            .body(AsyncBody::Empty)
+            .expect("must build");
+        let er = parse_error_response(resp).await.expect("must parse");
 
-    Error::new(kind, ObjectError::new(op, path, anyhow!("{er}")))
+        let err = parse_error(Operation::Stat, "a", er);
+        let oe = ObjectError::downcast(&err).expect("must be object error");
+        assert_eq!(oe.kind(), ObjectErrorKind::RateLimited);
+        assert!(oe.is_temporary());
+        assert_eq!(oe.service_code(), Some("ServerBusy"));
+    }
 }
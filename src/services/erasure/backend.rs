@@ -0,0 +1,648 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::io::Cursor;
+use futures::stream;
+use futures::AsyncReadExt;
+use futures::TryStreamExt;
+use log::debug;
+use log::info;
+use reed_solomon_erasure::galois_8::ReedSolomon;
+use time::OffsetDateTime;
+
+use crate::accessor::AccessorCapability;
+use crate::error::other;
+use crate::error::BackendError;
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::ops::OpCreate;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::Operation;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BytesReader;
+use crate::DirEntry;
+use crate::DirStreamer;
+use crate::ObjectMetadata;
+use crate::ObjectMode;
+use crate::Operator;
+use crate::Scheme;
+
+/// Every shard starts with a header of:
+///
+/// - length of object in little endian u64.
+/// - generation of the write in little endian u64.
+/// - md5 checksum of the shard content.
+const HEADER_SIZE: usize = 32;
+
+/// ShardHeader identifies which write a shard belongs to.
+///
+/// Shards are only used together if their headers are the same, so that
+/// shards left by a partially failed write will not be mixed up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ShardHeader {
+    length: u64,
+    generation: u64,
+}
+
+impl ShardHeader {
+    /// Create a header for a new write, generation is the current time so
+    /// that newer writes have larger generations.
+    fn new(length: u64) -> Self {
+        ShardHeader {
+            length,
+            generation: OffsetDateTime::now_utc().unix_timestamp_nanos() as u64,
+        }
+    }
+
+    /// Prepend header and checksum to shard content.
+    fn encode(&self, shard: &[u8]) -> Vec<u8> {
+        let mut v = Vec::with_capacity(HEADER_SIZE + shard.len());
+        v.extend_from_slice(&self.length.to_le_bytes());
+        v.extend_from_slice(&self.generation.to_le_bytes());
+        v.extend_from_slice(&md5::compute(shard).0);
+        v.extend_from_slice(shard);
+        v
+    }
+
+    /// Parse header and checksum, returns `None` if it's too short.
+    fn decode(bs: &[u8]) -> Option<(ShardHeader, [u8; 16])> {
+        if bs.len() < HEADER_SIZE {
+            return None;
+        }
+
+        let u64_at =
+            |idx: usize| u64::from_le_bytes(bs[idx..idx + 8].try_into().expect("must be 8 bytes"));
+        let header = ShardHeader {
+            length: u64_at(0),
+            generation: u64_at(8),
+        };
+        Some((header, bs[16..32].try_into().expect("must be 16 bytes")))
+    }
+}
+
+/// Builder for erasure service.
+#[derive(Default, Debug)]
+pub struct Builder {
+    operators: Vec<Operator>,
+    parity_shards: usize,
+}
+
+impl Builder {
+    /// Add an operator to store shards.
+    ///
+    /// Shards are assigned to operators in the order they are added.
+    pub fn operator(&mut self, op: Operator) -> &mut Self {
+        self.operators.push(op);
+
+        self
+    }
+
+    /// Set the count of parity shards.
+    ///
+    /// All other operators will be used to store data shards.
+    pub fn parity_shards(&mut self, parity_shards: usize) -> &mut Self {
+        self.parity_shards = parity_shards;
+
+        self
+    }
+
+    /// Consume builder to build an erasure service.
+    pub fn build(&mut self) -> Result<Backend> {
+        info!("backend build started: {:?}", &self);
+
+        let total = self.operators.len();
+        if self.parity_shards == 0 || self.parity_shards >= total {
+            return Err(other(BackendError::new(
+                HashMap::from([
+                    ("operators".to_string(), total.to_string()),
+                    ("parity_shards".to_string(), self.parity_shards.to_string()),
+                ]),
+                anyhow!("parity shards must be in [1, operators)"),
+            )));
+        }
+
+        let data_shards = total - self.parity_shards;
+        let rs = ReedSolomon::new(data_shards, self.parity_shards).map_err(|err| {
+            other(BackendError::new(
+                HashMap::from([
+                    ("data_shards".to_string(), data_shards.to_string()),
+                    ("parity_shards".to_string(), self.parity_shards.to_string()),
+                ]),
+                anyhow!("build reed solomon codec: {err}"),
+            ))
+        })?;
+
+        let operators = self.operators.drain(..).map(|op| op.inner()).collect();
+
+        info!("backend build finished: {:?}", &self);
+        Ok(Backend {
+            operators: Arc::new(operators),
+            data_shards,
+            rs: Arc::new(rs),
+        })
+    }
+}
+
+/// Backend for erasure service.
+#[derive(Debug, Clone)]
+pub struct Backend {
+    operators: Arc<Vec<Arc<dyn Accessor>>>,
+    data_shards: usize,
+    rs: Arc<ReedSolomon>,
+}
+
+impl Backend {
+    /// Size of every shard (without header) for object in given length.
+    fn shard_size(&self, length: u64) -> usize {
+        // Reed-Solomon doesn't accept empty shards.
+        (length as usize).div_ceil(self.data_shards).max(1)
+    }
+
+    /// Split content into data and parity shards.
+    fn encode(&self, op: Operation, path: &str, mut bs: Vec<u8>) -> Result<Vec<Vec<u8>>> {
+        let header = ShardHeader::new(bs.len() as u64);
+        let shard_size = self.shard_size(header.length);
+        bs.resize(shard_size * self.data_shards, 0);
+
+        let mut shards: Vec<Vec<u8>> = bs.chunks(shard_size).map(|v| v.to_vec()).collect();
+        shards.resize(self.operators.len(), vec![0; shard_size]);
+        self.rs
+            .encode(&mut shards)
+            .map_err(|err| new_codec_error(op, path, err))?;
+
+        Ok(shards.iter().map(|shard| header.encode(shard)).collect())
+    }
+
+    /// Decode shard, returns `None` if it's malformed or corrupted.
+    fn decode(&self, mut bs: Vec<u8>) -> Option<(ShardHeader, Vec<u8>)> {
+        let (header, checksum) = ShardHeader::decode(&bs)?;
+        let shard = bs.split_off(HEADER_SIZE);
+        if shard.len() != self.shard_size(header.length) || md5::compute(&shard).0 != checksum {
+            return None;
+        }
+        Some((header, shard))
+    }
+
+    /// Choose the header that most shards agree on, newer generation wins
+    /// if there is a tie.
+    ///
+    /// Returns error if there are not enough shards to reconstruct.
+    fn choose_header(
+        &self,
+        op: Operation,
+        path: &str,
+        headers: impl Iterator<Item = ShardHeader>,
+    ) -> Result<ShardHeader> {
+        let mut counts: HashMap<ShardHeader, usize> = HashMap::new();
+        for header in headers {
+            *counts.entry(header).or_default() += 1;
+        }
+
+        let (header, available) = counts
+            .into_iter()
+            .max_by_key(|(header, count)| (*count, header.generation))
+            .unwrap_or((ShardHeader::new(0), 0));
+        if available < self.data_shards {
+            return Err(ObjectError::new(
+                op,
+                path,
+                anyhow!(
+                    "not enough shards to reconstruct: available {available}, required {}",
+                    self.data_shards
+                ),
+            )
+            .with_context("available", available.to_string())
+            .into());
+        }
+
+        Ok(header)
+    }
+
+    /// Write shards into operators, all shards must be written.
+    async fn write_shards(&self, path: &str, shards: Vec<(usize, Vec<u8>)>) -> Result<()> {
+        let futs = shards.into_iter().map(|(idx, shard)| async move {
+            let args = OpWrite::new(path, shard.len() as u64)?;
+            let r: BytesReader = Box::new(Cursor::new(shard));
+            self.operators[idx].write(&args, r).await
+        });
+
+        for res in join_all(futs).await {
+            res?;
+        }
+        Ok(())
+    }
+
+    /// Read all shards of object, returns shards and their header.
+    ///
+    /// Missing, malformed or mismatched shards will be `None`.
+    async fn read_shards(
+        &self,
+        op: Operation,
+        path: &str,
+    ) -> Result<(Vec<Option<Vec<u8>>>, ShardHeader)> {
+        let futs = self.operators.iter().map(|acc| async move {
+            let mut r = acc.read(&OpRead::new(path, ..)?).await?;
+            let mut bs = Vec::new();
+            r.read_to_end(&mut bs).await?;
+            Ok::<_, std::io::Error>(bs)
+        });
+        let results = join_all(futs).await;
+
+        let mut not_found = 0;
+        let mut shards = Vec::with_capacity(results.len());
+        for res in results {
+            match res {
+                Ok(bs) => {
+                    let shard = self.decode(bs);
+                    if shard.is_none() {
+                        debug!("shard of {path} is malformed");
+                    }
+                    shards.push(shard);
+                }
+                Err(err) => {
+                    if err.kind() == ErrorKind::NotFound {
+                        not_found += 1;
+                    } else {
+                        debug!("shard of {path} read failed: {err:?}");
+                    }
+                    shards.push(None);
+                }
+            }
+        }
+
+        if not_found == self.operators.len() {
+            return Err(new_not_found_error(op, path));
+        }
+
+        let header = self.choose_header(
+            op,
+            path,
+            shards.iter().filter_map(|v| v.as_ref().map(|(h, _)| *h)),
+        )?;
+        let shards = shards
+            .into_iter()
+            .map(|v| match v {
+                Some((h, shard)) if h == header => Some(shard),
+                Some(_) => {
+                    debug!("shard of {path} is mismatched");
+                    None
+                }
+                None => None,
+            })
+            .collect();
+
+        Ok((shards, header))
+    }
+
+    /// Rebuild missing, malformed or mismatched shards of object.
+    ///
+    /// Returns the count of repaired shards.
+    pub async fn repair(&self, path: &str) -> Result<usize> {
+        let (mut shards, header) = self.read_shards(Operation::Write, path).await?;
+
+        let missing: Vec<usize> = shards
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, v)| v.is_none().then_some(idx))
+            .collect();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+
+        self.rs
+            .reconstruct(&mut shards)
+            .map_err(|err| new_codec_error(Operation::Write, path, err))?;
+
+        let shards = missing
+            .iter()
+            .map(|&idx| {
+                let shard = shards[idx].take().expect("shard must be reconstructed");
+                (idx, header.encode(&shard))
+            })
+            .collect();
+        self.write_shards(path, shards).await?;
+
+        Ok(missing.len())
+    }
+}
+
+fn new_codec_error(op: Operation, path: &str, err: reed_solomon_erasure::Error) -> std::io::Error {
+    ObjectError::new(op, path, anyhow!("reed solomon: {err}")).into()
+}
+
+fn new_not_found_error(op: Operation, path: &str) -> std::io::Error {
+    ObjectError::new(op, path, anyhow!("object not found"))
+        .with_kind(ObjectErrorKind::ObjectNotFound)
+        .into()
+}
+
+#[async_trait]
+impl Accessor for Backend {
+    fn metadata(&self) -> AccessorMetadata {
+        let mut am = AccessorMetadata::default();
+        am.set_scheme(Scheme::Erasure)
+            .set_root("/")
+            .set_name("erasure")
+            .set_capabilities(
+                AccessorCapability::Read | AccessorCapability::Write | AccessorCapability::List,
+            );
+
+        am
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        if args.mode() == ObjectMode::FILE {
+            let shards = self.encode(Operation::Create, args.path(), Vec::new())?;
+            return self
+                .write_shards(args.path(), shards.into_iter().enumerate().collect())
+                .await;
+        }
+
+        for res in join_all(self.operators.iter().map(|acc| acc.create(args))).await {
+            res?;
+        }
+        Ok(())
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let (mut shards, header) = self.read_shards(Operation::Read, args.path()).await?;
+        let length = header.length;
+        self.rs
+            .reconstruct_data(&mut shards)
+            .map_err(|err| new_codec_error(Operation::Read, args.path(), err))?;
+
+        let mut bs: Vec<u8> = shards
+            .into_iter()
+            .take(self.data_shards)
+            .flat_map(|v| v.expect("data shard must be reconstructed"))
+            .collect();
+        bs.truncate(length as usize);
+
+        let offset = args.offset().unwrap_or_default().min(length) as usize;
+        let size = args
+            .size()
+            .map_or(bs.len() - offset, |v| (v as usize).min(bs.len() - offset));
+        bs.truncate(offset + size);
+        bs.drain(..offset);
+
+        Ok(Box::new(Cursor::new(bs)))
+    }
+
+    async fn write(&self, args: &OpWrite, mut r: BytesReader) -> Result<u64> {
+        let mut bs = Vec::with_capacity(args.size() as usize);
+        r.read_to_end(&mut bs).await?;
+        let length = bs.len() as u64;
+
+        let shards = self.encode(Operation::Write, args.path(), bs)?;
+        self.write_shards(args.path(), shards.into_iter().enumerate().collect())
+            .await?;
+
+        Ok(length)
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        let path = args.path();
+
+        if path.ends_with('/') {
+            let mut last_err = None;
+            for acc in self.operators.iter() {
+                match acc.stat(args).await {
+                    Ok(meta) => return Ok(meta),
+                    Err(err) => last_err = Some(err),
+                }
+            }
+            return Err(last_err.expect("operators must not be empty"));
+        }
+
+        // Only headers are read, so checksums can't be verified here.
+        let futs = self.operators.iter().map(|acc| async move {
+            let mut r = acc.read(&OpRead::new(path, ..HEADER_SIZE as u64)?).await?;
+            let mut bs = Vec::with_capacity(HEADER_SIZE);
+            r.read_to_end(&mut bs).await?;
+            Ok::<_, std::io::Error>(bs)
+        });
+
+        let mut not_found = 0;
+        let mut headers = Vec::with_capacity(self.operators.len());
+        for res in join_all(futs).await {
+            match res {
+                Ok(bs) => match ShardHeader::decode(&bs) {
+                    Some((header, _)) => headers.push(header),
+                    None => debug!("shard of {path} is malformed"),
+                },
+                Err(err) if err.kind() == ErrorKind::NotFound => not_found += 1,
+                Err(err) => debug!("shard of {path} read failed: {err:?}"),
+            }
+        }
+
+        if not_found == self.operators.len() {
+            return Err(new_not_found_error(Operation::Stat, path));
+        }
+
+        let header = self.choose_header(Operation::Stat, path, headers.into_iter())?;
+        let mut meta = ObjectMetadata::default();
+        meta.set_mode(ObjectMode::FILE)
+            .set_content_length(header.length);
+        Ok(meta)
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        for res in join_all(self.operators.iter().map(|acc| acc.delete(args))).await {
+            res?;
+        }
+        Ok(())
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let futs = self.operators.iter().map(|acc| async move {
+            let ds = acc.list(args).await?;
+            ds.map_ok(|de| (de.path().to_string(), de.mode()))
+                .try_collect::<Vec<_>>()
+                .await
+        });
+
+        // Shards could be missing in some operators, so we need to merge
+        // entries from all operators.
+        let mut entries = BTreeMap::new();
+        let mut last_err = None;
+        let mut succeeded = false;
+        for res in join_all(futs).await {
+            match res {
+                Ok(v) => {
+                    succeeded = true;
+                    entries.extend(v);
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+        if !succeeded {
+            return Err(last_err.expect("error must be set"));
+        }
+
+        let acc: Arc<dyn Accessor> = Arc::new(self.clone());
+        Ok(Box::new(stream::iter(entries.into_iter().map(
+            move |(path, mode)| Ok(DirEntry::new(acc.clone(), mode, &path)),
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::memory;
+
+    fn new_backend(operators: &[Operator], parity_shards: usize) -> Result<Backend> {
+        let mut builder = Builder::default();
+        for op in operators {
+            builder.operator(op.clone());
+        }
+        builder.parity_shards(parity_shards).build()
+    }
+
+    fn new_memories(n: usize) -> Vec<Operator> {
+        (0..n)
+            .map(|_| Operator::new(memory::Builder::default().build().expect("must init")))
+            .collect()
+    }
+
+    #[test]
+    fn test_build() {
+        let ops = new_memories(3);
+        assert!(new_backend(&ops, 0).is_err());
+        assert!(new_backend(&ops, 3).is_err());
+        assert!(new_backend(&ops, 1).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_read_write() -> anyhow::Result<()> {
+        let ops = new_memories(6);
+        let op = Operator::new(new_backend(&ops, 2)?);
+
+        let content = "Hello, World!".repeat(100);
+        op.object("dir/file").write(content.clone()).await?;
+        assert_eq!(op.object("dir/file").read().await?, content.as_bytes());
+        assert_eq!(
+            op.object("dir/file").metadata().await?.content_length(),
+            content.len() as u64
+        );
+        assert_eq!(op.object("dir/file").range_read(7..12).await?, b"World");
+
+        op.object("empty").write("").await?;
+        assert!(op.object("empty").read().await?.is_empty());
+
+        // Shard `i` is stored in the `i`-th operator.
+        let shard = ops[0].object("dir/file").read().await?;
+        assert_eq!(&shard[HEADER_SIZE..HEADER_SIZE + 13], b"Hello, World!");
+
+        let paths: Vec<String> = op
+            .object("dir/")
+            .list()
+            .await?
+            .map_ok(|de| de.path().to_string())
+            .try_collect()
+            .await?;
+        assert_eq!(paths, vec!["dir/file"]);
+
+        op.object("dir/file").delete().await?;
+        for o in ops.iter() {
+            assert!(!o.object("dir/file").is_exist().await?);
+        }
+        let err = op
+            .object("dir/file")
+            .read()
+            .await
+            .expect_err("must be deleted");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reconstruct_and_repair() -> anyhow::Result<()> {
+        let ops = new_memories(5);
+        let backend = new_backend(&ops, 2)?;
+        let op = Operator::new(backend.clone());
+
+        let content = "Hello, Erasure!".repeat(10);
+        op.object("file").write(content.clone()).await?;
+
+        // Corrupt one data shard and lose one parity shard, the corrupted
+        // shard is long enough to carry a valid looking header.
+        let mut shard = ops[0].object("file").read().await?;
+        shard[HEADER_SIZE] ^= 1;
+        ops[0].object("file").write(shard).await?;
+        ops[4].object("file").delete().await?;
+        assert_eq!(op.object("file").read().await?, content.as_bytes());
+
+        assert_eq!(backend.repair("file").await?, 2);
+        assert_eq!(backend.repair("file").await?, 0);
+        for o in ops.iter() {
+            assert_eq!(
+                o.object("file").metadata().await?.content_length() as usize,
+                HEADER_SIZE + content.len().div_ceil(3)
+            );
+        }
+
+        // Lose more shards than parity shards.
+        for o in ops.iter().take(3) {
+            o.object("file").delete().await?;
+        }
+        let err = op.object("file").read().await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert!(backend.repair("file").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mixed_generations() -> anyhow::Result<()> {
+        let ops = new_memories(5);
+        let op = Operator::new(new_backend(&ops, 2)?);
+
+        op.object("file").write("Hello, World!").await?;
+        let mut old = Vec::new();
+        for o in ops.iter() {
+            old.push(o.object("file").read().await?);
+        }
+        op.object("file").write("Hello, Rust!!").await?;
+
+        // Simulate a partially failed write that left old shards behind.
+        for (o, shard) in ops.iter().zip(old).take(2) {
+            o.object("file").write(shard).await?;
+        }
+        assert_eq!(op.object("file").read().await?, b"Hello, Rust!!");
+        assert_eq!(op.object("file").metadata().await?.content_length(), 13);
+
+        // Not enough shards of the same write.
+        ops[2].object("file").delete().await?;
+        let err = op.object("file").read().await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Other);
+
+        Ok(())
+    }
+}
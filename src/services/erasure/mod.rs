@@ -0,0 +1,65 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Erasure coded service that stores objects across several services.
+//!
+//! Every object will be split into `k` data shards and `m` parity shards
+//! (Reed-Solomon), and shard `i` will be stored at the same path in the
+//! `i`-th operator. Objects can be read as long as any `k` shards are
+//! available, and missing shards can be rebuilt by [`Backend::repair`].
+//!
+//! This service requires feature `services-erasure`.
+//!
+//! # Notes
+//!
+//! - Objects will be buffered in memory while reading and writing.
+//! - Writes require all shards to be written successfully.
+//! - Every shard carries the checksum and generation of its write, so
+//!   corrupted shards and shards left by partially failed writes will be
+//!   treated as missing.
+//! - Operators must be added in the same order every time, otherwise
+//!   shards will be mismatched.
+//!
+//! # Configuration
+//!
+//! Erasure can't be built from env, please use [`Builder`] instead.
+//!
+//! # Example
+//!
+//! ```
+//! use anyhow::Result;
+//! use opendal::services::erasure;
+//! use opendal::services::memory;
+//! use opendal::Operator;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<()> {
+//!     let mut builder = erasure::Builder::default();
+//!     // Store objects across 4 data shards and 2 parity shards.
+//!     for _ in 0..6 {
+//!         builder.operator(Operator::new(memory::Builder::default().build()?));
+//!     }
+//!     builder.parity_shards(2);
+//!     let op = Operator::new(builder.build()?);
+//!
+//!     op.object("file").write("Hello, World!").await?;
+//!     assert_eq!(op.object("file").read().await?, b"Hello, World!");
+//!
+//!     Ok(())
+//! }
+//! ```
+
+mod backend;
+pub use backend::Backend;
+pub use backend::Builder;
@@ -38,6 +38,7 @@ use crate::ops::OpCreate;
 use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpRead;
+use crate::ops::OpRename;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::Operation;
@@ -124,7 +125,8 @@ impl Accessor for Backend {
                 AccessorCapability::Read
                     | AccessorCapability::Write
                     | AccessorCapability::List
-                    | AccessorCapability::Blocking,
+                    | AccessorCapability::Blocking
+                    | AccessorCapability::Rename,
             );
 
         am
@@ -296,6 +298,32 @@ impl Accessor for Backend {
         Ok(Box::new(rd))
     }
 
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        let from = build_rooted_abs_path(&self.root, args.from());
+        let to = build_rooted_abs_path(&self.root, args.to());
+
+        let parent = PathBuf::from(&to)
+            .parent()
+            .ok_or_else(|| {
+                other(ObjectError::new(
+                    Operation::Rename,
+                    &to,
+                    anyhow!("malformed path: {:?}", &to),
+                ))
+            })?
+            .to_path_buf();
+
+        fs::create_dir_all(&parent)
+            .await
+            .map_err(|e| parse_io_error(e, Operation::Rename, &parent.to_string_lossy()))?;
+
+        fs::rename(&from, &to)
+            .await
+            .map_err(|e| parse_io_error(e, Operation::Rename, &from))?;
+
+        Ok(())
+    }
+
     fn blocking_create(&self, args: &OpCreate) -> Result<()> {
         let path = build_rooted_abs_path(&self.root, args.path());
 
@@ -490,4 +518,28 @@ impl Accessor for Backend {
 
         Ok(Box::new(f))
     }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        let from = build_rooted_abs_path(&self.root, args.from());
+        let to = build_rooted_abs_path(&self.root, args.to());
+
+        let parent = PathBuf::from(&to)
+            .parent()
+            .ok_or_else(|| {
+                other(ObjectError::new(
+                    Operation::BlockingRename,
+                    &to,
+                    anyhow!("malformed path: {:?}", &to),
+                ))
+            })?
+            .to_path_buf();
+
+        std::fs::create_dir_all(&parent)
+            .map_err(|e| parse_io_error(e, Operation::BlockingRename, &parent.to_string_lossy()))?;
+
+        std::fs::rename(&from, &to)
+            .map_err(|e| parse_io_error(e, Operation::BlockingRename, &from))?;
+
+        Ok(())
+    }
 }
@@ -14,6 +14,7 @@
 
 use std::io::Error;
 
+use crate::error::parse_io_error_kind;
 use crate::error::ObjectError;
 use crate::ops::Operation;
 
@@ -23,5 +24,11 @@ use crate::ops::Operation;
 ///
 /// Skip utf-8 check to allow invalid path input.
 pub fn parse_io_error(err: Error, op: Operation, path: &str) -> Error {
-    Error::new(err.kind(), ObjectError::new(op, path, err))
+    let kind = err.kind();
+    Error::new(
+        kind,
+        ObjectError::new(op, path, err)
+            .with_kind(parse_io_error_kind(kind))
+            .with_context("service", "fs"),
+    )
 }
@@ -13,11 +13,12 @@
 // limitations under the License.
 
 use std::io::Error;
+use std::io::ErrorKind;
 
 use anyhow::anyhow;
 use suppaftp::FtpError;
 
-use crate::error::other;
+use crate::error::parse_io_error_kind;
 use crate::error::ObjectError;
 use crate::ops::Operation;
 
@@ -28,17 +29,37 @@ use crate::ops::Operation;
 /// In the future, we may have our own error struct.
 
 pub fn new_request_connection_err(e: FtpError, op: Operation, path: &str) -> Error {
-    other(ObjectError::new(
-        op,
-        path,
-        anyhow!("connection request: {e:?}"),
-    ))
+    new_ftp_error(op, path, anyhow!("connection request: {e:?}"), &e)
 }
 
 pub fn new_request_quit_err(e: FtpError, op: Operation, path: &str) -> Error {
-    other(ObjectError::new(op, path, anyhow!("quit request: {e:?}")))
+    new_ftp_error(op, path, anyhow!("quit request: {e:?}"), &e)
+}
+
+pub fn new_unexpected_response_err(e: FtpError, op: Operation, path: &str) -> Error {
+    new_ftp_error(op, path, anyhow!("unexpected response: {e:?}"), &e)
 }
 
 pub fn parse_io_error(err: Error, op: Operation, path: &str) -> Error {
-    Error::new(err.kind(), ObjectError::new(op, path, err))
+    let kind = err.kind();
+    Error::new(
+        kind,
+        ObjectError::new(op, path, err)
+            .with_kind(parse_io_error_kind(kind))
+            .with_context("service", "ftp"),
+    )
+}
+
+/// Only connection errors carry an io error kind, others are unexpected.
+fn new_ftp_error(op: Operation, path: &str, err: anyhow::Error, e: &FtpError) -> Error {
+    let kind = match e {
+        FtpError::ConnectionError(io_err) => io_err.kind(),
+        _ => ErrorKind::Other,
+    };
+    Error::new(
+        kind,
+        ObjectError::new(op, path, err)
+            .with_kind(parse_io_error_kind(kind))
+            .with_context("service", "ftp"),
+    )
 }
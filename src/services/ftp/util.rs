@@ -12,11 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::error::other;
-use crate::error::ObjectError;
+use super::err::new_request_quit_err;
+use super::err::new_unexpected_response_err;
 use crate::ops::Operation;
 use crate::BytesReader;
-use anyhow::anyhow;
 use futures::future::BoxFuture;
 use futures::ready;
 use futures::AsyncRead;
@@ -80,20 +79,13 @@ impl AsyncRead for FtpReader {
                             ])
                             .await
                             .map_err(|e| {
-                                other(ObjectError::new(
-                                    Operation::Read,
-                                    path.as_str(),
-                                    anyhow!("unexpected response: {e:?}"),
-                                ))
+                                new_unexpected_response_err(e, Operation::Read, path.as_str())
                             })?;
 
-                        backend.quit().await.map_err(|e| {
-                            other(ObjectError::new(
-                                Operation::Read,
-                                path.as_str(),
-                                anyhow!("quit request: {e:?}"),
-                            ))
-                        })?;
+                        backend
+                            .quit()
+                            .await
+                            .map_err(|e| new_request_quit_err(e, Operation::Read, path.as_str()))?;
 
                         Ok(())
                     };
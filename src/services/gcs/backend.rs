@@ -40,6 +40,7 @@ use crate::accessor::AccessorCapability;
 use crate::error::other;
 use crate::error::BackendError;
 use crate::error::ObjectError;
+use crate::http_util::insert_header;
 use crate::http_util::new_request_build_error;
 use crate::http_util::new_request_send_error;
 use crate::http_util::new_request_sign_error;
@@ -47,6 +48,8 @@ use crate::http_util::new_response_consume_error;
 use crate::http_util::parse_error_response;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::ops::BytesRange;
 use crate::ops::OpCreate;
 use crate::ops::OpDelete;
@@ -60,6 +63,7 @@ use crate::path::normalize_root;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
@@ -83,6 +87,8 @@ pub struct Builder {
 
     /// credential string for GCS service
     credential: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Builder {
@@ -117,6 +123,15 @@ impl Builder {
         self
     }
 
+    /// Set http client used by this service.
+    ///
+    /// Default: a new [`HttpClient`](crate::HttpClient) with default configs.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+
+        self
+    }
+
     /// Establish connection to GCS and finish making GCS backend
     pub fn build(&mut self) -> Result<Backend> {
         info!("backend build started: {:?}", self);
@@ -139,7 +154,7 @@ impl Builder {
         // TODO: server side encryption
 
         // build http client
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
         let endpoint = self
             .endpoint
             .clone()
@@ -213,7 +228,12 @@ impl Debug for Backend {
 impl Backend {
     pub(crate) fn from_iter(it: impl Iterator<Item = (String, String)>) -> Result<Self> {
         let mut builder = Builder::default();
+        let mut http_client = HttpClient::builder();
+
         for (k, v) in it {
+            if http_client.parse_option(&k, &v)? {
+                continue;
+            }
             let v = v.as_str();
             match k.as_ref() {
                 "root" => builder.root(v),
@@ -223,6 +243,7 @@ impl Backend {
                 _ => continue,
             };
         }
+        builder.http_client(http_client.build()?);
         builder.build()
     }
 }
@@ -269,12 +290,20 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(args).await?;
+
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
         let p = build_abs_path(&self.root, args.path());
 
-        let resp = self.get_object(&p, args.offset(), args.size()).await?;
+        let resp = self
+            .get_object(&p, args.offset(), args.size(), args.if_match())
+            .await?;
 
         if resp.status().is_success() {
-            Ok(resp.into_body().reader())
+            Ok(resp.into_body().stream())
         } else {
             let er = parse_error_response(resp).await?;
             let e = parse_error(Operation::Read, args.path(), er);
@@ -283,9 +312,25 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(args, Box::new(into_stream(r, 8 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
         let p = build_abs_path(&self.root, args.path());
 
-        let mut req = self.insert_object_request(&p, Some(args.size()), AsyncBody::Reader(r))?;
+        let mut req = self.insert_object_request(&p, Some(args.size()), AsyncBody::Stream(s))?;
+
+        // Set checksums of content so that gcs will reject corrupted content.
+        let hashes = [
+            args.content_crc32c().map(|v| format!("crc32c={v}")),
+            args.content_md5().map(|v| format!("md5={v}")),
+        ];
+        let hashes: Vec<_> = hashes.into_iter().flatten().collect();
+        if !hashes.is_empty() {
+            insert_header(&mut req, "x-goog-hash", &hashes.join(","))
+                .map_err(|e| new_request_build_error(Operation::Write, &p, e))?;
+        }
 
         self.signer
             .sign(&mut req)
@@ -343,6 +388,9 @@ impl Accessor for Backend {
 
             m.set_etag(&meta.etag);
             m.set_content_md5(&meta.md5_hash);
+            if let Some(v) = &meta.crc32c {
+                m.set_content_crc32c(v);
+            }
 
             let size = meta.size.parse::<u64>().map_err(|e| {
                 other(ObjectError::new(
@@ -415,6 +463,7 @@ impl Backend {
         path: &str,
         offset: Option<u64>,
         size: Option<u64>,
+        if_match: Option<&str>,
     ) -> Result<Request<AsyncBody>> {
         let url = format!(
             "{}/storage/v1/b/{}/o/{}?alt=media",
@@ -432,6 +481,10 @@ impl Backend {
             );
         }
 
+        if let Some(etag) = if_match {
+            req = req.header(http::header::IF_MATCH, etag);
+        }
+
         let req = req
             .body(AsyncBody::Empty)
             .map_err(|e| new_request_build_error(Operation::Read, path, e))?;
@@ -444,8 +497,9 @@ impl Backend {
         path: &str,
         offset: Option<u64>,
         size: Option<u64>,
+        if_match: Option<&str>,
     ) -> Result<Response<AsyncBody>> {
-        let mut req = self.get_object_request(path, offset, size)?;
+        let mut req = self.get_object_request(path, offset, size, if_match)?;
 
         self.signer
             .sign(&mut req)
@@ -587,6 +641,11 @@ struct GetObjectJsonResponse {
     ///
     /// For example: `"md5Hash": "fHcEH1vPwA6eTPqxuasXcg=="`
     md5_hash: String,
+    /// Base64 encoded big-endian CRC32C
+    ///
+    /// For example: `"crc32c": "j/un9g=="`
+    #[serde(default)]
+    crc32c: Option<String>,
 }
 
 #[cfg(test)]
@@ -621,6 +680,7 @@ mod tests {
         assert_eq!(meta.size, "56535");
         assert_eq!(meta.updated, "2022-08-15T11:33:34.866Z");
         assert_eq!(meta.md5_hash, "fHcEH1vPwA6eTPqxuasXcg==");
+        assert_eq!(meta.crc32c.as_deref(), Some("j/un9g=="));
         assert_eq!(meta.etag, "CKWasoTgyPkCEAE=");
     }
 }
@@ -13,30 +13,127 @@
 // limitations under the License.
 
 use std::io::Error;
-use std::io::ErrorKind;
 
 use anyhow::anyhow;
-use http::StatusCode;
+use serde::Deserialize;
 
 use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::http_util::parse_error_kind;
 use crate::http_util::ErrorResponse;
 use crate::ops::Operation;
 
-/// Parse error response into io::Error.
-///
-/// # TODO
+/// GcsErrorResponse is the error response returned by gcs json api.
 ///
-/// Make our own error type :)
+/// ref: https://cloud.google.com/storage/docs/json_api/v1/status-codes
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+struct GcsErrorResponse {
+    error: GcsError,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+struct GcsError {
+    message: String,
+    errors: Vec<GcsErrorDetail>,
+}
+
+#[derive(Default, Debug, Deserialize)]
+#[serde(default)]
+struct GcsErrorDetail {
+    reason: String,
+}
+
+/// Parse error response into io::Error.
 pub fn parse_error(op: Operation, path: &str, er: ErrorResponse) -> Error {
-    let kind = match er.status_code() {
-        StatusCode::NOT_FOUND => ErrorKind::NotFound,
-        StatusCode::FORBIDDEN => ErrorKind::PermissionDenied,
-        StatusCode::INTERNAL_SERVER_ERROR
-        | StatusCode::BAD_GATEWAY
-        | StatusCode::SERVICE_UNAVAILABLE
-        | StatusCode::GATEWAY_TIMEOUT => ErrorKind::Interrupted,
-        _ => ErrorKind::Other,
+    let mut err = ObjectError::new(op, path, anyhow!("{er}"))
+        .with_kind(parse_error_kind(er.status_code()))
+        .with_context("service", "gcs");
+
+    if let Some(v) = er
+        .headers()
+        .get("x-guploader-uploadid")
+        .and_then(|v| v.to_str().ok())
+    {
+        err = err.with_context("request_id", v);
+    }
+
+    let gcs_err: GcsErrorResponse = match serde_json::from_slice(er.body()) {
+        Ok(v) => v,
+        Err(_) => return err.into(),
+    };
+
+    // Use the reason of the first error as error code.
+    let reason = gcs_err
+        .error
+        .errors
+        .first()
+        .map(|v| v.reason.as_str())
+        .unwrap_or_default();
+    err = match reason {
+        "notFound" => err.with_kind(ObjectErrorKind::ObjectNotFound),
+        "forbidden" | "insufficientPermissions" => err.with_kind(ObjectErrorKind::PermissionDenied),
+        "conditionNotMet" => err.with_kind(ObjectErrorKind::ConditionNotMatch),
+        "rateLimitExceeded" | "userRateLimitExceeded" => {
+            err.with_kind(ObjectErrorKind::RateLimited)
+        }
+        "backendError" | "internalError" => err.with_kind(ObjectErrorKind::ServiceUnavailable),
+        _ => err,
     };
+    let request_id = err.request_id().map(|v| v.to_string());
+    err = err.with_source(er.describe(reason, &gcs_err.error.message, request_id.as_deref()));
+    if !reason.is_empty() {
+        err = err.with_context("code", reason);
+    }
+    if !gcs_err.error.message.is_empty() {
+        err = err.with_context("message", gcs_err.error.message);
+    }
+
+    err.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Response;
+    use http::StatusCode;
+
+    use super::*;
+    use crate::http_util::parse_error_response;
+    use crate::http_util::AsyncBody;
+
+    #[tokio::test]
+    async fn test_parse_error() {
+        let body = r#"{
+  "error": {
+    "code": 429,
+    "message": "The object exceeded the rate limit for object mutation operations.",
+    "errors": [
+      {
+        "message": "The object exceeded the rate limit for object mutation operations.",
+        "domain": "usageLimits",
+        "reason": "rateLimitExceeded"
+      }
+    ]
+  }
+}"#;
+        let resp = Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header("x-guploader-uploadid", "ADPycdtWQ3Bgq")
+            .body(AsyncBody::Bytes(body.into()))
+            .expect("must build");
+        let er = parse_error_response(resp).await.expect("must parse");
+
+        let err = parse_error(Operation::Write, "a", er);
+        assert_eq!(err.kind(), std::io::ErrorKind::Interrupted);
 
-    Error::new(kind, ObjectError::new(op, path, anyhow!("{er}")))
+        let oe = ObjectError::downcast(&err).expect("must be object error");
+        assert_eq!(oe.kind(), ObjectErrorKind::RateLimited);
+        assert_eq!(oe.service_code(), Some("rateLimitExceeded"));
+        assert_eq!(
+            oe.service_message(),
+            Some("The object exceeded the rate limit for object mutation operations.")
+        );
+        assert_eq!(oe.request_id(), Some("ADPycdtWQ3Bgq"));
+    }
 }
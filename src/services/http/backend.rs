@@ -39,6 +39,7 @@ use crate::http_util::parse_last_modified;
 use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
+use crate::io_util::into_reader;
 use crate::ops::BytesRange;
 use crate::ops::OpRead;
 use crate::ops::OpStat;
@@ -48,6 +49,7 @@ use crate::path::normalize_root;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
 use crate::Scheme;
@@ -57,6 +59,8 @@ use crate::Scheme;
 pub struct Builder {
     endpoint: Option<String>,
     root: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -94,6 +98,15 @@ impl Builder {
         self
     }
 
+    /// Set http client used by this service.
+    ///
+    /// Default: a new [`HttpClient`](crate::HttpClient) with default configs.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+
+        self
+    }
+
     /// Build a HTTP backend.
     pub fn build(&mut self) -> Result<Backend> {
         info!("backend build started: {:?}", &self);
@@ -111,7 +124,7 @@ impl Builder {
         let root = normalize_root(&self.root.take().unwrap_or_default());
         info!("backend use root {}", root);
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         info!("backend build finished: {:?}", &self);
         Ok(Backend {
@@ -143,8 +156,12 @@ impl Debug for Backend {
 impl Backend {
     pub(crate) fn from_iter(it: impl Iterator<Item = (String, String)>) -> Result<Self> {
         let mut builder = Builder::default();
+        let mut http_client = HttpClient::builder();
 
         for (k, v) in it {
+            if http_client.parse_option(&k, &v)? {
+                continue;
+            }
             let v = v.as_str();
             match k.as_ref() {
                 "root" => builder.root(v),
@@ -153,6 +170,7 @@ impl Backend {
             };
         }
 
+        builder.http_client(http_client.build()?);
         builder.build()
     }
 }
@@ -169,14 +187,22 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(args).await?;
+
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
         let p = build_rooted_abs_path(&self.root, args.path());
 
-        let resp = self.http_get(&p, args.offset(), args.size()).await?;
+        let resp = self
+            .http_get(&p, args.offset(), args.size(), args.if_match())
+            .await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, args.path(), er);
@@ -259,6 +285,7 @@ impl Backend {
         path: &str,
         offset: Option<u64>,
         size: Option<u64>,
+        if_match: Option<&str>,
     ) -> Result<Response<AsyncBody>> {
         let url = format!("{}{}", self.endpoint, percent_encode_path(path));
 
@@ -271,6 +298,10 @@ impl Backend {
             );
         }
 
+        if let Some(etag) = if_match {
+            req = req.header(http::header::IF_MATCH, etag);
+        }
+
         let req = req
             .body(AsyncBody::Empty)
             .map_err(|e| new_request_build_error(Operation::Read, path, e))?;
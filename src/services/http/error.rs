@@ -13,12 +13,11 @@
 // limitations under the License.
 
 use std::io::Error;
-use std::io::ErrorKind;
 
 use anyhow::anyhow;
-use http::StatusCode;
 
 use crate::error::ObjectError;
+use crate::http_util::parse_error_kind;
 use crate::http_util::ErrorResponse;
 use crate::ops::Operation;
 
@@ -28,15 +27,8 @@ use crate::ops::Operation;
 ///
 /// In the future, we may have our own error struct.
 pub fn parse_error(op: Operation, path: &str, er: ErrorResponse) -> Error {
-    let kind = match er.status_code() {
-        StatusCode::NOT_FOUND => ErrorKind::NotFound,
-        StatusCode::FORBIDDEN => ErrorKind::PermissionDenied,
-        StatusCode::INTERNAL_SERVER_ERROR
-        | StatusCode::BAD_GATEWAY
-        | StatusCode::SERVICE_UNAVAILABLE
-        | StatusCode::GATEWAY_TIMEOUT => ErrorKind::Interrupted,
-        _ => ErrorKind::Other,
-    };
-
-    Error::new(kind, ObjectError::new(op, path, anyhow!("{er}")))
+    ObjectError::new(op, path, anyhow!("{er}"))
+        .with_kind(parse_error_kind(er.status_code()))
+        .with_context("service", "http")
+        .into()
 }
@@ -48,6 +48,7 @@ use crate::http_util::parse_etag;
 use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
+use crate::io_util::into_reader;
 use crate::ops::OpRead;
 use crate::ops::OpStat;
 use crate::ops::Operation;
@@ -55,6 +56,7 @@ use crate::ops::{BytesRange, OpList};
 use crate::path::build_rooted_abs_path;
 use crate::path::normalize_root;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
 use crate::Scheme;
@@ -66,6 +68,8 @@ use crate::{AccessorMetadata, DirStreamer};
 pub struct Builder {
     endpoint: Option<String>,
     root: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Builder {
@@ -104,6 +108,15 @@ impl Builder {
         self
     }
 
+    /// Set http client used by this service.
+    ///
+    /// Default: a new [`HttpClient`](crate::HttpClient) with default configs.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+
+        self
+    }
+
     /// Consume builder to build an ipfs backend.
     pub fn build(&mut self) -> Result<Backend> {
         info!("backend build started: {:?}", &self);
@@ -130,7 +143,7 @@ impl Builder {
         Ok(Backend {
             root,
             endpoint,
-            client: HttpClient::new(),
+            client: self.http_client.take().unwrap_or_default(),
         })
     }
 }
@@ -156,8 +169,12 @@ impl Debug for Backend {
 impl Backend {
     pub(crate) fn from_iter(it: impl Iterator<Item = (String, String)>) -> Result<Self> {
         let mut builder = Builder::default();
+        let mut http_client = HttpClient::builder();
 
         for (k, v) in it {
+            if http_client.parse_option(&k, &v)? {
+                continue;
+            }
             let v = v.as_str();
             match k.as_ref() {
                 "root" => builder.root(v),
@@ -166,6 +183,7 @@ impl Backend {
             };
         }
 
+        builder.http_client(http_client.build()?);
         builder.build()
     }
 }
@@ -182,6 +200,12 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(args).await?;
+
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
         let p = build_rooted_abs_path(&self.root, args.path());
 
         let resp = self.ipfs_get(&p, args.offset(), args.size()).await?;
@@ -189,7 +213,7 @@ impl Accessor for Backend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, args.path(), er);
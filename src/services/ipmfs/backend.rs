@@ -40,6 +40,7 @@ use crate::http_util::parse_error_response;
 use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
+use crate::io_util::into_reader;
 use crate::ops::OpCreate;
 use crate::ops::OpDelete;
 use crate::ops::OpList;
@@ -51,6 +52,7 @@ use crate::path::build_rooted_abs_path;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
@@ -84,8 +86,12 @@ impl Backend {
 
     pub(crate) fn from_iter(it: impl Iterator<Item = (String, String)>) -> Result<Self> {
         let mut builder = Builder::default();
+        let mut http_client = HttpClient::builder();
 
         for (key, val) in it {
+            if http_client.parse_option(&key, &val)? {
+                continue;
+            }
             let val = val.as_str();
             match key.as_ref() {
                 "root" => builder.root(val),
@@ -94,6 +100,7 @@ impl Backend {
             };
         }
 
+        builder.http_client(http_client.build()?);
         builder.build()
     }
 }
@@ -139,6 +146,12 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(args).await?;
+
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
         let path = build_rooted_abs_path(&self.root, args.path());
 
         let offset = args.offset().and_then(|val| i64::try_from(val).ok());
@@ -148,7 +161,7 @@ impl Accessor for Backend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK => Ok(resp.into_body().reader()),
+            StatusCode::OK => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Stat, args.path(), er);
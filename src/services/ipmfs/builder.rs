@@ -25,6 +25,8 @@ use crate::path::normalize_root;
 pub struct Builder {
     root: Option<String>,
     endpoint: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Builder {
@@ -51,6 +53,15 @@ impl Builder {
         self
     }
 
+    /// Set http client used by this service.
+    ///
+    /// Default: a new [`HttpClient`](crate::HttpClient) with default configs.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+
+        self
+    }
+
     /// Consume builder to build an ipfs::Backend.
     pub fn build(&mut self) -> Result<Backend> {
         let root = normalize_root(&self.root.take().unwrap_or_default());
@@ -61,7 +72,7 @@ impl Builder {
             .clone()
             .unwrap_or_else(|| "http://localhost:5001".to_string());
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         info!("backend build finished: {:?}", &self);
         Ok(Backend::new(root, client, endpoint))
@@ -21,6 +21,8 @@ use http::StatusCode;
 use serde::Deserialize;
 
 use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::http_util::parse_error_kind;
 use crate::http_util::ErrorResponse;
 use crate::ops::Operation;
 
@@ -45,27 +47,30 @@ struct IpfsError {
 ///
 /// ref: https://docs.ipfs.tech/reference/kubo/rpc/#http-status-codes
 pub fn parse_error(op: Operation, path: &str, er: ErrorResponse) -> Error {
-    let kind = match er.status_code() {
-        StatusCode::INTERNAL_SERVER_ERROR => {
-            let ie: Result<IpfsError> = serde_json::from_slice(er.body()).map_err(|err| {
-                Error::new(
-                    ErrorKind::Other,
-                    ObjectError::new(op, path, anyhow!("deserialize error content: {err:?}")),
-                )
-            });
-            match ie {
-                Ok(ie) => match ie.message.as_str() {
-                    "file does not exist" => ErrorKind::NotFound,
-                    _ => ErrorKind::Other,
-                },
-                Err(e) => return e,
-            }
-        }
-        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT => {
-            ErrorKind::Interrupted
-        }
-        _ => ErrorKind::Other,
-    };
+    let mut err = ObjectError::new(op, path, anyhow!("{er}"))
+        .with_kind(parse_error_kind(er.status_code()))
+        .with_context("service", "ipmfs");
 
-    Error::new(kind, ObjectError::new(op, path, anyhow!("{er}")))
+    if er.status_code() == StatusCode::INTERNAL_SERVER_ERROR {
+        let ie: Result<IpfsError> = serde_json::from_slice(er.body()).map_err(|err| {
+            Error::new(
+                ErrorKind::Other,
+                ObjectError::new(op, path, anyhow!("deserialize error content: {err:?}")),
+            )
+        });
+        let ie = match ie {
+            Ok(ie) => ie,
+            Err(e) => return e,
+        };
+
+        let kind = match ie.message.as_str() {
+            "file does not exist" => ObjectErrorKind::ObjectNotFound,
+            _ => ObjectErrorKind::Unexpected,
+        };
+        err = err
+            .with_kind(kind)
+            .with_source(er.describe(&ie.ty, &ie.message, None));
+    }
+
+    err.into()
 }
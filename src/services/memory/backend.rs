@@ -14,8 +14,6 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::io::Error;
-use std::io::ErrorKind;
 use std::io::Result;
 use std::mem;
 use std::pin::Pin;
@@ -34,6 +32,7 @@ use parking_lot::Mutex;
 use crate::accessor::AccessorCapability;
 use crate::error::other;
 use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
 use crate::ops::OpCreate;
 use crate::ops::OpDelete;
 use crate::ops::OpList;
@@ -109,10 +108,9 @@ impl Accessor for Backend {
         let map = self.inner.lock();
 
         let data = map.get(path).ok_or_else(|| {
-            Error::new(
-                ErrorKind::NotFound,
-                ObjectError::new(Operation::Read, path, anyhow!("key not exists in map")),
-            )
+            ObjectError::new(Operation::Read, path, anyhow!("key not exists in map"))
+                .with_kind(ObjectErrorKind::ObjectNotFound)
+                .with_context("service", "memory")
         })?;
 
         let mut data = data.clone();
@@ -172,10 +170,9 @@ impl Accessor for Backend {
         let map = self.inner.lock();
 
         let data = map.get(path).ok_or_else(|| {
-            Error::new(
-                ErrorKind::NotFound,
-                ObjectError::new(Operation::Read, path, anyhow!("key not exists in map")),
-            )
+            ObjectError::new(Operation::Stat, path, anyhow!("key not exists in map"))
+                .with_kind(ObjectErrorKind::ObjectNotFound)
+                .with_context("service", "memory")
         })?;
 
         let mut meta = ObjectMetadata::default();
@@ -20,6 +20,8 @@
 //! - Backend: the service backend which implements the [`Accessor`][crate::Accessor] trait.
 
 pub mod azblob;
+#[cfg(feature = "services-erasure")]
+pub mod erasure;
 pub mod fs;
 #[cfg(feature = "services-ftp")]
 pub mod ftp;
@@ -32,4 +34,7 @@ pub mod ipfs;
 pub mod ipmfs;
 pub mod memory;
 pub mod obs;
+pub mod overlay;
+pub mod router;
 pub mod s3;
+pub mod shard;
@@ -34,6 +34,7 @@ use crate::accessor::AccessorCapability;
 use crate::error::other;
 use crate::error::BackendError;
 use crate::error::ObjectError;
+use crate::http_util::insert_header;
 use crate::http_util::new_request_build_error;
 use crate::http_util::new_request_send_error;
 use crate::http_util::new_request_sign_error;
@@ -45,6 +46,8 @@ use crate::http_util::parse_last_modified;
 use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::ops::BytesRange;
 use crate::ops::OpCreate;
 use crate::ops::OpDelete;
@@ -59,6 +62,7 @@ use crate::services::obs::dir_stream::DirStream;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
@@ -72,6 +76,8 @@ pub struct Builder {
     access_key_id: Option<String>,
     secret_access_key: Option<String>,
     bucket: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -146,6 +152,15 @@ impl Builder {
         self
     }
 
+    /// Set http client used by this service.
+    ///
+    /// Default: a new [`HttpClient`](crate::HttpClient) with default configs.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+
+        self
+    }
+
     /// Consume builder to build an OBS backend.
     pub fn build(&mut self) -> Result<Backend> {
         info!("backend build started: {:?}", &self);
@@ -196,7 +211,7 @@ impl Builder {
             ("endpoint".to_string(), endpoint.to_string()),
         ]);
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         let mut signer_builder = Signer::builder();
         if let (Some(access_key_id), Some(secret_access_key)) =
@@ -248,8 +263,12 @@ pub struct Backend {
 impl Backend {
     pub(crate) fn from_iter(it: impl Iterator<Item = (String, String)>) -> Result<Self> {
         let mut builder = Builder::default();
+        let mut http_client = HttpClient::builder();
 
         for (k, v) in it {
+            if http_client.parse_option(&k, &v)? {
+                continue;
+            }
             let v = v.as_str();
             match k.as_ref() {
                 "root" => builder.root(v),
@@ -261,6 +280,7 @@ impl Backend {
             };
         }
 
+        builder.http_client(http_client.build()?);
         builder.build()
     }
 }
@@ -313,14 +333,22 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(args).await?;
+
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
         let p = build_abs_path(&self.root, args.path());
 
-        let resp = self.get_object(&p, args.offset(), args.size()).await?;
+        let resp = self
+            .get_object(&p, args.offset(), args.size(), args.if_match())
+            .await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, args.path(), er);
@@ -330,9 +358,20 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(args, Box::new(into_stream(r, 8 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
         let p = build_abs_path(&self.root, args.path());
 
-        let mut req = self.put_object_request(&p, Some(args.size()), AsyncBody::Reader(r))?;
+        let mut req = self.put_object_request(&p, Some(args.size()), AsyncBody::Stream(s))?;
+
+        // Set md5 of content so that service will reject corrupted content.
+        if let Some(v) = args.content_md5() {
+            insert_header(&mut req, "content-md5", v)
+                .map_err(|e| new_request_build_error(Operation::Write, &p, e))?;
+        }
 
         self.signer
             .sign(&mut req)
@@ -391,7 +430,18 @@ impl Accessor for Backend {
                     .map_err(|e| other(ObjectError::new(Operation::Stat, &p, e)))?
                 {
                     m.set_etag(v);
-                    m.set_content_md5(v.trim_matches('"'));
+                    // ETag is the md5 of content only if the object is not
+                    // uploaded by multipart or encrypted by SSE-KMS/SSE-C.
+                    let headers = resp.headers();
+                    let kms = headers
+                        .get("x-obs-server-side-encryption")
+                        .map(|v| v.as_bytes() == b"kms")
+                        .unwrap_or_default();
+                    let ssec =
+                        headers.contains_key("x-obs-server-side-encryption-customer-algorithm");
+                    if !v.contains('-') && !kms && !ssec {
+                        m.set_content_md5(v.trim_matches('"'));
+                    }
                 }
 
                 if let Some(v) = parse_last_modified(resp.headers())
@@ -456,6 +506,7 @@ impl Backend {
         path: &str,
         offset: Option<u64>,
         size: Option<u64>,
+        if_match: Option<&str>,
     ) -> Result<Response<AsyncBody>> {
         let url = format!("{}/{}", self.endpoint, percent_encode_path(path));
 
@@ -468,6 +519,10 @@ impl Backend {
             )
         }
 
+        if let Some(etag) = if_match {
+            req = req.header(http::header::IF_MATCH, etag);
+        }
+
         let mut req = req
             .body(AsyncBody::Empty)
             .map_err(|e| new_request_build_error(Operation::Read, path, e))?;
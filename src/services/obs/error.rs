@@ -13,30 +13,71 @@
 // limitations under the License.
 
 use std::io::Error;
-use std::io::ErrorKind;
 
 use anyhow::anyhow;
-use http::StatusCode;
+use bytes::Buf;
+use quick_xml::de;
+use serde::Deserialize;
 
 use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::http_util::parse_error_kind;
 use crate::http_util::ErrorResponse;
 use crate::ops::Operation;
 
+/// ObsError is the error returned by obs service.
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct ObsError {
+    code: String,
+    message: String,
+    request_id: String,
+}
+
 /// Parse error response into io::Error.
-///
-/// # TODO
-///
-/// In the future, we may have our own error struct.
 pub fn parse_error(op: Operation, path: &str, er: ErrorResponse) -> Error {
-    let kind = match er.status_code() {
-        StatusCode::NOT_FOUND => ErrorKind::NotFound,
-        StatusCode::FORBIDDEN => ErrorKind::PermissionDenied,
-        StatusCode::INTERNAL_SERVER_ERROR
-        | StatusCode::BAD_GATEWAY
-        | StatusCode::SERVICE_UNAVAILABLE
-        | StatusCode::GATEWAY_TIMEOUT => ErrorKind::Interrupted,
-        _ => ErrorKind::Other,
+    let mut err = ObjectError::new(op, path, anyhow!("{er}"))
+        .with_kind(parse_error_kind(er.status_code()))
+        .with_context("service", "obs");
+
+    if let Some(v) = er
+        .headers()
+        .get("x-obs-request-id")
+        .and_then(|v| v.to_str().ok())
+    {
+        err = err.with_context("request_id", v);
+    }
+
+    let obs_err: ObsError = match de::from_reader(er.body().reader()) {
+        Ok(v) => v,
+        Err(_) => return err.into(),
+    };
+
+    err = match obs_err.code.as_str() {
+        "NoSuchKey" | "NoSuchBucket" => err.with_kind(ObjectErrorKind::ObjectNotFound),
+        "AccessDenied" => err.with_kind(ObjectErrorKind::PermissionDenied),
+        "PreconditionFailed" => err.with_kind(ObjectErrorKind::ConditionNotMatch),
+        "InternalError" | "ServiceUnavailable" => {
+            err.with_kind(ObjectErrorKind::ServiceUnavailable)
+        }
+        "RequestTimeout" => err.with_temporary(true),
+        _ => err,
+    };
+    let request_id = match err.request_id() {
+        Some(v) => Some(v.to_string()),
+        None if !obs_err.request_id.is_empty() => Some(obs_err.request_id.clone()),
+        None => None,
     };
+    err = err.with_source(er.describe(&obs_err.code, &obs_err.message, request_id.as_deref()));
+    if !obs_err.code.is_empty() {
+        err = err.with_context("code", obs_err.code);
+    }
+    if !obs_err.message.is_empty() {
+        err = err.with_context("message", obs_err.message);
+    }
+    if !obs_err.request_id.is_empty() && err.request_id().is_none() {
+        err = err.with_context("request_id", obs_err.request_id);
+    }
 
-    Error::new(kind, ObjectError::new(op, path, anyhow!("{er}")))
+    err.into()
 }
@@ -0,0 +1,841 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::io::Cursor;
+use futures::stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use log::info;
+
+use crate::accessor::AccessorCapability;
+use crate::error::other;
+use crate::error::BackendError;
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignOperation;
+use crate::ops::PresignedRequest;
+use crate::path::get_parent;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirEntry;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::Operator;
+use crate::Scheme;
+
+/// Prefix of whiteout objects in upper service.
+const WHITEOUT_PREFIX: &str = ".wh.";
+/// Name of the opaque marker in upper service, all entries in lower service
+/// under the dir that contains this marker will be hidden.
+const OPAQUE_MARKER: &str = ".wh..wh..opq";
+
+/// Builder for overlay service.
+#[derive(Default, Debug)]
+pub struct Builder {
+    upper: Option<Operator>,
+    lower: Option<Operator>,
+}
+
+impl Builder {
+    /// Set the writable upper operator.
+    ///
+    /// All changes will be written into this operator.
+    pub fn upper(&mut self, op: Operator) -> &mut Self {
+        self.upper = Some(op);
+
+        self
+    }
+
+    /// Set the read-only lower operator.
+    ///
+    /// This operator will never be changed by overlay.
+    pub fn lower(&mut self, op: Operator) -> &mut Self {
+        self.lower = Some(op);
+
+        self
+    }
+
+    /// Consume builder to build an overlay service.
+    pub fn build(&mut self) -> Result<Backend> {
+        info!("backend build started: {:?}", &self);
+
+        let upper = match self.upper.take() {
+            Some(v) => v.inner(),
+            None => {
+                return Err(other(BackendError::new(
+                    HashMap::new(),
+                    anyhow!("upper operator is empty"),
+                )))
+            }
+        };
+        let lower = match self.lower.take() {
+            Some(v) => v.inner(),
+            None => {
+                return Err(other(BackendError::new(
+                    HashMap::new(),
+                    anyhow!("lower operator is empty"),
+                )))
+            }
+        };
+
+        info!("backend build finished: {:?}", &self);
+        Ok(Backend { upper, lower })
+    }
+}
+
+/// Backend for overlay service.
+#[derive(Debug, Clone)]
+pub struct Backend {
+    upper: Arc<dyn Accessor>,
+    lower: Arc<dyn Accessor>,
+}
+
+impl Backend {
+    /// Check if this path or any of its parents has been deleted in overlay.
+    async fn is_whiteout(&self, path: &str) -> Result<bool> {
+        for wh in whiteout_paths(path) {
+            match self.upper.stat(&OpStat::new(&wh)?).await {
+                Ok(_) => return Ok(true),
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Record a whiteout for this path if it exists in lower service.
+    async fn add_whiteout(&self, path: &str) -> Result<()> {
+        let wh = match whiteout_path(path) {
+            Some(wh) => wh,
+            None => return Ok(()),
+        };
+
+        match self.lower.stat(&OpStat::new(path)?).await {
+            Ok(_) => {
+                let r: BytesReader = Box::new(Cursor::new(Vec::new()));
+                self.upper.write(&OpWrite::new(&wh, 0)?, r).await?;
+                Ok(())
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Remove the whiteout of this path so that new object could be visible.
+    async fn remove_whiteout(&self, path: &str) -> Result<()> {
+        match whiteout_path(path) {
+            Some(wh) => self.upper.delete(&OpDelete::new(&wh)?).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Mark a recreated dir as opaque so that its old entries in lower
+    /// service are still hidden after removing the whiteout.
+    async fn add_opaque(&self, path: &str) -> Result<()> {
+        if !self.is_whiteout(path).await? {
+            return Ok(());
+        }
+
+        let r: BytesReader = Box::new(Cursor::new(Vec::new()));
+        self.upper
+            .write(&OpWrite::new(&opaque_path(path), 0)?, r)
+            .await?;
+        Ok(())
+    }
+
+    fn blocking_is_whiteout(&self, path: &str) -> Result<bool> {
+        for wh in whiteout_paths(path) {
+            match self.upper.blocking_stat(&OpStat::new(&wh)?) {
+                Ok(_) => return Ok(true),
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn blocking_add_whiteout(&self, path: &str) -> Result<()> {
+        let wh = match whiteout_path(path) {
+            Some(wh) => wh,
+            None => return Ok(()),
+        };
+
+        match self.lower.blocking_stat(&OpStat::new(path)?) {
+            Ok(_) => {
+                let r: BlockingBytesReader = Box::new(std::io::Cursor::new(Vec::new()));
+                self.upper.blocking_write(&OpWrite::new(&wh, 0)?, r)?;
+                Ok(())
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn blocking_remove_whiteout(&self, path: &str) -> Result<()> {
+        match whiteout_path(path) {
+            Some(wh) => self.upper.blocking_delete(&OpDelete::new(&wh)?),
+            None => Ok(()),
+        }
+    }
+
+    fn blocking_add_opaque(&self, path: &str) -> Result<()> {
+        if !self.blocking_is_whiteout(path)? {
+            return Ok(());
+        }
+
+        let r: BlockingBytesReader = Box::new(std::io::Cursor::new(Vec::new()));
+        self.upper
+            .blocking_write(&OpWrite::new(&opaque_path(path), 0)?, r)?;
+        Ok(())
+    }
+
+    /// Merge entries from upper service into the visible entries and the
+    /// hidden names that should be skipped in lower service.
+    fn merge_upper_entry(
+        &self,
+        de: DirEntry,
+        entries: &mut Vec<DirEntry>,
+        hidden: &mut HashSet<String>,
+    ) {
+        if is_opaque_marker(de.path()) {
+            return;
+        }
+
+        match whiteout_target(de.path()) {
+            Some(target) => {
+                hidden.insert(target);
+            }
+            None => {
+                hidden.insert(de.path().trim_end_matches('/').to_string());
+
+                let mut de = de;
+                de.set_accessor(Arc::new(self.clone()));
+                entries.push(de);
+            }
+        }
+    }
+}
+
+/// Returns the whiteout path of input path, root doesn't have whiteout.
+///
+/// - `dir/file` => `dir/.wh.file`
+/// - `dir/sub/` => `dir/.wh.sub`
+fn whiteout_path(path: &str) -> Option<String> {
+    let path = path.trim_end_matches('/');
+    if path.is_empty() {
+        return None;
+    }
+
+    match path.rfind('/') {
+        Some(idx) => Some(format!(
+            "{}{WHITEOUT_PREFIX}{}",
+            &path[..=idx],
+            &path[idx + 1..]
+        )),
+        None => Some(format!("{WHITEOUT_PREFIX}{path}")),
+    }
+}
+
+/// Returns the opaque marker path of input dir.
+///
+/// - `dir/sub/` => `dir/sub/.wh..wh..opq`
+fn opaque_path(dir: &str) -> String {
+    format!("{dir}{OPAQUE_MARKER}")
+}
+
+fn is_opaque_marker(path: &str) -> bool {
+    path == OPAQUE_MARKER || path.ends_with(&format!("/{OPAQUE_MARKER}"))
+}
+
+/// Returns the paths in upper service that hide input path in lower
+/// service: whiteouts of input path and all its parents, and opaque
+/// markers of all dirs containing it.
+///
+/// - `dir/sub/file` => `dir/sub/.wh.file`, `dir/sub/.wh..wh..opq`, `dir/.wh.sub`, `dir/.wh..wh..opq`, `.wh.dir`
+/// - `dir/` => `dir/.wh..wh..opq`, `.wh.dir`
+fn whiteout_paths(path: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    if path.ends_with('/') && path != "/" {
+        paths.push(opaque_path(path));
+    }
+
+    let mut path = path;
+    while let Some(wh) = whiteout_path(path) {
+        paths.push(wh);
+        path = get_parent(path);
+        if path != "/" {
+            paths.push(opaque_path(path));
+        }
+    }
+    paths
+}
+
+/// Check that input path doesn't collide with whiteouts and opaque markers.
+fn check_path(op: Operation, path: &str) -> Result<()> {
+    if path.split('/').any(|v| v.starts_with(WHITEOUT_PREFIX)) {
+        return Err(ObjectError::new(
+            op,
+            path,
+            anyhow!("name starts with {WHITEOUT_PREFIX} is reserved by overlay"),
+        )
+        .with_kind(ObjectErrorKind::Unsupported)
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Returns the hidden path (without trailing `/`) if input is a whiteout.
+fn whiteout_target(path: &str) -> Option<String> {
+    if path.ends_with('/') {
+        return None;
+    }
+
+    let (parent, name) = match path.rfind('/') {
+        Some(idx) => path.split_at(idx + 1),
+        None => ("", path),
+    };
+    name.strip_prefix(WHITEOUT_PREFIX)
+        .filter(|v| !v.is_empty())
+        .map(|v| format!("{parent}{v}"))
+}
+
+fn new_not_found_error(op: Operation, path: &str) -> std::io::Error {
+    ObjectError::new(op, path, anyhow!("object has been deleted in overlay"))
+        .with_kind(ObjectErrorKind::ObjectNotFound)
+        .into()
+}
+
+#[async_trait]
+impl Accessor for Backend {
+    fn metadata(&self) -> AccessorMetadata {
+        let upper = self.upper.metadata().capabilities();
+        let lower = self.lower.metadata().capabilities();
+
+        let mut capabilities = AccessorCapability::Read | AccessorCapability::Write;
+        if (upper | lower).contains(AccessorCapability::List) {
+            capabilities |= AccessorCapability::List;
+        }
+        if upper.contains(AccessorCapability::Multipart) {
+            capabilities |= AccessorCapability::Multipart;
+        }
+        if upper.contains(AccessorCapability::Presign) {
+            capabilities |= AccessorCapability::Presign;
+        }
+        if upper.contains(AccessorCapability::Blocking)
+            && lower.contains(AccessorCapability::Blocking)
+        {
+            capabilities |= AccessorCapability::Blocking;
+        }
+
+        let mut am = AccessorMetadata::default();
+        am.set_scheme(Scheme::Overlay)
+            .set_root("/")
+            .set_name("overlay")
+            .set_capabilities(capabilities);
+
+        am
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        check_path(Operation::Create, args.path())?;
+
+        self.upper.create(args).await?;
+        if args.path().ends_with('/') {
+            self.add_opaque(args.path()).await?;
+        }
+        self.remove_whiteout(args.path()).await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        check_path(Operation::Read, args.path())?;
+
+        match self.upper.read(args).await {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                if self.is_whiteout(args.path()).await? {
+                    return Err(new_not_found_error(Operation::Read, args.path()));
+                }
+                self.lower.read(args).await
+            }
+            v => v,
+        }
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        check_path(Operation::Write, args.path())?;
+
+        let n = self.upper.write(args, r).await?;
+        self.remove_whiteout(args.path()).await?;
+        Ok(n)
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        check_path(Operation::Read, args.path())?;
+
+        match self.upper.read_stream(args).await {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                if self.is_whiteout(args.path()).await? {
+                    return Err(new_not_found_error(Operation::Read, args.path()));
+                }
+                self.lower.read_stream(args).await
+            }
+            v => v,
+        }
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        check_path(Operation::Write, args.path())?;
+
+        let n = self.upper.write_stream(args, s).await?;
+        self.remove_whiteout(args.path()).await?;
+        Ok(n)
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        check_path(Operation::Stat, args.path())?;
+
+        match self.upper.stat(args).await {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                if self.is_whiteout(args.path()).await? {
+                    return Err(new_not_found_error(Operation::Stat, args.path()));
+                }
+                self.lower.stat(args).await
+            }
+            v => v,
+        }
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        check_path(Operation::Delete, args.path())?;
+
+        if args.path().ends_with('/') {
+            self.upper
+                .delete(&OpDelete::new(&opaque_path(args.path()))?)
+                .await?;
+        }
+        self.upper.delete(args).await?;
+        self.add_whiteout(args.path()).await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        check_path(Operation::List, args.path())?;
+
+        let mut entries = Vec::new();
+        let mut hidden = HashSet::new();
+
+        let upper_found = match self.upper.list(args).await {
+            Ok(mut ds) => {
+                while let Some(de) = ds.try_next().await? {
+                    self.merge_upper_entry(de, &mut entries, &mut hidden);
+                }
+                true
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => false,
+            Err(err) => return Err(err),
+        };
+
+        // Entries in lower service will be hidden if this dir has been deleted.
+        if self.is_whiteout(args.path()).await? {
+            return Ok(Box::new(stream::iter(entries.into_iter().map(Ok))));
+        }
+
+        let ds = match self.lower.list(args).await {
+            Ok(ds) => ds,
+            Err(err) if err.kind() == ErrorKind::NotFound && upper_found => {
+                return Ok(Box::new(stream::iter(entries.into_iter().map(Ok))))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        let ds = ds
+            .try_filter_map(move |mut de| {
+                let visible = !hidden.contains(de.path().trim_end_matches('/'));
+                if visible {
+                    de.set_accessor(this.clone());
+                }
+                futures::future::ready(Ok(visible.then_some(de)))
+            })
+            .boxed();
+        Ok(Box::new(
+            stream::iter(entries.into_iter().map(Ok)).chain(ds),
+        ))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        check_path(Operation::Presign, args.path())?;
+
+        match args.operation() {
+            // Object could be in either upper or lower service, we can't
+            // decide which one to presign without sending requests.
+            PresignOperation::Read(_) => Err(ObjectError::new(
+                Operation::Presign,
+                args.path(),
+                anyhow!("presign read is not supported by overlay"),
+            )
+            .with_kind(ObjectErrorKind::Unsupported)
+            .into()),
+            _ => self.upper.presign(args),
+        }
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        check_path(Operation::CreateMultipart, args.path())?;
+
+        self.upper.create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        check_path(Operation::WriteMultipart, args.path())?;
+
+        self.upper.write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        check_path(Operation::CompleteMultipart, args.path())?;
+
+        self.upper.complete_multipart(args).await?;
+        self.remove_whiteout(args.path()).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        check_path(Operation::AbortMultipart, args.path())?;
+
+        self.upper.abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        check_path(Operation::BlockingCreate, args.path())?;
+
+        self.upper.blocking_create(args)?;
+        if args.path().ends_with('/') {
+            self.blocking_add_opaque(args.path())?;
+        }
+        self.blocking_remove_whiteout(args.path())
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        check_path(Operation::BlockingRead, args.path())?;
+
+        match self.upper.blocking_read(args) {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                if self.blocking_is_whiteout(args.path())? {
+                    return Err(new_not_found_error(Operation::BlockingRead, args.path()));
+                }
+                self.lower.blocking_read(args)
+            }
+            v => v,
+        }
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        check_path(Operation::BlockingWrite, args.path())?;
+
+        let n = self.upper.blocking_write(args, r)?;
+        self.blocking_remove_whiteout(args.path())?;
+        Ok(n)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        check_path(Operation::BlockingStat, args.path())?;
+
+        match self.upper.blocking_stat(args) {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                if self.blocking_is_whiteout(args.path())? {
+                    return Err(new_not_found_error(Operation::BlockingStat, args.path()));
+                }
+                self.lower.blocking_stat(args)
+            }
+            v => v,
+        }
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        check_path(Operation::BlockingDelete, args.path())?;
+
+        if args.path().ends_with('/') {
+            self.upper
+                .blocking_delete(&OpDelete::new(&opaque_path(args.path()))?)?;
+        }
+        self.upper.blocking_delete(args)?;
+        self.blocking_add_whiteout(args.path())
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        check_path(Operation::BlockingList, args.path())?;
+
+        let mut entries = Vec::new();
+        let mut hidden = HashSet::new();
+
+        let upper_found = match self.upper.blocking_list(args) {
+            Ok(it) => {
+                for de in it {
+                    self.merge_upper_entry(de?, &mut entries, &mut hidden);
+                }
+                true
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => false,
+            Err(err) => return Err(err),
+        };
+
+        if self.blocking_is_whiteout(args.path())? {
+            return Ok(Box::new(entries.into_iter().map(Ok)));
+        }
+
+        let it = match self.lower.blocking_list(args) {
+            Ok(it) => it,
+            Err(err) if err.kind() == ErrorKind::NotFound && upper_found => {
+                return Ok(Box::new(entries.into_iter().map(Ok)))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let this: Arc<dyn Accessor> = Arc::new(self.clone());
+        let it = it.filter_map(move |de| match de {
+            Ok(mut de) => {
+                if hidden.contains(de.path().trim_end_matches('/')) {
+                    return None;
+                }
+                de.set_accessor(this.clone());
+                Some(Ok(de))
+            }
+            Err(err) => Some(Err(err)),
+        });
+        Ok(Box::new(entries.into_iter().map(Ok).chain(it)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::memory;
+
+    #[test]
+    fn test_whiteout_path() {
+        let cases = vec![
+            ("file", Some(".wh.file")),
+            ("dir/file", Some("dir/.wh.file")),
+            ("dir/sub/", Some("dir/.wh.sub")),
+            ("dir/", Some(".wh.dir")),
+            ("/", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(whiteout_path(input).as_deref(), expected, "input: {input}");
+        }
+
+        assert_eq!(
+            whiteout_paths("dir/sub/file"),
+            vec![
+                "dir/sub/.wh.file",
+                "dir/sub/.wh..wh..opq",
+                "dir/.wh.sub",
+                "dir/.wh..wh..opq",
+                ".wh.dir"
+            ]
+        );
+        assert_eq!(whiteout_paths("dir/"), vec!["dir/.wh..wh..opq", ".wh.dir"]);
+        assert!(whiteout_paths("/").is_empty());
+
+        let cases = vec![
+            (".wh.file", Some("file")),
+            ("dir/.wh.sub", Some("dir/sub")),
+            ("dir/file", None),
+            ("dir/.wh.sub/", None),
+            ("dir/.wh.", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                whiteout_target(input).as_deref(),
+                expected,
+                "input: {input}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlay() -> anyhow::Result<()> {
+        let lower = Operator::new(memory::Builder::default().build()?);
+        let upper = Operator::new(memory::Builder::default().build()?);
+        lower.object("a").write("lower a").await?;
+        lower.object("dir/b").write("lower b").await?;
+        lower.object("dir/c").write("lower c").await?;
+
+        let mut builder = Builder::default();
+        builder.lower(lower.clone()).upper(upper.clone());
+        let op = Operator::new(builder.build()?);
+
+        // Reads fall through to lower.
+        assert_eq!(op.object("a").read().await?, b"lower a");
+
+        // Writes go to upper.
+        op.object("a").write("upper a").await?;
+        assert_eq!(op.object("a").read().await?, b"upper a");
+        assert_eq!(lower.object("a").read().await?, b"lower a");
+
+        // Deletes are recorded as whiteouts.
+        op.object("dir/b").delete().await?;
+        let err = op
+            .object("dir/b")
+            .read()
+            .await
+            .expect_err("must be deleted");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(upper.object("dir/.wh.b").is_exist().await?);
+        assert_eq!(lower.object("dir/b").read().await?, b"lower b");
+
+        let mut paths: Vec<String> = op
+            .object("dir/")
+            .list()
+            .await?
+            .map_ok(|de| de.path().to_string())
+            .try_collect()
+            .await?;
+        paths.sort();
+        assert_eq!(paths, vec!["dir/c"]);
+
+        // Write again will remove the whiteout.
+        op.object("dir/b").write("upper b").await?;
+        assert_eq!(op.object("dir/b").read().await?, b"upper b");
+        assert!(!upper.object("dir/.wh.b").is_exist().await?);
+
+        // Entries in upper take precedence.
+        let mut entries = op.object("/").list().await?;
+        let mut count = 0;
+        while let Some(de) = entries.try_next().await? {
+            count += 1;
+            if de.path() == "a" {
+                assert_eq!(de.into_object().read().await?, b"upper a");
+            }
+        }
+        assert_eq!(count, 2, "only a and dir/ should be listed");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_overlay_delete_dir() -> anyhow::Result<()> {
+        let lower = Operator::new(memory::Builder::default().build()?);
+        let upper = Operator::new(memory::Builder::default().build()?);
+        lower.object("dir/b").write("lower b").await?;
+        lower.object("dir/sub/c").write("lower c").await?;
+
+        let mut builder = Builder::default();
+        builder.lower(lower.clone()).upper(upper.clone());
+        let op = Operator::new(builder.build()?);
+
+        op.object("dir/").delete().await?;
+        assert!(upper.object(".wh.dir").is_exist().await?);
+
+        // Objects under a deleted dir should be hidden.
+        for path in ["dir/b", "dir/sub/c"] {
+            let err = op
+                .object(path)
+                .metadata()
+                .await
+                .expect_err("must be deleted");
+            assert_eq!(err.kind(), ErrorKind::NotFound, "stat {path}");
+        }
+        let err = op
+            .object("dir/b")
+            .read()
+            .await
+            .expect_err("must be deleted");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        let entries: Vec<DirEntry> = op.object("dir/sub/").list().await?.try_collect().await?;
+        assert!(entries.is_empty());
+
+        // New objects under the deleted dir are visible.
+        op.object("dir/b").write("upper b").await?;
+        assert_eq!(op.object("dir/b").read().await?, b"upper b");
+        assert!(!op.object("dir/sub/c").is_exist().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_overlay_recreate_dir() -> anyhow::Result<()> {
+        let lower = Operator::new(memory::Builder::default().build()?);
+        let upper = Operator::new(memory::Builder::default().build()?);
+        lower.object("dir/b").write("lower b").await?;
+        lower.object("dir/sub/c").write("lower c").await?;
+
+        let mut builder = Builder::default();
+        builder.lower(lower.clone()).upper(upper.clone());
+        let op = Operator::new(builder.build()?);
+
+        op.object("dir/").delete().await?;
+        op.object("dir/").create().await?;
+        assert!(!upper.object(".wh.dir").is_exist().await?);
+        assert!(upper.object("dir/.wh..wh..opq").is_exist().await?);
+
+        // Recreated dir is opaque, entries in lower are still hidden.
+        assert!(op.object("dir/").is_exist().await?);
+        for path in ["dir/b", "dir/sub/c"] {
+            assert!(!op.object(path).is_exist().await?, "stat {path}");
+        }
+        let entries: Vec<DirEntry> = op.object("dir/").list().await?.try_collect().await?;
+        assert!(entries.is_empty());
+
+        op.object("dir/d").write("upper d").await?;
+        let paths: Vec<String> = op
+            .object("dir/")
+            .list()
+            .await?
+            .map_ok(|de| de.path().to_string())
+            .try_collect()
+            .await?;
+        assert_eq!(paths, vec!["dir/d"]);
+
+        // Names of whiteouts are reserved.
+        for path in [".wh.a", "dir/.wh.b", "dir/.wh..wh..opq"] {
+            let err = op
+                .object(path)
+                .write("x")
+                .await
+                .expect_err("must be rejected");
+            assert_eq!(err.kind(), ErrorKind::Unsupported, "write {path}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Overlay service that stacks a writable upper service on top of a
+//! read-only lower service.
+//!
+//! # Behavior
+//!
+//! - Reads will try the upper service first and fall through to the lower.
+//! - Writes will always go to the upper service, the lower service will
+//!   never be changed.
+//! - Deletes will remove the object from the upper service and record a
+//!   whiteout object in the upper service if the lower one has this object.
+//! - Lists will merge entries from both services, entries in the upper
+//!   service take precedence.
+//!
+//! Whiteouts are stored as empty objects named `.wh.<name>` in the same dir
+//! of the upper service, so they persist as long as the upper service does.
+//! A whiteout dir hides all objects under it in the lower service, so
+//! falling through to the lower service will check whiteouts of every
+//! parent dir.
+//!
+//! A dir created again after being deleted will be marked as opaque by an
+//! empty object named `.wh..wh..opq` inside it, so objects under it in the
+//! lower service stay hidden. Names starting with `.wh.` are reserved, and
+//! operations on them will return [`std::io::ErrorKind::Unsupported`].
+//!
+//! # Configuration
+//!
+//! Overlay can't be built from env, please use [`Builder`] instead.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use anyhow::Result;
+//! use opendal::services::memory;
+//! use opendal::services::overlay;
+//! use opendal::Operator;
+//! use opendal::Scheme;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<()> {
+//!     let lower = Operator::from_env(Scheme::S3)?;
+//!     let upper = Operator::new(memory::Builder::default().build()?);
+//!
+//!     let mut builder = overlay::Builder::default();
+//!     builder.lower(lower).upper(upper);
+//!     let op = Operator::new(builder.build()?);
+//!
+//!     // Changes will only be visible in this overlay.
+//!     op.object("fixtures/data.csv").write("a,b,c").await?;
+//!     op.object("fixtures/old.csv").delete().await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+mod backend;
+pub use backend::Backend;
+pub use backend::Builder;
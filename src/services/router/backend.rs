@@ -0,0 +1,653 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use flagset::FlagSet;
+use futures::stream;
+use futures::StreamExt;
+use log::info;
+
+use crate::accessor::AccessorCapability;
+use crate::error::other;
+use crate::error::BackendError;
+use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpRename;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::path::normalize_path;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::DirEntry;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::ObjectMetadata;
+use crate::ObjectMode;
+use crate::ObjectPart;
+use crate::Operator;
+use crate::Scheme;
+
+/// Builder for router service.
+#[derive(Default, Debug)]
+pub struct Builder {
+    mounts: Vec<(String, Operator)>,
+}
+
+impl Builder {
+    /// Mount operator at path.
+    ///
+    /// Path will be normalized into dir path like `data/hot/`. Mount at `/`
+    /// to serve all paths that not matched by other mount points.
+    pub fn mount(&mut self, path: &str, op: Operator) -> &mut Self {
+        let mut path = normalize_path(path);
+        if !path.ends_with('/') {
+            path.push('/');
+        }
+        self.mounts.push((path, op));
+
+        self
+    }
+
+    /// Consume builder to build a router service.
+    pub fn build(&mut self) -> Result<Backend> {
+        info!("backend build started: {:?}", &self);
+
+        if self.mounts.is_empty() {
+            return Err(other(BackendError::new(
+                HashMap::new(),
+                anyhow!("mount table is empty"),
+            )));
+        }
+
+        let mut mounts: Vec<(String, Arc<dyn Accessor>)> = Vec::with_capacity(self.mounts.len());
+        for (path, op) in self.mounts.drain(..) {
+            if mounts.iter().any(|(v, _)| v == &path) {
+                return Err(other(BackendError::new(
+                    HashMap::from([("mount".to_string(), path.clone())]),
+                    anyhow!("path has been mounted"),
+                )));
+            }
+            mounts.push((path, op.inner()));
+        }
+        // Sort by length in descending order so that we can find the
+        // longest matched mount point first.
+        mounts.sort_by_key(|(path, _)| Reverse(path.len()));
+
+        info!("backend build finished: {:?}", &self);
+        Ok(Backend {
+            mounts: Arc::new(mounts),
+        })
+    }
+}
+
+/// Backend for router service.
+#[derive(Debug, Clone)]
+pub struct Backend {
+    /// Mount points sorted by length in descending order.
+    mounts: Arc<Vec<(String, Arc<dyn Accessor>)>>,
+}
+
+impl Backend {
+    /// Find the mount point that serves this path.
+    ///
+    /// Returns the mount path, the accessor and the relative path in accessor.
+    fn matched(&self, path: &str) -> Option<(&str, &Arc<dyn Accessor>, String)> {
+        self.mounts.iter().find_map(|(mount, acc)| {
+            if mount == "/" {
+                return Some((mount.as_str(), acc, path.to_string()));
+            }
+
+            path.strip_prefix(mount.as_str()).map(|rel| {
+                let rel = if rel.is_empty() { "/" } else { rel };
+                (mount.as_str(), acc, rel.to_string())
+            })
+        })
+    }
+
+    /// Route the path to the mounted accessor, returns error if the path
+    /// is not mounted or contains other mount points.
+    fn route(&self, op: Operation, path: &str) -> Result<(Arc<dyn Accessor>, String)> {
+        if self.is_mount_parent(path) {
+            return Err(ObjectError::new(
+                op,
+                path,
+                anyhow!("operation across mount points is not supported"),
+            )
+            .with_kind(ObjectErrorKind::Unsupported)
+            .into());
+        }
+
+        match self.matched(path) {
+            Some((_, acc, rel)) => Ok((acc.clone(), rel)),
+            None => Err(ObjectError::new(op, path, anyhow!("path is not mounted"))
+                .with_kind(ObjectErrorKind::ObjectNotFound)
+                .into()),
+        }
+    }
+
+    /// Route both paths of rename, returns error if they are not served by
+    /// the same mount point.
+    fn route_rename(
+        &self,
+        op: Operation,
+        args: &OpRename,
+    ) -> Result<(Arc<dyn Accessor>, OpRename)> {
+        let (acc, from) = self.route(op, args.from())?;
+        let (to_acc, to) = self.route(op, args.to())?;
+        if !Arc::ptr_eq(&acc, &to_acc) {
+            return Err(ObjectError::new(
+                op,
+                args.from(),
+                anyhow!("rename across mount points is not supported"),
+            )
+            .with_kind(ObjectErrorKind::Unsupported)
+            .into());
+        }
+
+        Ok((acc, OpRename::new(&from, &to)?))
+    }
+
+    /// Check if this path is a mount point or parent dir of mount points.
+    ///
+    /// Root is only a parent dir if it's not mounted.
+    fn is_mount_parent(&self, path: &str) -> bool {
+        if path == "/" {
+            return !self.mounts.iter().any(|(mount, _)| mount == "/");
+        }
+
+        path.ends_with('/')
+            && self
+                .mounts
+                .iter()
+                .any(|(mount, _)| mount != "/" && mount.starts_with(path))
+    }
+
+    /// Returns the child dirs of path that lead to mount points.
+    fn mount_children(&self, path: &str) -> BTreeSet<String> {
+        let path = if path == "/" { "" } else { path };
+
+        self.mounts
+            .iter()
+            .filter(|(mount, _)| mount != "/" && mount.len() > path.len())
+            .filter_map(|(mount, _)| mount.strip_prefix(path))
+            .map(|rest| {
+                let idx = rest.find('/').expect("mount path must be dir");
+                format!("{path}{}", &rest[..=idx])
+            })
+            .collect()
+    }
+
+    /// Convert entry returned by mounted accessor into router's entry.
+    fn mounted_entry(&self, mount: &str, de: DirEntry) -> DirEntry {
+        let path = if mount == "/" {
+            de.path().to_string()
+        } else {
+            format!("{mount}{}", de.path())
+        };
+
+        let mut entry = DirEntry::new(Arc::new(self.clone()), de.mode(), &path);
+        if let Some(v) = de.content_length() {
+            entry.set_content_length(v);
+        }
+        if let Some(v) = de.content_md5() {
+            entry.set_content_md5(v);
+        }
+        if let Some(v) = de.last_modified() {
+            entry.set_last_modified(v);
+        }
+        if let Some(v) = de.etag() {
+            entry.set_etag(v);
+        }
+        entry
+    }
+
+    /// Virtual dir entries of mount points under this path, which are not
+    /// returned by mounted accessor.
+    fn virtual_entries(&self, path: &str) -> Vec<DirEntry> {
+        self.mount_children(path)
+            .into_iter()
+            .map(|p| DirEntry::new(Arc::new(self.clone()), ObjectMode::DIR, &p))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Accessor for Backend {
+    fn metadata(&self) -> AccessorMetadata {
+        // Only capabilities supported by all mounts are available.
+        let capabilities = self
+            .mounts
+            .iter()
+            .fold(FlagSet::<AccessorCapability>::full(), |caps, (_, acc)| {
+                caps & acc.metadata().capabilities()
+            });
+
+        let mut am = AccessorMetadata::default();
+        am.set_scheme(Scheme::Router)
+            .set_root("/")
+            .set_name("router")
+            .set_capabilities(capabilities);
+
+        am
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        // Parent dirs of mount points always exist.
+        if self.is_mount_parent(args.path()) {
+            return Ok(());
+        }
+
+        let (acc, path) = self.route(Operation::Create, args.path())?;
+        acc.create(&args.clone().with_path(&path)).await
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let (acc, path) = self.route(Operation::Read, args.path())?;
+        acc.read(&args.clone().with_path(&path)).await
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        let (acc, path) = self.route(Operation::Write, args.path())?;
+        acc.write(&args.clone().with_path(&path), r).await
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
+        let (acc, path) = self.route(Operation::Read, args.path())?;
+        acc.read_stream(&args.clone().with_path(&path)).await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
+        let (acc, path) = self.route(Operation::Write, args.path())?;
+        acc.write_stream(&args.clone().with_path(&path), s).await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        if self.is_mount_parent(args.path()) {
+            let mut meta = ObjectMetadata::default();
+            meta.set_mode(ObjectMode::DIR);
+            return Ok(meta);
+        }
+
+        let (acc, path) = self.route(Operation::Stat, args.path())?;
+        acc.stat(&args.clone().with_path(&path)).await
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        let (acc, path) = self.route(Operation::Delete, args.path())?;
+        acc.delete(&args.clone().with_path(&path)).await
+    }
+
+    async fn rename(&self, args: &OpRename) -> Result<()> {
+        let (acc, args) = self.route_rename(Operation::Rename, args)?;
+        acc.rename(&args).await
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let virtual_entries = self.virtual_entries(args.path());
+
+        let matched = match self.matched(args.path()) {
+            Some(v) => v,
+            None if virtual_entries.is_empty() => {
+                return Err(ObjectError::new(
+                    Operation::List,
+                    args.path(),
+                    anyhow!("path is not mounted"),
+                )
+                .with_kind(ObjectErrorKind::ObjectNotFound)
+                .into())
+            }
+            None => return Ok(Box::new(stream::iter(virtual_entries.into_iter().map(Ok)))),
+        };
+        let (mount, acc, path) = matched;
+        let mount = mount.to_string();
+
+        let ds = match acc.list(&args.clone().with_path(&path)).await {
+            Ok(ds) => ds,
+            // The dir could be not exist in the mounted accessor.
+            Err(err) if err.kind() == ErrorKind::NotFound && !virtual_entries.is_empty() => {
+                return Ok(Box::new(stream::iter(virtual_entries.into_iter().map(Ok))))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let this = self.clone();
+        let virtual_paths: BTreeSet<String> = virtual_entries
+            .iter()
+            .map(|v| v.path().to_string())
+            .collect();
+        let ds = ds
+            .map(move |de| de.map(|de| this.mounted_entry(&mount, de)))
+            .filter(move |de| {
+                // Mount points will shadow the dirs in mounted accessor.
+                let shadowed = matches!(de, Ok(de) if virtual_paths.contains(de.path()));
+                futures::future::ready(!shadowed)
+            })
+            .chain(stream::iter(virtual_entries.into_iter().map(Ok)));
+        Ok(Box::new(ds))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        let (acc, path) = self.route(Operation::Presign, args.path())?;
+        acc.presign(&args.clone().with_path(&path))
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        let (acc, path) = self.route(Operation::CreateMultipart, args.path())?;
+        acc.create_multipart(&args.clone().with_path(&path)).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        let (acc, path) = self.route(Operation::WriteMultipart, args.path())?;
+        acc.write_multipart(&args.clone().with_path(&path), r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        let (acc, path) = self.route(Operation::CompleteMultipart, args.path())?;
+        acc.complete_multipart(&args.clone().with_path(&path)).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        let (acc, path) = self.route(Operation::AbortMultipart, args.path())?;
+        acc.abort_multipart(&args.clone().with_path(&path)).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        if self.is_mount_parent(args.path()) {
+            return Ok(());
+        }
+
+        let (acc, path) = self.route(Operation::BlockingCreate, args.path())?;
+        acc.blocking_create(&args.clone().with_path(&path))
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        let (acc, path) = self.route(Operation::BlockingRead, args.path())?;
+        acc.blocking_read(&args.clone().with_path(&path))
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let (acc, path) = self.route(Operation::BlockingWrite, args.path())?;
+        acc.blocking_write(&args.clone().with_path(&path), r)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        if self.is_mount_parent(args.path()) {
+            let mut meta = ObjectMetadata::default();
+            meta.set_mode(ObjectMode::DIR);
+            return Ok(meta);
+        }
+
+        let (acc, path) = self.route(Operation::BlockingStat, args.path())?;
+        acc.blocking_stat(&args.clone().with_path(&path))
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        let (acc, path) = self.route(Operation::BlockingDelete, args.path())?;
+        acc.blocking_delete(&args.clone().with_path(&path))
+    }
+
+    fn blocking_rename(&self, args: &OpRename) -> Result<()> {
+        let (acc, args) = self.route_rename(Operation::BlockingRename, args)?;
+        acc.blocking_rename(&args)
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let virtual_entries = self.virtual_entries(args.path());
+
+        let (mount, acc, path) = match self.matched(args.path()) {
+            Some(v) => v,
+            None if virtual_entries.is_empty() => {
+                return Err(ObjectError::new(
+                    Operation::BlockingList,
+                    args.path(),
+                    anyhow!("path is not mounted"),
+                )
+                .with_kind(ObjectErrorKind::ObjectNotFound)
+                .into())
+            }
+            None => return Ok(Box::new(virtual_entries.into_iter().map(Ok))),
+        };
+        let mount = mount.to_string();
+
+        let it = match acc.blocking_list(&args.clone().with_path(&path)) {
+            Ok(it) => it,
+            Err(err) if err.kind() == ErrorKind::NotFound && !virtual_entries.is_empty() => {
+                return Ok(Box::new(virtual_entries.into_iter().map(Ok)))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let this = self.clone();
+        let virtual_paths: BTreeSet<String> = virtual_entries
+            .iter()
+            .map(|v| v.path().to_string())
+            .collect();
+        let it = it
+            .map(move |de| de.map(|de| this.mounted_entry(&mount, de)))
+            .filter(move |de| !matches!(de, Ok(de) if virtual_paths.contains(de.path())))
+            .chain(virtual_entries.into_iter().map(Ok));
+        Ok(Box::new(it))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::services::fs;
+    use crate::services::memory;
+
+    fn new_memory() -> Result<Operator> {
+        Ok(Operator::new(memory::Builder::default().build()?))
+    }
+
+    async fn list_paths(op: &Operator, path: &str) -> Result<Vec<String>> {
+        let mut paths: Vec<String> = op
+            .object(path)
+            .list()
+            .await?
+            .map_ok(|de| de.path().to_string())
+            .try_collect()
+            .await?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    #[test]
+    fn test_mount_children() -> Result<()> {
+        let mut builder = Builder::default();
+        builder
+            .mount("/", new_memory()?)
+            .mount("data/hot", new_memory()?)
+            .mount("data/archive/old/", new_memory()?)
+            .mount("tmp/", new_memory()?);
+        let backend = builder.build()?;
+
+        assert_eq!(
+            backend.mount_children("/").into_iter().collect::<Vec<_>>(),
+            vec!["data/", "tmp/"]
+        );
+        assert_eq!(
+            backend
+                .mount_children("data/")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["data/archive/", "data/hot/"]
+        );
+        assert!(backend.mount_children("tmp/").is_empty());
+
+        let (mount, _, path) = backend.matched("data/hot/file").expect("must match");
+        assert_eq!((mount, path.as_str()), ("data/hot/", "file"));
+        let (mount, _, path) = backend.matched("data/file").expect("must match");
+        assert_eq!((mount, path.as_str()), ("/", "data/file"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_router() -> Result<()> {
+        let hot = new_memory()?;
+        let archive = new_memory()?;
+
+        let mut builder = Builder::default();
+        builder
+            .mount("data/hot/", hot.clone())
+            .mount("data/archive/", archive.clone());
+        let op = Operator::new(builder.build()?);
+
+        op.object("data/hot/a").write("hot").await?;
+        op.object("data/archive/b").write("archive").await?;
+        assert_eq!(hot.object("a").read().await?, b"hot");
+        assert_eq!(archive.object("b").read().await?, b"archive");
+
+        assert_eq!(list_paths(&op, "/").await?, vec!["data/"]);
+        assert_eq!(
+            list_paths(&op, "data/").await?,
+            vec!["data/archive/", "data/hot/"]
+        );
+        assert_eq!(list_paths(&op, "data/hot/").await?, vec!["data/hot/a"]);
+
+        // Entries returned by list should go through router.
+        let de = op
+            .object("data/hot/")
+            .list()
+            .await?
+            .try_next()
+            .await?
+            .expect("must have entry");
+        assert_eq!(de.into_object().read().await?, b"hot");
+
+        assert!(op.object("data/").metadata().await?.mode().is_dir());
+
+        let err = op
+            .object("data/")
+            .delete()
+            .await
+            .expect_err("cross mount delete must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        let err = op
+            .object("other")
+            .write("x")
+            .await
+            .expect_err("write to unmounted path must fail");
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_router_with_root_mount() -> Result<()> {
+        let root = new_memory()?;
+        let hot = new_memory()?;
+
+        let mut builder = Builder::default();
+        builder.mount("/", root.clone()).mount("data/hot/", hot);
+        let op = Operator::new(builder.build()?);
+
+        op.object("data/cold").write("cold").await?;
+        op.object("data/hot/a").write("hot").await?;
+        assert_eq!(root.object("data/cold").read().await?, b"cold");
+
+        assert_eq!(
+            list_paths(&op, "data/").await?,
+            vec!["data/cold", "data/hot/"]
+        );
+
+        // Root should be served by the root mount.
+        op.object("/").create().await?;
+        op.object("/").delete().await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_router_capabilities() -> Result<()> {
+        let root = format!("/tmp/{}/", uuid::Uuid::new_v4());
+
+        let mut builder = Builder::default();
+        builder
+            .mount(
+                "data/hot/",
+                Operator::new(fs::Builder::default().root(&root).build()?),
+            )
+            .mount("data/archive/", new_memory()?);
+        let op = Operator::new(builder.build()?);
+
+        // Only capabilities supported by all mounts are available.
+        let meta = op.metadata();
+        assert!(meta.can_read() && meta.can_write() && meta.can_list());
+        assert!(!meta.can_blocking());
+        assert!(!meta.can_rename());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_router_rename() -> Result<()> {
+        let root = format!("/tmp/{}/", uuid::Uuid::new_v4());
+        let hot = Operator::new(fs::Builder::default().root(&format!("{root}hot")).build()?);
+        let archive = Operator::new(
+            fs::Builder::default()
+                .root(&format!("{root}archive"))
+                .build()?,
+        );
+
+        let mut builder = Builder::default();
+        builder
+            .mount("data/hot/", hot.clone())
+            .mount("data/archive/", archive.clone());
+        let op = Operator::new(builder.build()?);
+        assert!(op.metadata().can_rename());
+
+        op.object("data/hot/a").write("hot").await?;
+        op.object("data/hot/a").rename("data/hot/b").await?;
+        assert_eq!(hot.object("b").read().await?, b"hot");
+        assert!(!hot.object("a").is_exist().await?);
+
+        let err = op
+            .object("data/hot/b")
+            .rename("data/archive/b")
+            .await
+            .expect_err("cross mount rename must fail");
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert!(hot.object("b").is_exist().await?);
+
+        hot.batch().remove_all("/").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Router service that dispatches paths to mounted services.
+//!
+//! Router presents a unified namespace over multiple operators. Every
+//! operator is mounted at a path prefix, and operations will be dispatched
+//! to the operator with the longest matched prefix.
+//!
+//! # Behavior
+//!
+//! - Operators mounted at `/` will serve all paths that not matched by
+//!   other mount points.
+//! - Parent dirs of mount points will be listed and stat as dirs.
+//! - Operations across mount points like deleting the parent dir of mount
+//!   points will return `Unsupported` errors.
+//! - Operations on paths that not mounted will return `NotFound` errors.
+//!
+//! # Configuration
+//!
+//! Router can't be built from env, please use [`Builder`] instead.
+//!
+//! # Example
+//!
+//! ```
+//! use anyhow::Result;
+//! use opendal::services::memory;
+//! use opendal::services::router;
+//! use opendal::Operator;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<()> {
+//!     let hot = Operator::new(memory::Builder::default().build()?);
+//!     let archive = Operator::new(memory::Builder::default().build()?);
+//!
+//!     let mut builder = router::Builder::default();
+//!     builder.mount("data/hot/", hot).mount("data/archive/", archive);
+//!     let op = Operator::new(builder.build()?);
+//!
+//!     // Write into the `hot` operator with path `file`.
+//!     op.object("data/hot/file").write("hello").await?;
+//!     // List `data/` will return mount points `hot/` and `archive/`.
+//!     let _ = op.object("data/").list().await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+mod backend;
+pub use backend::Backend;
+pub use backend::Builder;
@@ -46,6 +46,7 @@ use crate::accessor::AccessorCapability;
 use crate::error::other;
 use crate::error::BackendError;
 use crate::error::ObjectError;
+use crate::http_util::insert_header;
 use crate::http_util::new_request_build_error;
 use crate::http_util::new_request_send_error;
 use crate::http_util::new_request_sign_error;
@@ -53,11 +54,14 @@ use crate::http_util::new_response_consume_error;
 use crate::http_util::parse_content_length;
 use crate::http_util::parse_error_response;
 use crate::http_util::parse_etag;
+use crate::http_util::parse_header_to_str;
 use crate::http_util::parse_last_modified;
 use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::Body;
 use crate::http_util::HttpClient;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::multipart::ObjectPart;
 use crate::ops::BytesRange;
 use crate::ops::OpAbortMultipart;
@@ -79,6 +83,7 @@ use crate::path::normalize_root;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::DirStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
@@ -105,6 +110,10 @@ mod constants {
         "x-amz-server-side-encryption-customer-key-md5";
     pub const X_AMZ_SERVER_SIDE_ENCRYPTION_AWS_KMS_KEY_ID: &str =
         "x-amz-server-side-encryption-aws-kms-key-id";
+    pub const X_AMZ_CHECKSUM_MODE: &str = "x-amz-checksum-mode";
+    pub const X_AMZ_CHECKSUM_CRC32C: &str = "x-amz-checksum-crc32c";
+    pub const X_AMZ_CHECKSUM_SHA256: &str = "x-amz-checksum-sha256";
+    pub const CONTENT_MD5: &str = "content-md5";
 }
 
 /// Builder for s3 services
@@ -125,6 +134,8 @@ pub struct Builder {
 
     disable_credential_loader: bool,
     enable_virtual_host_style: bool,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -527,6 +538,15 @@ impl Builder {
         }
     }
 
+    /// Set http client used by this service.
+    ///
+    /// Default: a new [`HttpClient`](crate::HttpClient) with default configs.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+
+        self
+    }
+
     /// Finish the build process and create a new accessor.
     pub fn build(&mut self) -> Result<Backend> {
         info!("backend build started: {:?}", &self);
@@ -616,7 +636,7 @@ impl Builder {
                 })?),
             };
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         let (mut endpoint, region) = self.detect_region(&client, bucket, &context)?;
         // Construct endpoint which contains bucket name.
@@ -688,8 +708,12 @@ pub struct Backend {
 impl Backend {
     pub(crate) fn from_iter(it: impl Iterator<Item = (String, String)>) -> Result<Self> {
         let mut builder = Builder::default();
+        let mut http_client = HttpClient::builder();
 
         for (k, v) in it {
+            if http_client.parse_option(&k, &v)? {
+                continue;
+            }
             let v = v.as_str();
             match k.as_ref() {
                 "root" => builder.root(v),
@@ -717,6 +741,7 @@ impl Backend {
             };
         }
 
+        builder.http_client(http_client.build()?);
         builder.build()
     }
 
@@ -834,14 +859,22 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(args).await?;
+
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, args: &OpRead) -> Result<BytesStreamer> {
         let p = build_abs_path(&self.root, args.path());
 
-        let resp = self.get_object(&p, args.offset(), args.size()).await?;
+        let resp = self
+            .get_object(&p, args.offset(), args.size(), args.if_match())
+            .await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, args.path(), er);
@@ -851,9 +884,16 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(args, Box::new(into_stream(r, 8 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, args: &OpWrite, s: BytesStreamer) -> Result<u64> {
         let p = build_abs_path(&self.root, args.path());
 
-        let mut req = self.put_object_request(&p, Some(args.size()), AsyncBody::Reader(r))?;
+        let mut req = self.put_object_request(&p, Some(args.size()), AsyncBody::Stream(s))?;
+        self.insert_checksum_headers(&mut req, args)
+            .map_err(|e| new_request_build_error(Operation::Write, &p, e))?;
 
         self.signer
             .sign(&mut req)
@@ -912,7 +952,32 @@ impl Accessor for Backend {
                     .map_err(|e| other(ObjectError::new(Operation::Stat, &p, e)))?
                 {
                     m.set_etag(v);
-                    m.set_content_md5(v.trim_matches('"'));
+                    // ETag is the md5 of content only if the object is not
+                    // uploaded by multipart or encrypted by SSE-KMS/SSE-C.
+                    let headers = resp.headers();
+                    let kms = headers
+                        .get(constants::X_AMZ_SERVER_SIDE_ENCRYPTION)
+                        .map(|v| v.as_bytes().starts_with(b"aws:kms"))
+                        .unwrap_or_default();
+                    let ssec = headers
+                        .contains_key(constants::X_AMZ_SERVER_SIDE_ENCRYPTION_CUSTOMER_ALGORITHM);
+                    if !v.contains('-') && !kms && !ssec {
+                        m.set_content_md5(v.trim_matches('"'));
+                    }
+                }
+
+                if let Some(v) =
+                    parse_header_to_str(resp.headers(), constants::X_AMZ_CHECKSUM_CRC32C)
+                        .map_err(|e| other(ObjectError::new(Operation::Stat, &p, e)))?
+                {
+                    m.set_content_crc32c(v);
+                }
+
+                if let Some(v) =
+                    parse_header_to_str(resp.headers(), constants::X_AMZ_CHECKSUM_SHA256)
+                        .map_err(|e| other(ObjectError::new(Operation::Stat, &p, e)))?
+                {
+                    m.set_content_sha256(v);
                 }
 
                 if let Some(v) = parse_last_modified(resp.headers())
@@ -979,7 +1044,9 @@ impl Accessor for Backend {
 
         // We will not send this request out, just for signing.
         let mut req = match args.operation() {
-            PresignOperation::Read(v) => self.get_object_request(&path, v.offset(), v.size())?,
+            PresignOperation::Read(v) => {
+                self.get_object_request(&path, v.offset(), v.size(), v.if_match())?
+            }
             PresignOperation::Write(_) => self.put_object_request(&path, None, AsyncBody::Empty)?,
             PresignOperation::WriteMultipart(v) => self.s3_upload_part_request(
                 &path,
@@ -1144,6 +1211,7 @@ impl Backend {
         path: &str,
         offset: Option<u64>,
         size: Option<u64>,
+        if_match: Option<&str>,
     ) -> Result<Request<AsyncBody>> {
         let url = format!("{}/{}", self.endpoint, percent_encode_path(path));
 
@@ -1156,6 +1224,10 @@ impl Backend {
             );
         }
 
+        if let Some(etag) = if_match {
+            req = req.header(http::header::IF_MATCH, etag);
+        }
+
         // Set SSE headers.
         // TODO: how will this work with presign?
         req = self.insert_sse_headers(req, false);
@@ -1172,8 +1244,9 @@ impl Backend {
         path: &str,
         offset: Option<u64>,
         size: Option<u64>,
+        if_match: Option<&str>,
     ) -> Result<Response<AsyncBody>> {
-        let mut req = self.get_object_request(path, offset, size)?;
+        let mut req = self.get_object_request(path, offset, size, if_match)?;
 
         self.signer
             .sign(&mut req)
@@ -1210,6 +1283,24 @@ impl Backend {
         Ok(req)
     }
 
+    /// Set checksums of content so that s3 will reject corrupted content.
+    fn insert_checksum_headers(
+        &self,
+        req: &mut Request<AsyncBody>,
+        args: &OpWrite,
+    ) -> std::result::Result<(), http::Error> {
+        if let Some(v) = args.content_md5() {
+            insert_header(req, constants::CONTENT_MD5, v)?;
+        }
+        if let Some(v) = args.content_crc32c() {
+            insert_header(req, constants::X_AMZ_CHECKSUM_CRC32C, v)?;
+        }
+        if let Some(v) = args.content_sha256() {
+            insert_header(req, constants::X_AMZ_CHECKSUM_SHA256, v)?;
+        }
+        Ok(())
+    }
+
     async fn head_object(&self, path: &str) -> Result<Response<AsyncBody>> {
         let url = format!("{}/{}", self.endpoint, percent_encode_path(path));
 
@@ -1218,6 +1309,12 @@ impl Backend {
         // Set SSE headers.
         req = self.insert_sse_headers(req, false);
 
+        // Ask for additional checksums stored along with object.
+        req = req.header(
+            HeaderName::from_static(constants::X_AMZ_CHECKSUM_MODE),
+            "ENABLED",
+        );
+
         let mut req = req
             .body(AsyncBody::Empty)
             .map_err(|e| new_request_build_error(Operation::Stat, path, e))?;
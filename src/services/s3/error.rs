@@ -13,30 +13,140 @@
 // limitations under the License.
 
 use std::io::Error;
-use std::io::ErrorKind;
 
 use anyhow::anyhow;
-use http::StatusCode;
+use bytes::Buf;
+use quick_xml::de;
+use serde::Deserialize;
 
 use crate::error::ObjectError;
+use crate::error::ObjectErrorKind;
+use crate::http_util::parse_error_kind;
 use crate::http_util::ErrorResponse;
 use crate::ops::Operation;
 
-/// Parse error response into io::Error.
-///
-/// # TODO
+/// S3Error is the error returned by s3 service.
 ///
-/// In the future, we may have our own error struct.
+/// ref: https://docs.aws.amazon.com/AmazonS3/latest/API/ErrorResponses.html
+#[derive(Default, Debug, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct S3Error {
+    code: String,
+    message: String,
+    request_id: String,
+}
+
+/// Parse error response into io::Error.
 pub fn parse_error(op: Operation, path: &str, er: ErrorResponse) -> Error {
-    let kind = match er.status_code() {
-        StatusCode::NOT_FOUND => ErrorKind::NotFound,
-        StatusCode::FORBIDDEN => ErrorKind::PermissionDenied,
-        StatusCode::INTERNAL_SERVER_ERROR
-        | StatusCode::BAD_GATEWAY
-        | StatusCode::SERVICE_UNAVAILABLE
-        | StatusCode::GATEWAY_TIMEOUT => ErrorKind::Interrupted,
-        _ => ErrorKind::Other,
+    let mut err = ObjectError::new(op, path, anyhow!("{er}"))
+        .with_kind(parse_error_kind(er.status_code()))
+        .with_context("service", "s3");
+
+    // HEAD requests don't have response body, so we should always check the
+    // request id in headers first.
+    if let Some(v) = er
+        .headers()
+        .get("x-amz-request-id")
+        .and_then(|v| v.to_str().ok())
+    {
+        err = err.with_context("request_id", v);
+    }
+
+    let s3_err: S3Error = match de::from_reader(er.body().reader()) {
+        Ok(v) => v,
+        Err(_) => return err.into(),
+    };
+
+    err = match s3_err.code.as_str() {
+        "NoSuchKey" | "NoSuchBucket" => err.with_kind(ObjectErrorKind::ObjectNotFound),
+        "AccessDenied" => err.with_kind(ObjectErrorKind::PermissionDenied),
+        "PreconditionFailed" => err.with_kind(ObjectErrorKind::ConditionNotMatch),
+        "SlowDown" => err.with_kind(ObjectErrorKind::RateLimited),
+        "InternalError" | "ServiceUnavailable" => {
+            err.with_kind(ObjectErrorKind::ServiceUnavailable)
+        }
+        // S3 returns `400 Bad Request` for these errors, but they are
+        // expected to be fixed by retrying.
+        "RequestTimeout" | "OperationAborted" => err.with_temporary(true),
+        _ => err,
+    };
+    let request_id = match err.request_id() {
+        Some(v) => Some(v.to_string()),
+        None if !s3_err.request_id.is_empty() => Some(s3_err.request_id.clone()),
+        None => None,
     };
+    err = err.with_source(er.describe(&s3_err.code, &s3_err.message, request_id.as_deref()));
+    if !s3_err.code.is_empty() {
+        err = err.with_context("code", s3_err.code);
+    }
+    if !s3_err.message.is_empty() {
+        err = err.with_context("message", s3_err.message);
+    }
+    if !s3_err.request_id.is_empty() && err.request_id().is_none() {
+        err = err.with_context("request_id", s3_err.request_id);
+    }
+
+    err.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Response;
+    use http::StatusCode;
+
+    use super::*;
+    use crate::http_util::parse_error_response;
+    use crate::http_util::AsyncBody;
+
+    #[tokio::test]
+    async fn test_parse_error() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>SlowDown</Code>
+  <Message>Please reduce your request rate.</Message>
+  <Resource>/mybucket/myfoto.jpg</Resource>
+  <RequestId>4442587FB7D0A2F9</RequestId>
+</Error>"#;
+        let resp = Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .body(AsyncBody::Bytes(body.into()))
+            .expect("must build");
+        let er = parse_error_response(resp).await.expect("must parse");
+
+        let err = parse_error(Operation::Read, "myfoto.jpg", er);
+        assert_eq!(err.kind(), std::io::ErrorKind::Interrupted);
+
+        let oe = ObjectError::downcast(&err).expect("must be object error");
+        assert_eq!(oe.kind(), ObjectErrorKind::RateLimited);
+        assert!(oe.is_temporary());
+        assert_eq!(oe.service_code(), Some("SlowDown"));
+        assert_eq!(
+            oe.service_message(),
+            Some("Please reduce your request rate.")
+        );
+        assert_eq!(oe.request_id(), Some("4442587FB7D0A2F9"));
+
+        // Raw body should not be carried once parsed.
+        let source = std::error::Error::source(oe).expect("must have source");
+        assert!(!source.to_string().contains("<Error>"), "{source}");
+        assert!(source.to_string().contains("code: SlowDown"), "{source}");
+    }
+
+    #[tokio::test]
+    async fn test_parse_error_without_body() {
+        let resp = Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header("x-amz-request-id", "4442587FB7D0A2F9")
+            .body(AsyncBody::Empty)
+            .expect("must build");
+        let er = parse_error_response(resp).await.expect("must parse");
+
+        let err = parse_error(Operation::Stat, "myfoto.jpg", er);
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
 
-    Error::new(kind, ObjectError::new(op, path, anyhow!("{er}")))
+        let oe = ObjectError::downcast(&err).expect("must be object error");
+        assert_eq!(oe.kind(), ObjectErrorKind::ObjectNotFound);
+        assert_eq!(oe.service_code(), None);
+        assert_eq!(oe.request_id(), Some("4442587FB7D0A2F9"));
+    }
 }
@@ -0,0 +1,488 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use flagset::FlagSet;
+use futures::future::join_all;
+use futures::stream;
+use futures::TryStreamExt;
+use log::debug;
+use log::info;
+
+use crate::accessor::AccessorCapability;
+use crate::error::other;
+use crate::error::BackendError;
+use crate::io_util::TopDownWalker;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::DirEntry;
+use crate::DirIterator;
+use crate::DirStreamer;
+use crate::Object;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::Operator;
+use crate::Scheme;
+
+/// Builder for shard service.
+#[derive(Default, Debug)]
+pub struct Builder {
+    shards: Vec<(String, Operator)>,
+}
+
+impl Builder {
+    /// Add a shard with name.
+    ///
+    /// Paths are assigned by shard names, so the name of a shard must be
+    /// kept the same while adding or removing other shards.
+    pub fn shard(&mut self, name: &str, op: Operator) -> &mut Self {
+        self.shards.push((name.to_string(), op));
+
+        self
+    }
+
+    /// Consume builder to build a shard service.
+    pub fn build(&mut self) -> Result<Backend> {
+        info!("backend build started: {:?}", &self);
+
+        if self.shards.is_empty() {
+            return Err(other(BackendError::new(
+                HashMap::new(),
+                anyhow!("shards is empty"),
+            )));
+        }
+
+        let mut shards: Vec<(String, Arc<dyn Accessor>)> = Vec::with_capacity(self.shards.len());
+        for (name, op) in self.shards.drain(..) {
+            if shards.iter().any(|(v, _)| v == &name) {
+                return Err(other(BackendError::new(
+                    HashMap::from([("shard".to_string(), name.clone())]),
+                    anyhow!("shard name is duplicated"),
+                )));
+            }
+            shards.push((name, op.inner()));
+        }
+
+        info!("backend build finished: {:?}", &self);
+        Ok(Backend {
+            shards: Arc::new(shards),
+        })
+    }
+}
+
+/// Backend for shard service.
+#[derive(Debug, Clone)]
+pub struct Backend {
+    shards: Arc<Vec<(String, Arc<dyn Accessor>)>>,
+}
+
+impl Backend {
+    /// Returns the index of shard that path belongs to.
+    ///
+    /// Shard with the highest weight of `md5(name + path)` wins.
+    fn locate(&self, path: &str) -> usize {
+        self.shards
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, (name, _))| weight(name, path))
+            .map(|(idx, _)| idx)
+            .expect("shards must not be empty")
+    }
+
+    fn shard(&self, path: &str) -> &Arc<dyn Accessor> {
+        &self.shards[self.locate(path)].1
+    }
+
+    /// Merge entries listed from all shards in sorted order.
+    fn merge_entries(&self, results: Vec<Result<Vec<DirEntry>>>) -> Result<Vec<DirEntry>> {
+        let mut entries = BTreeMap::new();
+        let mut last_err = None;
+        let mut succeeded = false;
+        for res in results {
+            match res {
+                Ok(v) => {
+                    succeeded = true;
+                    for mut de in v {
+                        de.set_accessor(Arc::new(self.clone()));
+                        entries.entry(de.path().to_string()).or_insert(de);
+                    }
+                }
+                // Dir could be not exist in some shards.
+                Err(err) if err.kind() == ErrorKind::NotFound => last_err = Some(err),
+                Err(err) => return Err(err),
+            }
+        }
+
+        if !succeeded {
+            return Err(last_err.expect("error must be set"));
+        }
+        Ok(entries.into_values().collect())
+    }
+
+    /// Move files under path to the shards they belong to.
+    ///
+    /// This should be called after shards changed. Returns the count of
+    /// moved files.
+    pub async fn rebalance(&self, path: &str) -> Result<usize> {
+        let mut moved = 0;
+
+        for (idx, (name, acc)) in self.shards.iter().enumerate() {
+            let mut walker = TopDownWalker::new(Object::new(acc.clone(), path));
+            while let Some(de) = walker.try_next().await? {
+                if de.mode().is_dir() {
+                    continue;
+                }
+
+                let target = self.locate(de.path());
+                if target == idx {
+                    continue;
+                }
+
+                let (target_name, target_acc) = &self.shards[target];
+                let source = de.into_object();
+                let bs = source.read().await?;
+                Object::new(target_acc.clone(), source.path())
+                    .write(bs)
+                    .await?;
+                source.delete().await?;
+
+                debug!(
+                    "shard rebalance moved {} from {name} to {target_name}",
+                    source.path()
+                );
+                moved += 1;
+            }
+        }
+
+        Ok(moved)
+    }
+}
+
+/// Weight of path in shard for rendezvous hashing.
+fn weight(name: &str, path: &str) -> u64 {
+    let mut ctx = md5::Context::new();
+    ctx.consume(name.as_bytes());
+    ctx.consume(b"/");
+    ctx.consume(path.as_bytes());
+
+    let digest = ctx.compute();
+    let mut bs = [0; 8];
+    bs.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bs)
+}
+
+#[async_trait]
+impl Accessor for Backend {
+    fn metadata(&self) -> AccessorMetadata {
+        // Only capabilities supported by all shards are available, and
+        // rename is not supported since paths could be moved across shards.
+        let capabilities = self
+            .shards
+            .iter()
+            .fold(FlagSet::<AccessorCapability>::full(), |caps, (_, acc)| {
+                caps & acc.metadata().capabilities()
+            })
+            - AccessorCapability::Rename;
+
+        let mut am = AccessorMetadata::default();
+        am.set_scheme(Scheme::Shard)
+            .set_root("/")
+            .set_name("shard")
+            .set_capabilities(capabilities);
+
+        am
+    }
+
+    async fn create(&self, args: &OpCreate) -> Result<()> {
+        if args.mode().is_file() {
+            return self.shard(args.path()).create(args).await;
+        }
+
+        for res in join_all(self.shards.iter().map(|(_, acc)| acc.create(args))).await {
+            res?;
+        }
+        Ok(())
+    }
+
+    async fn read(&self, args: &OpRead) -> Result<BytesReader> {
+        self.shard(args.path()).read(args).await
+    }
+
+    async fn write(&self, args: &OpWrite, r: BytesReader) -> Result<u64> {
+        self.shard(args.path()).write(args, r).await
+    }
+
+    async fn stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        if !args.path().ends_with('/') {
+            return self.shard(args.path()).stat(args).await;
+        }
+
+        let mut last_err = None;
+        for res in join_all(self.shards.iter().map(|(_, acc)| acc.stat(args))).await {
+            match res {
+                Ok(meta) => return Ok(meta),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.expect("error must be set"))
+    }
+
+    async fn delete(&self, args: &OpDelete) -> Result<()> {
+        if !args.path().ends_with('/') {
+            return self.shard(args.path()).delete(args).await;
+        }
+
+        for res in join_all(self.shards.iter().map(|(_, acc)| acc.delete(args))).await {
+            res?;
+        }
+        Ok(())
+    }
+
+    async fn list(&self, args: &OpList) -> Result<DirStreamer> {
+        let futs = self.shards.iter().map(|(_, acc)| async move {
+            let ds = acc.list(args).await?;
+            ds.try_collect::<Vec<_>>().await
+        });
+
+        let entries = self.merge_entries(join_all(futs).await)?;
+        Ok(Box::new(stream::iter(entries.into_iter().map(Ok))))
+    }
+
+    fn presign(&self, args: &OpPresign) -> Result<PresignedRequest> {
+        self.shard(args.path()).presign(args)
+    }
+
+    async fn create_multipart(&self, args: &OpCreateMultipart) -> Result<String> {
+        self.shard(args.path()).create_multipart(args).await
+    }
+
+    async fn write_multipart(&self, args: &OpWriteMultipart, r: BytesReader) -> Result<ObjectPart> {
+        self.shard(args.path()).write_multipart(args, r).await
+    }
+
+    async fn complete_multipart(&self, args: &OpCompleteMultipart) -> Result<()> {
+        self.shard(args.path()).complete_multipart(args).await
+    }
+
+    async fn abort_multipart(&self, args: &OpAbortMultipart) -> Result<()> {
+        self.shard(args.path()).abort_multipart(args).await
+    }
+
+    fn blocking_create(&self, args: &OpCreate) -> Result<()> {
+        if args.mode().is_file() {
+            return self.shard(args.path()).blocking_create(args);
+        }
+
+        for (_, acc) in self.shards.iter() {
+            acc.blocking_create(args)?;
+        }
+        Ok(())
+    }
+
+    fn blocking_read(&self, args: &OpRead) -> Result<BlockingBytesReader> {
+        self.shard(args.path()).blocking_read(args)
+    }
+
+    fn blocking_write(&self, args: &OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.shard(args.path()).blocking_write(args, r)
+    }
+
+    fn blocking_stat(&self, args: &OpStat) -> Result<ObjectMetadata> {
+        if !args.path().ends_with('/') {
+            return self.shard(args.path()).blocking_stat(args);
+        }
+
+        let mut last_err = None;
+        for (_, acc) in self.shards.iter() {
+            match acc.blocking_stat(args) {
+                Ok(meta) => return Ok(meta),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.expect("error must be set"))
+    }
+
+    fn blocking_delete(&self, args: &OpDelete) -> Result<()> {
+        if !args.path().ends_with('/') {
+            return self.shard(args.path()).blocking_delete(args);
+        }
+
+        for (_, acc) in self.shards.iter() {
+            acc.blocking_delete(args)?;
+        }
+        Ok(())
+    }
+
+    fn blocking_list(&self, args: &OpList) -> Result<DirIterator> {
+        let results = self
+            .shards
+            .iter()
+            .map(|(_, acc)| acc.blocking_list(args).and_then(|it| it.collect()))
+            .collect();
+
+        let entries = self.merge_entries(results)?;
+        Ok(Box::new(entries.into_iter().map(Ok)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::memory;
+
+    fn new_backend(names: &[&str]) -> Result<(Backend, Vec<Operator>)> {
+        let mut builder = Builder::default();
+        let mut ops = Vec::new();
+        for name in names {
+            let op = Operator::new(memory::Builder::default().build()?);
+            builder.shard(name, op.clone());
+            ops.push(op);
+        }
+        Ok((builder.build()?, ops))
+    }
+
+    #[test]
+    fn test_locate() -> Result<()> {
+        let (backend, _) = new_backend(&["a", "b", "c"])?;
+        let (larger, _) = new_backend(&["a", "b", "c", "d"])?;
+
+        let mut counts = [0; 3];
+        let mut moved = 0;
+        for i in 0..3000 {
+            let path = format!("dir/file-{i}");
+            let idx = backend.locate(&path);
+            counts[idx] += 1;
+
+            // Paths either stay or move to the new shard.
+            let new_idx = larger.locate(&path);
+            if new_idx != idx {
+                assert_eq!(new_idx, 3);
+                moved += 1;
+            }
+        }
+
+        for count in counts {
+            assert!(count > 800, "shards should be balanced: {counts:?}");
+        }
+        assert!(moved > 500 && moved < 1000, "moved: {moved}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shard() -> anyhow::Result<()> {
+        let (backend, ops) = new_backend(&["a", "b", "c"])?;
+        let op = Operator::new(backend.clone());
+
+        for i in 0..30 {
+            op.object(&format!("dir/file-{i:02}"))
+                .write(format!("{i}"))
+                .await?;
+        }
+        for (idx, o) in ops.iter().enumerate() {
+            let paths: Vec<String> = o
+                .object("dir/")
+                .list()
+                .await?
+                .map_ok(|de| de.path().to_string())
+                .try_collect()
+                .await?;
+            assert!(!paths.is_empty());
+            for path in paths {
+                assert_eq!(backend.locate(&path), idx);
+            }
+        }
+
+        let paths: Vec<String> = op
+            .object("dir/")
+            .list()
+            .await?
+            .map_ok(|de| de.path().to_string())
+            .try_collect()
+            .await?;
+        let expected: Vec<String> = (0..30).map(|i| format!("dir/file-{i:02}")).collect();
+        assert_eq!(paths, expected);
+        assert_eq!(op.object("dir/file-07").read().await?, b"7");
+
+        let paths: Vec<String> = op
+            .object("/")
+            .list()
+            .await?
+            .map_ok(|de| de.path().to_string())
+            .try_collect()
+            .await?;
+        assert_eq!(paths, vec!["dir/"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rebalance() -> anyhow::Result<()> {
+        let (backend, mut ops) = new_backend(&["a", "b"])?;
+        let op = Operator::new(backend);
+        for i in 0..30 {
+            op.object(&format!("file-{i}"))
+                .write(format!("{i}"))
+                .await?;
+        }
+
+        let extra = Operator::new(memory::Builder::default().build()?);
+        ops.push(extra.clone());
+        let mut builder = Builder::default();
+        for (name, o) in ["a", "b", "c"].iter().zip(ops.iter()) {
+            builder.shard(name, o.clone());
+        }
+        let backend = builder.build()?;
+
+        let moved = backend.rebalance("/").await?;
+        assert!(moved > 0);
+        assert_eq!(backend.rebalance("/").await?, 0);
+
+        let op = Operator::new(backend.clone());
+        for i in 0..30 {
+            let path = format!("file-{i}");
+            assert_eq!(op.object(&path).read().await?, format!("{i}").as_bytes());
+        }
+        let count = extra
+            .object("/")
+            .list()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .len();
+        assert_eq!(count, moved);
+
+        Ok(())
+    }
+}
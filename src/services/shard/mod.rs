@@ -0,0 +1,59 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shard service that spreads paths across several services.
+//!
+//! Every file path will be assigned to one shard by rendezvous hashing (a.k.a.
+//! highest random weight hashing), so adding or removing a shard only moves
+//! the paths that belong to it.
+//!
+//! # Behavior
+//!
+//! - File operations will be sent to the shard that path belongs to.
+//! - Dirs exist in all shards: `create` and `delete` will be applied to all
+//!   shards, `stat` will succeed if any shard has this dir.
+//! - `list` will merge entries from all shards in sorted order.
+//! - Paths could be moved to their new shards by [`Backend::rebalance`]
+//!   after shards changed.
+//!
+//! # Configuration
+//!
+//! Shard can't be built from env, please use [`Builder`] instead.
+//!
+//! # Example
+//!
+//! ```
+//! use anyhow::Result;
+//! use opendal::services::memory;
+//! use opendal::services::shard;
+//! use opendal::Operator;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<()> {
+//!     let mut builder = shard::Builder::default();
+//!     for name in ["shard-0", "shard-1", "shard-2"] {
+//!         builder.shard(name, Operator::new(memory::Builder::default().build()?));
+//!     }
+//!     let op = Operator::new(builder.build()?);
+//!
+//!     op.object("dir/file").write("Hello, World!").await?;
+//!     let _ = op.object("dir/").list().await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+mod backend;
+pub use backend::Backend;
+pub use backend::Builder;
@@ -0,0 +1,104 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::ErrorKind;
+use std::io::Result;
+
+use opendal::Operator;
+
+use super::utils::*;
+
+/// Test services that meet the following capability:
+///
+/// - can_read
+/// - can_write
+/// - can_blocking
+/// - can_rename
+macro_rules! behavior_blocking_rename_test {
+    ($service:ident, $($(#[$meta:meta])* $test:ident),*,) => {
+        paste::item! {
+            mod [<services_ $service:lower _blocking_rename>] {
+                $(
+                    #[test]
+                    $(
+                        #[$meta]
+                    )*
+                    fn [< $test >]() -> std::io::Result<()> {
+                        let op = $crate::utils::init_service(opendal::Scheme::$service, true);
+                        match op {
+                            Some(op) if op.metadata().can_read()
+                                && op.metadata().can_write()
+                                && op.metadata().can_blocking()
+                                && op.metadata().can_rename() => $crate::blocking_rename::$test(op),
+                            Some(_) => {
+                                log::warn!("service {} doesn't support blocking rename, ignored", opendal::Scheme::$service);
+                                Ok(())
+                            },
+                            None => {
+                                log::warn!("service {} not initiated, ignored", opendal::Scheme::$service);
+                                Ok(())
+                            }
+                        }
+                    }
+                )*
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! behavior_blocking_rename_tests {
+     ($($service:ident),*) => {
+        $(
+            behavior_blocking_rename_test!(
+                $service,
+
+                test_rename_file,
+                test_rename_not_exist,
+            );
+        )*
+    };
+}
+
+/// Rename a file should move its content to the target.
+pub fn test_rename_file(op: Operator) -> Result<()> {
+    let from = uuid::Uuid::new_v4().to_string();
+    let to = format!("{}/{}", uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+    let (content, _) = gen_bytes();
+
+    op.object(&from).blocking_write(content.clone())?;
+    op.object(&from).blocking_rename(&to)?;
+
+    assert!(op.object(&from).blocking_metadata().is_err());
+    assert_eq!(op.object(&to).blocking_read()?, content);
+
+    op.object(&to)
+        .blocking_delete()
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Rename a not existing file should return NotFound.
+pub fn test_rename_not_exist(op: Operator) -> Result<()> {
+    let from = uuid::Uuid::new_v4().to_string();
+    let to = uuid::Uuid::new_v4().to_string();
+
+    let err = op
+        .object(&from)
+        .blocking_rename(&to)
+        .expect_err("rename must fail");
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    Ok(())
+}
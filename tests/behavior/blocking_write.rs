@@ -13,7 +13,10 @@
 // limitations under the License.
 
 use std::io;
+use std::io::Read;
 use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
 
 use log::debug;
 use opendal::ObjectMode;
@@ -80,6 +83,10 @@ macro_rules! behavior_blocking_write_tests {
                 test_stat_not_exist,
                 test_read_full,
                 test_read_range,
+                test_read_ranges,
+                test_read_seekable,
+                #[cfg(feature = "compress")]
+                test_read_decompress_gzip,
                 test_read_not_exist,
                 test_delete,
             );
@@ -324,6 +331,44 @@ pub fn test_read_full(op: Operator) -> Result<()> {
     Ok(())
 }
 
+// Read a compressed gzip file.
+#[cfg(feature = "compress")]
+pub fn test_read_decompress_gzip(op: Operator) -> Result<()> {
+    use futures::executor::block_on;
+    use futures::AsyncReadExt;
+    use opendal::io_util::CompressAlgorithm;
+    use opendal::io_util::CompressReader;
+
+    let path = format!("{}.gz", uuid::Uuid::new_v4());
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    let mut encoder = CompressReader::new(
+        futures::io::Cursor::new(content.clone()),
+        CompressAlgorithm::Gzip,
+    );
+    let mut compressed_content = vec![];
+    block_on(encoder.read_to_end(&mut compressed_content))?;
+
+    op.object(&path).blocking_write(compressed_content)?;
+
+    let bs = op
+        .object(&path)
+        .blocking_decompress_read()?
+        .expect("decompress read must succeed");
+    assert_eq!(bs.len(), size, "read size");
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .blocking_delete()
+        .expect("delete must succeed");
+    Ok(())
+}
+
 /// Read range content should match.
 pub fn test_read_range(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
@@ -354,6 +399,73 @@ pub fn test_read_range(op: Operator) -> Result<()> {
     Ok(())
 }
 
+/// Read multiple ranges content should match.
+pub fn test_read_ranges(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let content = gen_fixed_bytes(4 * 1024 * 1024);
+
+    op.object(&path)
+        .blocking_write(content.clone())
+        .expect("write must succeed");
+
+    let ranges = vec![
+        3 * 1024 * 1024..3 * 1024 * 1024 + 10,
+        0..1024,
+        512..2048,
+        4096..4096,
+        2 * 1024 * 1024..4 * 1024 * 1024,
+    ];
+
+    let bss = op.object(&path).blocking_read_ranges(&ranges)?;
+    assert_eq!(bss.len(), ranges.len(), "read ranges count");
+    for (r, bs) in ranges.iter().zip(bss) {
+        assert_eq!(
+            bs,
+            content[r.start as usize..r.end as usize],
+            "read content"
+        );
+    }
+
+    op.object(&path)
+        .blocking_delete()
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Read with seekable reader should match.
+pub fn test_read_seekable(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+    let (offset, length) = gen_offset_length(size);
+
+    op.object(&path)
+        .blocking_write(content.clone())
+        .expect("write must succeed");
+
+    let mut r = op.object(&path).blocking_seekable_reader(..);
+    let n = r.seek(SeekFrom::End(0))?;
+    assert_eq!(n, size as u64, "seek to end");
+
+    r.seek(SeekFrom::Start(offset))?;
+    let mut bs = vec![0; length as usize];
+    r.read_exact(&mut bs)?;
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!(
+            "{:x}",
+            Sha256::digest(&content[offset as usize..(offset + length) as usize])
+        ),
+        "read content"
+    );
+
+    op.object(&path)
+        .blocking_delete()
+        .expect("delete must succeed");
+    Ok(())
+}
+
 /// Read not exist file should return NotFound
 pub fn test_read_not_exist(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
@@ -19,6 +19,8 @@ mod blocking_list;
 #[macro_use]
 mod blocking_read;
 #[macro_use]
+mod blocking_rename;
+#[macro_use]
 mod blocking_write;
 #[macro_use]
 mod list;
@@ -33,6 +35,8 @@ mod presign;
 #[macro_use]
 mod read_only;
 #[macro_use]
+mod rename;
+#[macro_use]
 mod write;
 
 mod utils;
@@ -63,6 +67,10 @@ macro_rules! behavior_tests {
             behavior_multipart_presign_tests!($service);
             // can_list && !can_write
             behavior_list_only_tests!($service);
+            // can_read && can_write && can_rename
+            behavior_rename_tests!($service);
+            // can_read && can_write && can_blocking && can_rename
+            behavior_blocking_rename_tests!($service);
         )*
     };
 }
@@ -0,0 +1,136 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::ErrorKind;
+use std::io::Result;
+
+use opendal::Operator;
+
+use super::utils::*;
+
+/// Test services that meet the following capability:
+///
+/// - can_read
+/// - can_write
+/// - can_rename
+macro_rules! behavior_rename_test {
+    ($service:ident, $($(#[$meta:meta])* $test:ident),*,) => {
+        paste::item! {
+            mod [<services_ $service:lower _rename>] {
+                $(
+                    #[tokio::test]
+                    $(
+                        #[$meta]
+                    )*
+                    async fn [< $test >]() -> std::io::Result<()> {
+                        let op = $crate::utils::init_service(opendal::Scheme::$service, true);
+                        match op {
+                            Some(op) if op.metadata().can_read()
+                                && op.metadata().can_write()
+                                && op.metadata().can_rename() => $crate::rename::$test(op).await,
+                            Some(_) => {
+                                log::warn!("service {} doesn't support rename, ignored", opendal::Scheme::$service);
+                                Ok(())
+                            },
+                            None => {
+                                log::warn!("service {} not initiated, ignored", opendal::Scheme::$service);
+                                Ok(())
+                            }
+                        }
+                    }
+                )*
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! behavior_rename_tests {
+     ($($service:ident),*) => {
+        $(
+            behavior_rename_test!(
+                $service,
+
+                test_rename_file,
+                test_rename_overwrite,
+                test_rename_not_exist,
+                test_rename_dir,
+            );
+        )*
+    };
+}
+
+/// Rename a file should move its content to the target.
+pub async fn test_rename_file(op: Operator) -> Result<()> {
+    let from = uuid::Uuid::new_v4().to_string();
+    let to = format!("{}/{}", uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+    let (content, _) = gen_bytes();
+
+    op.object(&from).write(content.clone()).await?;
+    op.object(&from).rename(&to).await?;
+
+    assert!(!op.object(&from).is_exist().await?);
+    assert_eq!(op.object(&to).read().await?, content);
+
+    op.object(&to).delete().await.expect("delete must succeed");
+    Ok(())
+}
+
+/// Rename to an existing file should overwrite it.
+pub async fn test_rename_overwrite(op: Operator) -> Result<()> {
+    let from = uuid::Uuid::new_v4().to_string();
+    let to = uuid::Uuid::new_v4().to_string();
+    let (content, _) = gen_bytes();
+
+    op.object(&from).write(content.clone()).await?;
+    op.object(&to).write("old").await?;
+    op.object(&from).rename(&to).await?;
+
+    assert!(!op.object(&from).is_exist().await?);
+    assert_eq!(op.object(&to).read().await?, content);
+
+    op.object(&to).delete().await.expect("delete must succeed");
+    Ok(())
+}
+
+/// Rename a not existing file should return NotFound.
+pub async fn test_rename_not_exist(op: Operator) -> Result<()> {
+    let from = uuid::Uuid::new_v4().to_string();
+    let to = uuid::Uuid::new_v4().to_string();
+
+    let err = op
+        .object(&from)
+        .rename(&to)
+        .await
+        .expect_err("rename must fail");
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(!op.object(&to).is_exist().await?);
+
+    Ok(())
+}
+
+/// Rename a dir should be rejected.
+pub async fn test_rename_dir(op: Operator) -> Result<()> {
+    let from = format!("{}/", uuid::Uuid::new_v4());
+    let to = uuid::Uuid::new_v4().to_string();
+
+    op.object(&from).create().await?;
+    assert!(op.object(&from).rename(&to).await.is_err());
+
+    op.object(&from)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
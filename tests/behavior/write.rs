@@ -15,7 +15,11 @@
 use std::io;
 use std::io::Result;
 
+use bytes::Bytes;
+use futures::TryStreamExt;
 use log::debug;
+use opendal::ObjectError;
+use opendal::ObjectErrorKind;
 use opendal::ObjectMode;
 use opendal::Operator;
 use sha2::Digest;
@@ -69,6 +73,7 @@ macro_rules! behavior_write_tests {
                 test_create_dir,
                 test_create_dir_exising,
                 test_write,
+                test_write_stream,
                 test_write_with_dir_path,
                 test_write_with_special_chars,
                 test_stat,
@@ -79,12 +84,18 @@ macro_rules! behavior_write_tests {
                 test_stat_root,
                 test_read_full,
                 test_read_range,
+                test_read_ranges,
+                test_read_parallel,
+                test_read_stream,
+                test_read_range_stream,
                 test_read_not_exist,
                 test_read_with_dir_path,
                 #[cfg(feature = "compress")]
                 test_read_decompress_gzip,
                 #[cfg(feature = "compress")]
                 test_read_decompress_zstd,
+                #[cfg(feature = "compress")]
+                test_write_compress_gzip,
                 test_read_with_special_chars,
                 test_delete,
                 test_delete_empty_dir,
@@ -213,6 +224,41 @@ pub async fn test_write(op: Operator) -> Result<()> {
     Ok(())
 }
 
+/// Write a single file from stream and test with stat.
+pub async fn test_write_stream(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    let chunks = content
+        .chunks(4 * 1024)
+        .map(|v| Ok(Bytes::copy_from_slice(v)))
+        .collect::<Vec<_>>();
+    op.object(&path)
+        .write_stream(size as u64, futures::stream::iter(chunks))
+        .await?;
+
+    let meta = op
+        .object(&path)
+        .metadata()
+        .await
+        .expect("stat must succeed");
+    assert_eq!(meta.content_length(), size as u64);
+
+    let bs = op.object(&path).read().await?;
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
 /// Write file with dir path should return an error
 pub async fn test_write_with_dir_path(op: Operator) -> Result<()> {
     let path = format!("{}/", uuid::Uuid::new_v4());
@@ -332,7 +378,12 @@ pub async fn test_stat_not_exist(op: Operator) -> Result<()> {
 
     let meta = op.object(&path).metadata().await;
     assert!(meta.is_err());
-    assert_eq!(meta.unwrap_err().kind(), io::ErrorKind::NotFound);
+    let err = meta.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(
+        ObjectError::downcast(&err).map(|e| e.kind()),
+        Some(ObjectErrorKind::ObjectNotFound)
+    );
 
     Ok(())
 }
@@ -404,13 +455,161 @@ pub async fn test_read_range(op: Operator) -> Result<()> {
     Ok(())
 }
 
+/// Read multiple ranges content should match.
+pub async fn test_read_ranges(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let content = gen_fixed_bytes(4 * 1024 * 1024);
+
+    op.object(&path)
+        .write(content.clone())
+        .await
+        .expect("write must succeed");
+
+    let ranges = vec![
+        3 * 1024 * 1024..3 * 1024 * 1024 + 10,
+        0..1024,
+        512..2048,
+        4096..4096,
+        2 * 1024 * 1024..4 * 1024 * 1024,
+    ];
+
+    // Merge all ranges.
+    let bss = op.object(&path).read_ranges(&ranges).await?;
+    assert_eq!(bss.len(), ranges.len(), "read ranges count");
+    for (r, bs) in ranges.iter().zip(bss) {
+        assert_eq!(
+            bs,
+            content[r.start as usize..r.end as usize],
+            "read content"
+        );
+    }
+
+    // Don't merge any ranges.
+    let bss = op.object(&path).read_ranges_with(&ranges, 0).await?;
+    assert_eq!(bss.len(), ranges.len(), "read ranges count");
+    for (r, bs) in ranges.iter().zip(bss) {
+        assert_eq!(
+            bs,
+            content[r.start as usize..r.end as usize],
+            "read content"
+        );
+    }
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Read with parallel reader should match.
+pub async fn test_read_parallel(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    op.object(&path)
+        .write(content.clone())
+        .await
+        .expect("write must succeed");
+
+    let r = op.object(&path).parallel_reader(256 * 1024, 4).await?;
+    let mut bs = futures::io::Cursor::new(Vec::with_capacity(size));
+    futures::io::copy(r, &mut bs).await?;
+    let bs = bs.into_inner();
+    assert_eq!(size, bs.len(), "read size");
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Read with stream should match.
+pub async fn test_read_stream(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    op.object(&path)
+        .write(content.clone())
+        .await
+        .expect("write must succeed");
+
+    let mut s = op.object(&path).stream().await?;
+    let mut bs = Vec::with_capacity(size);
+    while let Some(chunk) = s.try_next().await? {
+        bs.extend_from_slice(&chunk);
+    }
+    assert_eq!(size, bs.len(), "read size");
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Read range with stream should match.
+pub async fn test_read_range_stream(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+    let (offset, length) = gen_offset_length(size as usize);
+
+    op.object(&path)
+        .write(content.clone())
+        .await
+        .expect("write must succeed");
+
+    let mut s = op
+        .object(&path)
+        .range_stream(offset..offset + length)
+        .await?;
+    let mut bs = Vec::with_capacity(length as usize);
+    while let Some(chunk) = s.try_next().await? {
+        bs.extend_from_slice(&chunk);
+    }
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!(
+            "{:x}",
+            Sha256::digest(&content[offset as usize..(offset + length) as usize])
+        ),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
 /// Read not exist file should return NotFound
 pub async fn test_read_not_exist(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
 
     let bs = op.object(&path).read().await;
     assert!(bs.is_err());
-    assert_eq!(bs.unwrap_err().kind(), io::ErrorKind::NotFound);
+    let err = bs.unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(
+        ObjectError::downcast(&err).map(|e| e.kind()),
+        Some(ObjectErrorKind::ObjectNotFound)
+    );
 
     Ok(())
 }
@@ -504,6 +703,38 @@ pub async fn test_read_decompress_zstd(op: Operator) -> Result<()> {
     Ok(())
 }
 
+// Write a compressed gzip file.
+#[cfg(feature = "compress")]
+pub async fn test_write_compress_gzip(op: Operator) -> Result<()> {
+    use opendal::io_util::CompressAlgorithm;
+
+    let path = format!("{}.gz", uuid::Uuid::new_v4());
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    op.object(&path)
+        .compress_write_with(CompressAlgorithm::Gzip, content.clone())
+        .await?;
+
+    let bs = op
+        .object(&path)
+        .decompress_read()
+        .await?
+        .expect("decompress read must succeed");
+    assert_eq!(bs.len(), size, "read size");
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
 /// Read file with special chars should succeed.
 pub async fn test_read_with_special_chars(op: Operator) -> Result<()> {
     let path = format!("{} !@#$%^&*()_+-=;'><,?.txt", uuid::Uuid::new_v4());